@@ -0,0 +1,37 @@
+// Compare haversine and Vincenty distance formulas on a realistic 10k-point track
+// Run with: cargo bench --bench geometry
+
+use backend::track_utils::{haversine_distance, vincenty_distance};
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+fn synthetic_track(points: usize) -> Vec<(f64, f64)> {
+    // Winding route of roughly 100 km starting in the Alps
+    (0..points)
+        .map(|i| {
+            let t = i as f64;
+            (
+                46.0 + t * 0.00008 + (t / 150.0).sin() * 0.002,
+                7.0 + t * 0.00006 + (t / 90.0).cos() * 0.002,
+            )
+        })
+        .collect()
+}
+
+fn track_length_m(points: &[(f64, f64)], distance: fn((f64, f64), (f64, f64)) -> f64) -> f64 {
+    points.windows(2).map(|w| distance(w[0], w[1])).sum()
+}
+
+fn bench_distance_formulas(c: &mut Criterion) {
+    let track = synthetic_track(10_000);
+    let mut group = c.benchmark_group("track_length_10k");
+    group.bench_function("haversine", |b| {
+        b.iter(|| track_length_m(black_box(&track), haversine_distance))
+    });
+    group.bench_function("vincenty", |b| {
+        b.iter(|| track_length_m(black_box(&track), vincenty_distance))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_distance_formulas);
+criterion_main!(benches);
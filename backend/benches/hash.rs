@@ -0,0 +1,28 @@
+// Compare the duplicate-detection hash (xxh3_128) with the previous SHA-256 on a 20 MB file
+// Run with: cargo bench --bench hash
+
+use backend::track_utils::calculate_file_hash;
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use sha2::{Digest, Sha256};
+
+fn synthetic_gpx(size: usize) -> Vec<u8> {
+    let point = b"<trkpt lat=\"46.123456\" lon=\"7.654321\"><ele>1234.5</ele></trkpt>\n";
+    point.iter().copied().cycle().take(size).collect()
+}
+
+fn bench_file_hash(c: &mut Criterion) {
+    let data = synthetic_gpx(20 * 1024 * 1024);
+    let mut group = c.benchmark_group("file_hash_20mb");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.sample_size(20);
+    group.bench_function("sha256", |b| {
+        b.iter(|| format!("{:x}", Sha256::digest(black_box(&data))))
+    });
+    group.bench_function("xxh3_128", |b| {
+        b.iter(|| calculate_file_hash(black_box(&data)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_file_hash);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+-- Webhook subscriptions: URLs notified when a session's tracks change
+-- Payloads are signed with HMAC-SHA256 using the per-webhook secret
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id UUID PRIMARY KEY,
+    url TEXT NOT NULL CHECK (LENGTH(url) <= 2048),
+    session_id UUID NOT NULL,
+    events TEXT[] NOT NULL DEFAULT '{}',
+    secret TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_session_id_idx ON webhooks(session_id);
+
+COMMENT ON TABLE webhooks IS 'Registered webhook endpoints per session';
+COMMENT ON COLUMN webhooks.events IS 'Subscribed event types, e.g. track.uploaded, elevation.enriched';
+COMMENT ON COLUMN webhooks.secret IS 'Shared secret for the X-Trackly-Signature HMAC-SHA256 header';
@@ -0,0 +1,44 @@
+-- Hierarchical category taxonomy
+-- tracks.categories stays TEXT[]; its values are slugs from this table
+
+CREATE TABLE IF NOT EXISTS categories (
+    id SERIAL PRIMARY KEY,
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    display_name VARCHAR(100) NOT NULL,
+    parent_slug VARCHAR(100) REFERENCES categories(slug) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS categories_parent_slug_idx ON categories(parent_slug);
+
+COMMENT ON TABLE categories IS 'Track category taxonomy; submitting a child category also adds its parents';
+COMMENT ON COLUMN categories.parent_slug IS 'Slug of the parent category, NULL for top-level categories';
+
+-- Top-level categories (match the upload form options)
+INSERT INTO categories (slug, display_name, parent_slug) VALUES
+    ('hiking', 'Hiking', NULL),
+    ('running', 'Running', NULL),
+    ('walking', 'Walking', NULL),
+    ('cycling', 'Cycling', NULL),
+    ('skiing', 'Skiing', NULL),
+    ('other', 'Other', NULL)
+ON CONFLICT (slug) DO NOTHING;
+
+-- Subcategories, including every slug produced by the automatic track classifier
+INSERT INTO categories (slug, display_name, parent_slug) VALUES
+    ('trail_running', 'Trail running', 'running'),
+    ('road_running', 'Road running', 'running'),
+    ('long_run', 'Long run', 'running'),
+    ('marathon', 'Marathon', 'long_run'),
+    ('half_marathon', 'Half marathon', 'long_run'),
+    ('interval', 'Intervals', 'running'),
+    ('fartlek', 'Fartlek', 'running'),
+    ('tempo_run', 'Tempo run', 'running'),
+    ('aerobic_run', 'Aerobic run', 'running'),
+    ('recovery_run', 'Recovery run', 'running'),
+    ('nordic_walking', 'Nordic walking', 'walking'),
+    ('road_cycling', 'Road cycling', 'cycling'),
+    ('mountain_biking', 'Mountain biking', 'cycling'),
+    ('gravel', 'Gravel', 'cycling'),
+    ('cross_country_skiing', 'Cross-country skiing', 'skiing'),
+    ('ski_touring', 'Ski touring', 'skiing')
+ON CONFLICT (slug) DO NOTHING;
@@ -0,0 +1,4 @@
+-- Add moving distance (distance covered excluding pause gaps)
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS moving_distance_km DOUBLE PRECISION;
+
+COMMENT ON COLUMN tracks.moving_distance_km IS 'Distance in km excluding hops across time gaps longer than the pause threshold';
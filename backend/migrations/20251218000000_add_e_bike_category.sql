@@ -0,0 +1,4 @@
+-- E-bike rides detected by the automatic track classifier
+INSERT INTO categories (slug, display_name, parent_slug) VALUES
+    ('e_bike', 'E-bike', 'cycling')
+ON CONFLICT (slug) DO NOTHING;
@@ -0,0 +1,7 @@
+-- Record where elevation values come from so slope smoothing can adapt to the data quality
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS elevation_source TEXT;
+
+COMMENT ON COLUMN tracks.elevation_source IS 'Origin of elevation data: gps, barometric or enriched (DEM)';
+
+-- Tracks enriched before this column existed
+UPDATE tracks SET elevation_source = 'enriched' WHERE elevation_enriched = TRUE AND elevation_source IS NULL;
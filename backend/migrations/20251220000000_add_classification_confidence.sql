@@ -0,0 +1,4 @@
+-- Confidence (0.0-1.0) of each automatic classification label
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS classification_confidence JSONB;
+
+COMMENT ON COLUMN tracks.classification_confidence IS 'Map of auto classification label to confidence score; NULL when labels were set manually';
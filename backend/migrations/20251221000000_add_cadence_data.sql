@@ -0,0 +1,4 @@
+-- Per-point cadence (rpm / spm) from <gpxtpx:cad>
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS cadence_data JSONB;
+
+COMMENT ON COLUMN tracks.cadence_data IS 'Array of cadence values for each track point, as recorded by the device';
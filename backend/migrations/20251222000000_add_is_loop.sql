@@ -0,0 +1,12 @@
+-- Whether a track starts and ends near the same point
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS is_loop BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Backfill existing tracks with the default 100 m threshold
+UPDATE tracks
+SET is_loop = ST_NPoints(geom) > 2
+    AND ST_Distance(
+        ST_StartPoint(ST_GeometryN(geom, 1))::geography,
+        ST_EndPoint(ST_GeometryN(geom, ST_NumGeometries(geom)))::geography
+    ) < 100;
+
+COMMENT ON COLUMN tracks.is_loop IS 'True when the first and last points are within TRACK_LOOP_THRESHOLD_M (default 100 m)';
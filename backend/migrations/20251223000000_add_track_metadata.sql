@@ -0,0 +1,8 @@
+-- Provenance copied from the GPX <metadata> element
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS metadata_author TEXT;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS metadata_link TEXT;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS metadata_copyright TEXT;
+
+COMMENT ON COLUMN tracks.metadata_author IS 'GPX <metadata><author><name>';
+COMMENT ON COLUMN tracks.metadata_link IS 'GPX <metadata><link href>, http(s) only';
+COMMENT ON COLUMN tracks.metadata_copyright IS 'GPX <metadata><copyright author>';
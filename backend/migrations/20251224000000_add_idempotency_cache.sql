@@ -0,0 +1,9 @@
+-- Responses remembered per Idempotency-Key so client retries are not processed twice
+CREATE TABLE IF NOT EXISTS idempotency_cache (
+    key TEXT PRIMARY KEY,
+    response_body JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_cache_expires_at ON idempotency_cache (expires_at);
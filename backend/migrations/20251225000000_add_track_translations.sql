@@ -0,0 +1,10 @@
+-- Per-language names and descriptions, served on GET /tracks/{id} via Accept-Language
+CREATE TABLE IF NOT EXISTS track_translations (
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    language_code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (track_id, language_code)
+);
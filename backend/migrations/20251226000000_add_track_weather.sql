@@ -0,0 +1,9 @@
+-- Historical weather at a track's start, cached because past conditions do not change
+CREATE TABLE IF NOT EXISTS track_weather (
+    track_id UUID PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    temperature_c DOUBLE PRECISION NOT NULL,
+    wind_speed_kmh DOUBLE PRECISION NOT NULL,
+    precipitation_mm DOUBLE PRECISION NOT NULL,
+    weather_code INTEGER NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,6 @@
+-- Rendered 256x256 PNG previews of tracks on a map tile background
+CREATE TABLE IF NOT EXISTS track_thumbnails (
+    track_id UUID PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    png BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
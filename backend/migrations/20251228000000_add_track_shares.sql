@@ -0,0 +1,9 @@
+-- Expiring links that let anyone holding the token view a track, even a private one
+CREATE TABLE IF NOT EXISTS track_shares (
+    token TEXT PRIMARY KEY,
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_track_shares_expires_at ON track_shares (expires_at);
@@ -0,0 +1,4 @@
+-- Aggregate cadence (rpm / steps per minute) from the per-point cadence_data
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS cadence_avg INTEGER;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS cadence_min INTEGER;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS cadence_max INTEGER;
@@ -0,0 +1,5 @@
+-- Power meter data (watts) for cycling tracks
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS power_data JSONB;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS power_avg INTEGER;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS power_max INTEGER;
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS power_normalized INTEGER;
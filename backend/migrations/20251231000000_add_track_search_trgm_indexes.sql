@@ -0,0 +1,5 @@
+-- Trigram indexes for typo-tolerant track search
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS tracks_name_trgm_idx ON tracks USING GIN(name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS tracks_description_trgm_idx ON tracks USING GIN(description gin_trgm_ops);
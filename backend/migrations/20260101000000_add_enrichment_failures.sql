@@ -0,0 +1,7 @@
+-- Background elevation enrichments that failed, kept so they can be retried
+CREATE TABLE IF NOT EXISTS enrichment_failures (
+    track_id UUID PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    error_message TEXT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,3 @@
+-- A key without a response marks a request still being processed, so concurrent
+-- retries with the same key are rejected instead of uploading twice
+ALTER TABLE idempotency_cache ALTER COLUMN response_body DROP NOT NULL;
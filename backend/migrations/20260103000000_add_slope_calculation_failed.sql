@@ -0,0 +1,2 @@
+-- Set when slopes could not be calculated from enriched elevation, so readers stop waiting
+ALTER TABLE tracks ADD COLUMN IF NOT EXISTS slope_calculation_failed BOOLEAN NOT NULL DEFAULT FALSE;
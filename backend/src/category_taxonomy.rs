@@ -0,0 +1,116 @@
+// Category taxonomy helpers
+// Categories form a tree (e.g. trail_running -> running); tracks store a flat slug list
+
+use crate::models::{Category, CategoryNode};
+use std::collections::HashMap;
+
+/// Build the nested category tree from the flat taxonomy, preserving input order
+pub fn build_category_tree(categories: &[Category]) -> Vec<CategoryNode> {
+    let mut children: HashMap<Option<&str>, Vec<&Category>> = HashMap::new();
+    for category in categories {
+        children
+            .entry(category.parent_slug.as_deref())
+            .or_default()
+            .push(category);
+    }
+
+    fn build(
+        parent: Option<&str>,
+        children: &HashMap<Option<&str>, Vec<&Category>>,
+    ) -> Vec<CategoryNode> {
+        children
+            .get(&parent)
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|c| CategoryNode {
+                        slug: c.slug.clone(),
+                        display_name: c.display_name.clone(),
+                        children: build(Some(&c.slug), children),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    build(None, &children)
+}
+
+/// Validate submitted slugs and add every ancestor of each one.
+/// Returns the first unknown slug as the error.
+pub fn expand_with_parents(
+    categories: &[Category],
+    submitted: &[String],
+) -> Result<Vec<String>, String> {
+    let parents: HashMap<&str, Option<&str>> = categories
+        .iter()
+        .map(|c| (c.slug.as_str(), c.parent_slug.as_deref()))
+        .collect();
+
+    let mut result: Vec<String> = Vec::new();
+    for slug in submitted {
+        if !parents.contains_key(slug.as_str()) {
+            return Err(slug.clone());
+        }
+        let mut current = Some(slug.as_str());
+        // Bounded walk guards against accidental cycles in the taxonomy
+        for _ in 0..=categories.len() {
+            let Some(s) = current else { break };
+            if !result.iter().any(|r| r == s) {
+                result.push(s.to_string());
+            }
+            current = parents.get(s).copied().flatten();
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn taxonomy() -> Vec<Category> {
+        let c = |slug: &str, parent: Option<&str>| Category {
+            slug: slug.to_string(),
+            display_name: slug.to_string(),
+            parent_slug: parent.map(str::to_string),
+        };
+        vec![
+            c("running", None),
+            c("cycling", None),
+            c("trail_running", Some("running")),
+            c("long_run", Some("running")),
+            c("marathon", Some("long_run")),
+        ]
+    }
+
+    #[test]
+    fn expand_adds_all_ancestors_once() {
+        let expanded = expand_with_parents(
+            &taxonomy(),
+            &["trail_running".to_string(), "marathon".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            vec!["trail_running", "running", "marathon", "long_run"]
+        );
+    }
+
+    #[test]
+    fn expand_rejects_unknown_slug() {
+        let result = expand_with_parents(&taxonomy(), &["parkour".to_string()]);
+        assert_eq!(result, Err("parkour".to_string()));
+    }
+
+    #[test]
+    fn tree_nests_children_under_parents() {
+        let tree = build_category_tree(&taxonomy());
+        assert_eq!(tree.len(), 2);
+        let running = &tree[0];
+        assert_eq!(running.slug, "running");
+        assert_eq!(running.children.len(), 2);
+        assert_eq!(running.children[1].children[0].slug, "marathon");
+        assert!(tree[1].children.is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+//! Response compression for large JSON, GPX and KML payloads.
+
+use axum::http::{Extensions, HeaderMap, StatusCode, Version, header::CONTENT_TYPE};
+use tower_http::compression::{
+    CompressionLayer, Predicate,
+    predicate::{And, DefaultPredicate},
+};
+
+/// Content types worth compressing; everything else (e.g. images) is passed through
+const COMPRESSIBLE_CONTENT_TYPES: [&str; 3] = [
+    "application/json",
+    "application/gpx+xml",
+    "application/vnd.google-earth.kml+xml",
+];
+
+type ContentTypePredicate = fn(StatusCode, Version, &HeaderMap, &Extensions) -> bool;
+
+pub type ResponseCompressionLayer = CompressionLayer<And<DefaultPredicate, ContentTypePredicate>>;
+
+fn is_compressible_content_type(
+    _status: StatusCode,
+    _version: Version,
+    headers: &HeaderMap,
+    _extensions: &Extensions,
+) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| COMPRESSIBLE_CONTENT_TYPES.iter().any(|t| ct.starts_with(t)))
+}
+
+/// gzip/brotli compression negotiated via `Accept-Encoding`.
+/// `DISABLE_COMPRESSION=true` turns it off, e.g. when a reverse proxy already compresses.
+pub fn layer() -> ResponseCompressionLayer {
+    let enabled = !std::env::var("DISABLE_COMPRESSION")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false);
+
+    CompressionLayer::new()
+        .gzip(enabled)
+        .br(enabled)
+        .compress_when(
+            DefaultPredicate::new().and(is_compressible_content_type as ContentTypePredicate),
+        )
+}
@@ -0,0 +1,146 @@
+//! Cross-origin access for browsers loading the frontend from another origin.
+
+use crate::request_tracing::TRACE_ID_HEADER;
+use crate::services::rate_limiter::{RATE_LIMIT_REMAINING_HEADER, RATE_LIMIT_RESET_HEADER};
+use axum::http::{
+    HeaderName, HeaderValue, Method,
+    header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER},
+};
+use std::fmt;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::warn;
+
+/// How long browsers may cache a preflight response
+const PREFLIGHT_MAX_AGE: Duration = Duration::from_secs(3600);
+
+/// Origins allowed to call the API, from `CORS_ALLOW_ORIGIN`
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorsPolicy {
+    /// No `Access-Control-Allow-Origin` header, so browsers only allow same-origin calls
+    SameOrigin,
+    /// `*`
+    Any,
+    /// Comma-separated list of exact origins
+    Origins(Vec<HeaderValue>),
+}
+
+impl CorsPolicy {
+    pub fn from_env() -> Self {
+        Self::parse(std::env::var("CORS_ALLOW_ORIGIN").ok().as_deref())
+    }
+
+    /// Unset, empty and `null` all mean same-origin only; unparsable origins are skipped
+    fn parse(value: Option<&str>) -> Self {
+        let value = value.map(str::trim).unwrap_or_default();
+        if value.is_empty() || value.eq_ignore_ascii_case("null") {
+            return CorsPolicy::SameOrigin;
+        }
+        // A wildcard anywhere in the list wins; tower-http rejects `*` inside an origin list
+        if value.split(',').any(|origin| origin.trim() == "*") {
+            return CorsPolicy::Any;
+        }
+        let origins: Vec<HeaderValue> = value
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/'))
+            .filter(|origin| !origin.is_empty())
+            .filter_map(|origin| match HeaderValue::from_str(origin) {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    warn!(origin, "ignoring invalid CORS_ALLOW_ORIGIN entry");
+                    None
+                }
+            })
+            .collect();
+        if origins.is_empty() {
+            CorsPolicy::SameOrigin
+        } else {
+            CorsPolicy::Origins(origins)
+        }
+    }
+}
+
+impl fmt::Display for CorsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorsPolicy::SameOrigin => write!(f, "same-origin only"),
+            CorsPolicy::Any => write!(f, "any origin"),
+            CorsPolicy::Origins(origins) => {
+                let list: Vec<&str> = origins.iter().filter_map(|o| o.to_str().ok()).collect();
+                write!(f, "origins [{}]", list.join(", "))
+            }
+        }
+    }
+}
+
+/// Answers `OPTIONS` preflights and adds CORS headers to responses for allowed origins
+pub fn layer(policy: &CorsPolicy) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            CONTENT_TYPE,
+            IF_NONE_MATCH,
+            HeaderName::from_static("x-session-id"),
+            HeaderName::from_static("idempotency-key"),
+        ])
+        .expose_headers([
+            ETAG,
+            RETRY_AFTER,
+            TRACE_ID_HEADER,
+            RATE_LIMIT_REMAINING_HEADER,
+            RATE_LIMIT_RESET_HEADER,
+        ])
+        .max_age(PREFLIGHT_MAX_AGE);
+
+    match policy {
+        CorsPolicy::SameOrigin => layer,
+        CorsPolicy::Any => layer.allow_origin(AllowOrigin::any()),
+        CorsPolicy::Origins(origins) => layer.allow_origin(AllowOrigin::list(origins.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_empty_and_null_mean_same_origin() {
+        assert_eq!(CorsPolicy::parse(None), CorsPolicy::SameOrigin);
+        assert_eq!(CorsPolicy::parse(Some("  ")), CorsPolicy::SameOrigin);
+        assert_eq!(CorsPolicy::parse(Some("null")), CorsPolicy::SameOrigin);
+    }
+
+    #[test]
+    fn parses_wildcard_and_origin_list() {
+        assert_eq!(CorsPolicy::parse(Some("*")), CorsPolicy::Any);
+        assert_eq!(
+            CorsPolicy::parse(Some("https://a.example, *")),
+            CorsPolicy::Any
+        );
+        assert_eq!(
+            CorsPolicy::parse(Some("https://a.example, https://b.example/,")),
+            CorsPolicy::Origins(vec![
+                HeaderValue::from_static("https://a.example"),
+                HeaderValue::from_static("https://b.example"),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_origins_are_skipped() {
+        assert_eq!(
+            CorsPolicy::parse(Some("https://ok.example,bad\norigin")),
+            CorsPolicy::Origins(vec![HeaderValue::from_static("https://ok.example")])
+        );
+        assert_eq!(
+            CorsPolicy::parse(Some("bad\norigin")),
+            CorsPolicy::SameOrigin
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::ApiUsageStat;
 use sqlx::PgPool;
 
 /// Record API usage for elevation service
@@ -59,8 +60,8 @@ pub async fn get_api_usage_stats(
     pool: &PgPool,
     service_name: &str,
     days: i32,
-) -> Result<Vec<(chrono::NaiveDate, i32)>, sqlx::Error> {
-    let result = sqlx::query_as(
+) -> Result<Vec<ApiUsageStat>, sqlx::Error> {
+    let result = sqlx::query_as::<_, ApiUsageStat>(
         r#"
         SELECT date, api_calls_count
         FROM elevation_api_usage
@@ -0,0 +1,29 @@
+use crate::metrics;
+use crate::models::Category;
+use sqlx::{PgPool, Row};
+use std::time::Instant;
+
+/// Load the full category taxonomy (flat, parents referenced by slug)
+pub async fn list_categories(pool: &PgPool) -> Result<Vec<Category>, sqlx::Error> {
+    let start = Instant::now();
+    let rows = sqlx::query(
+        r#"
+        SELECT slug, display_name, parent_slug
+        FROM categories
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    metrics::observe_db_query("list_categories", start.elapsed().as_secs_f64());
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(Category {
+                slug: row.try_get("slug")?,
+                display_name: row.try_get("display_name")?,
+                parent_slug: row.try_get("parent_slug")?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,157 @@
+use crate::metrics;
+use crate::models::EnrichmentFailure;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Record a failed enrichment attempt; a repeated failure bumps `retry_count`
+pub async fn record_enrichment_failure(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+    error_message: &str,
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    sqlx::query(
+        r#"
+        INSERT INTO enrichment_failures (track_id, attempted_at, error_message)
+        VALUES ($1, NOW(), $2)
+        ON CONFLICT (track_id) DO UPDATE
+        SET attempted_at = NOW(),
+            error_message = EXCLUDED.error_message,
+            retry_count = enrichment_failures.retry_count + 1
+        "#,
+    )
+    .bind(track_id)
+    .bind(error_message)
+    .execute(&**pool)
+    .await?;
+    metrics::observe_db_query("record_enrichment_failure", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Forget the failure of a track once an enrichment has succeeded
+pub async fn clear_enrichment_failure(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    sqlx::query("DELETE FROM enrichment_failures WHERE track_id = $1")
+        .bind(track_id)
+        .execute(&**pool)
+        .await?;
+    metrics::observe_db_query("clear_enrichment_failure", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Recorded failures, oldest attempt first
+pub async fn list_enrichment_failures(
+    pool: &Arc<PgPool>,
+) -> Result<Vec<EnrichmentFailure>, sqlx::Error> {
+    let start = Instant::now();
+    let failures = sqlx::query_as::<_, EnrichmentFailure>(
+        r#"
+        SELECT track_id, attempted_at, error_message, retry_count
+        FROM enrichment_failures
+        ORDER BY attempted_at
+        "#,
+    )
+    .fetch_all(&**pool)
+    .await?;
+    metrics::observe_db_query("list_enrichment_failures", start.elapsed().as_secs_f64());
+    Ok(failures)
+}
+
+/// Failure record of one track, if its last enrichment failed
+pub async fn get_enrichment_failure(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Option<EnrichmentFailure>, sqlx::Error> {
+    let start = Instant::now();
+    let failure = sqlx::query_as::<_, EnrichmentFailure>(
+        r#"
+        SELECT track_id, attempted_at, error_message, retry_count
+        FROM enrichment_failures
+        WHERE track_id = $1
+        "#,
+    )
+    .bind(track_id)
+    .fetch_optional(&**pool)
+    .await?;
+    metrics::observe_db_query("get_enrichment_failure", start.elapsed().as_secs_f64());
+    Ok(failure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn failure_is_recorded_bumped_and_cleared() {
+        use sqlx::postgres::PgPoolOptions;
+
+        let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+        let pool = Arc::new(
+            PgPoolOptions::new()
+                .max_connections(1)
+                .connect(&db_url)
+                .await
+                .unwrap(),
+        );
+        let track_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash)
+            VALUES ($1, 'Enrichment failure test', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(10.6 51.8, 10.61 51.81)', 4326)), 1.3, $2)
+            "#,
+        )
+        .bind(track_id)
+        .bind(format!("enrichment-failure-{track_id}"))
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        record_enrichment_failure(&pool, track_id, "elevation API: timeout")
+            .await
+            .unwrap();
+        let failure = get_enrichment_failure(&pool, track_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failure.retry_count, 0);
+        assert_eq!(failure.error_message, "elevation API: timeout");
+
+        record_enrichment_failure(&pool, track_id, "persist slope: deadlock")
+            .await
+            .unwrap();
+        let failure = get_enrichment_failure(&pool, track_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failure.retry_count, 1);
+        assert_eq!(failure.error_message, "persist slope: deadlock");
+        assert!(
+            list_enrichment_failures(&pool)
+                .await
+                .unwrap()
+                .iter()
+                .any(|f| f.track_id == track_id)
+        );
+
+        clear_enrichment_failure(&pool, track_id).await.unwrap();
+        assert!(
+            get_enrichment_failure(&pool, track_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        sqlx::query("DELETE FROM tracks WHERE id = $1")
+            .bind(track_id)
+            .execute(&*pool)
+            .await
+            .unwrap();
+    }
+}
@@ -2,17 +2,65 @@
 // Split into focused submodules for better maintainability
 
 mod api_usage;
+mod categories;
+mod enrichment_failures;
+mod shares;
+mod thumbnails;
 mod tracks;
+mod translations;
+mod weather;
+mod webhooks;
 
 // Re-export API usage functions
 pub use api_usage::{
     get_api_usage_stats, get_today_api_usage, is_daily_limit_exceeded, record_api_usage,
 };
 
+// Re-export category taxonomy functions
+pub use categories::list_categories;
+
+/// PostgreSQL `unique_violation`, e.g. two concurrent uploads of the same file racing past
+/// the duplicate check into the unique index on `tracks.hash`
+pub fn is_unique_violation(err: &sqlx::Error) -> bool {
+    matches!(err, sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505"))
+}
+
 // Re-export track-related functions and types
 pub use tracks::{
-    InsertTrackParams, UpdateElevationParams, UpdateSlopeParams, delete_track, get_track_by_id,
-    get_track_detail, get_track_detail_adaptive, insert_track, list_public_tracks_for_sitemap,
-    list_tracks, list_tracks_geojson, search_tracks, track_exists, update_track_categories,
+    ExistingTrack, IdempotencyClaim, InsertTrackParams, TrackPaceSeries, UpdateElevationParams,
+    UpdateSlopeParams, bulk_delete_tracks, claim_idempotency_key, clone_track, delete_track,
+    find_geometrically_similar_tracks, get_comparable_stats, get_track_by_id, get_track_detail,
+    get_track_detail_adaptive, get_track_elevation_profile, get_track_heatmap,
+    get_track_pace_series, get_track_power_series, get_track_stats, insert_track, list_my_tracks,
+    list_public_tracks_for_sitemap, list_tracks, list_tracks_geojson,
+    mark_slope_calculation_failed, release_idempotency_key, search_tracks,
+    slope_calculation_failed, store_idempotency_response, track_exists, track_exists_with_metadata,
+    track_id_exists, update_track_auto_classifications, update_track_categories,
     update_track_description, update_track_elevation, update_track_name, update_track_slope,
+    update_track_visibility,
+};
+
+// Re-export enrichment failure log functions
+pub use enrichment_failures::{
+    clear_enrichment_failure, get_enrichment_failure, list_enrichment_failures,
+    record_enrichment_failure,
 };
+
+// Re-export track share link functions
+pub use shares::{create_track_share, delete_expired_track_shares, get_shared_track_id};
+
+// Re-export track thumbnail cache functions
+pub use thumbnails::{TrackThumbnail, get_track_thumbnail, store_track_thumbnail};
+
+// Re-export track translation functions
+pub use translations::{
+    delete_track_translation, list_track_translations, upsert_track_translation,
+};
+
+// Re-export track weather cache functions
+pub use weather::{
+    TrackWeatherLocation, get_cached_track_weather, get_track_weather_location, store_track_weather,
+};
+
+// Re-export webhook functions
+pub use webhooks::{create_webhook, delete_webhook, find_webhooks_for_track_event, list_webhooks};
@@ -0,0 +1,56 @@
+use crate::metrics;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Store a share token valid for `expires_in_hours`; returns when it expires
+pub async fn create_track_share(
+    pool: &Arc<PgPool>,
+    token: &str,
+    track_id: Uuid,
+    expires_in_hours: i32,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    let start = Instant::now();
+    let expires_at = sqlx::query_scalar(
+        r#"
+        INSERT INTO track_shares (token, track_id, expires_at)
+        VALUES ($1, $2, NOW() + make_interval(hours => $3))
+        RETURNING expires_at
+        "#,
+    )
+    .bind(token)
+    .bind(track_id)
+    .bind(expires_in_hours)
+    .fetch_one(&**pool)
+    .await?;
+    metrics::observe_db_query("create_track_share", start.elapsed().as_secs_f64());
+    Ok(expires_at)
+}
+
+/// Track a share token grants access to; `None` for unknown or expired tokens
+pub async fn get_shared_track_id(
+    pool: &Arc<PgPool>,
+    token: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let start = Instant::now();
+    let track_id = sqlx::query_scalar(
+        "SELECT track_id FROM track_shares WHERE token = $1 AND expires_at > NOW()",
+    )
+    .bind(token)
+    .fetch_optional(&**pool)
+    .await?;
+    metrics::observe_db_query("get_shared_track_id", start.elapsed().as_secs_f64());
+    Ok(track_id)
+}
+
+/// Remove expired share tokens; returns the number of rows removed
+pub async fn delete_expired_track_shares(pool: &Arc<PgPool>) -> Result<u64, sqlx::Error> {
+    let start = Instant::now();
+    let result = sqlx::query("DELETE FROM track_shares WHERE expires_at <= NOW()")
+        .execute(&**pool)
+        .await?;
+    metrics::observe_db_query("delete_expired_track_shares", start.elapsed().as_secs_f64());
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,61 @@
+use crate::metrics;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Cached thumbnail with the owning track's visibility
+pub struct TrackThumbnail {
+    pub png: Vec<u8>,
+    pub is_public: bool,
+    pub session_id: Option<Uuid>,
+}
+
+pub async fn store_track_thumbnail(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+    png: &[u8],
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    sqlx::query(
+        r#"
+        INSERT INTO track_thumbnails (track_id, png)
+        VALUES ($1, $2)
+        ON CONFLICT (track_id) DO UPDATE SET png = EXCLUDED.png, created_at = NOW()
+        "#,
+    )
+    .bind(track_id)
+    .bind(png)
+    .execute(&**pool)
+    .await?;
+    metrics::observe_db_query("store_track_thumbnail", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+pub async fn get_track_thumbnail(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Option<TrackThumbnail>, sqlx::Error> {
+    let start = Instant::now();
+    let row = sqlx::query(
+        r#"
+        SELECT th.png, t.is_public, t.session_id
+        FROM track_thumbnails th
+        JOIN tracks t ON t.id = th.track_id
+        WHERE th.track_id = $1
+        "#,
+    )
+    .bind(track_id)
+    .fetch_optional(&**pool)
+    .await?;
+    metrics::observe_db_query("get_track_thumbnail", start.elapsed().as_secs_f64());
+
+    row.map(|row| {
+        Ok(TrackThumbnail {
+            png: row.try_get("png")?,
+            is_public: row.try_get::<Option<bool>, _>("is_public")?.unwrap_or(true),
+            session_id: row.try_get("session_id")?,
+        })
+    })
+    .transpose()
+}
@@ -0,0 +1,80 @@
+use crate::metrics;
+use crate::models::TrackTranslation;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Create or replace the translation of a track for one language
+pub async fn upsert_track_translation(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+    language_code: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    let description = description.map(|raw| ammonia::clean(raw).to_string());
+    sqlx::query(
+        r#"
+        INSERT INTO track_translations (track_id, language_code, name, description)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (track_id, language_code)
+        DO UPDATE SET name = EXCLUDED.name, description = EXCLUDED.description, updated_at = NOW()
+        "#,
+    )
+    .bind(track_id)
+    .bind(language_code)
+    .bind(name)
+    .bind(description)
+    .execute(&**pool)
+    .await?;
+    metrics::observe_db_query("upsert_track_translation", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+pub async fn list_track_translations(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Vec<TrackTranslation>, sqlx::Error> {
+    let start = Instant::now();
+    let rows = sqlx::query(
+        r#"
+        SELECT language_code, name, description
+        FROM track_translations
+        WHERE track_id = $1
+        ORDER BY language_code
+        "#,
+    )
+    .bind(track_id)
+    .fetch_all(&**pool)
+    .await?;
+    metrics::observe_db_query("list_track_translations", start.elapsed().as_secs_f64());
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(TrackTranslation {
+                language_code: row.try_get("language_code")?,
+                name: row.try_get("name")?,
+                description: row.try_get("description")?,
+            })
+        })
+        .collect()
+}
+
+/// Delete one translation; returns the number of rows removed
+pub async fn delete_track_translation(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+    language_code: &str,
+) -> Result<u64, sqlx::Error> {
+    let start = Instant::now();
+    let result =
+        sqlx::query("DELETE FROM track_translations WHERE track_id = $1 AND language_code = $2")
+            .bind(track_id)
+            .bind(language_code)
+            .execute(&**pool)
+            .await?;
+    metrics::observe_db_query("delete_track_translation", start.elapsed().as_secs_f64());
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,109 @@
+use crate::metrics;
+use crate::models::WeatherObservation;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Where and when a track was recorded, plus what is needed for the visibility check
+pub struct TrackWeatherLocation {
+    pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub lat: f64,
+    pub lon: f64,
+    pub is_public: bool,
+    pub session_id: Option<Uuid>,
+}
+
+/// Recording time and geometric midpoint of a track
+pub async fn get_track_weather_location(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Option<TrackWeatherLocation>, sqlx::Error> {
+    let start = Instant::now();
+    let row = sqlx::query(
+        r#"
+        SELECT recorded_at, is_public, session_id, ST_Y(mid) AS lat, ST_X(mid) AS lon
+        FROM (
+            SELECT recorded_at, is_public, session_id,
+                CASE WHEN GeometryType(geom) = 'LINESTRING'
+                    THEN ST_LineInterpolatePoint(geom, 0.5)
+                    ELSE ST_Centroid(geom)
+                END AS mid
+            FROM tracks
+            WHERE id = $1
+        ) t
+        "#,
+    )
+    .bind(track_id)
+    .fetch_optional(&**pool)
+    .await?;
+    metrics::observe_db_query("get_track_weather_location", start.elapsed().as_secs_f64());
+
+    row.map(|row| {
+        Ok(TrackWeatherLocation {
+            recorded_at: row.try_get("recorded_at")?,
+            lat: row.try_get("lat")?,
+            lon: row.try_get("lon")?,
+            is_public: row.try_get::<Option<bool>, _>("is_public")?.unwrap_or(true),
+            session_id: row.try_get("session_id")?,
+        })
+    })
+    .transpose()
+}
+
+pub async fn get_cached_track_weather(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Option<WeatherObservation>, sqlx::Error> {
+    let start = Instant::now();
+    let row = sqlx::query(
+        r#"
+        SELECT temperature_c, wind_speed_kmh, precipitation_mm, weather_code
+        FROM track_weather
+        WHERE track_id = $1
+        "#,
+    )
+    .bind(track_id)
+    .fetch_optional(&**pool)
+    .await?;
+    metrics::observe_db_query("get_cached_track_weather", start.elapsed().as_secs_f64());
+
+    row.map(|row| {
+        Ok(WeatherObservation {
+            temperature_c: row.try_get("temperature_c")?,
+            wind_speed_kmh: row.try_get("wind_speed_kmh")?,
+            precipitation_mm: row.try_get("precipitation_mm")?,
+            weather_code: row.try_get("weather_code")?,
+        })
+    })
+    .transpose()
+}
+
+pub async fn store_track_weather(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+    observation: &WeatherObservation,
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    sqlx::query(
+        r#"
+        INSERT INTO track_weather (track_id, temperature_c, wind_speed_kmh, precipitation_mm, weather_code)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (track_id) DO UPDATE SET
+            temperature_c = EXCLUDED.temperature_c,
+            wind_speed_kmh = EXCLUDED.wind_speed_kmh,
+            precipitation_mm = EXCLUDED.precipitation_mm,
+            weather_code = EXCLUDED.weather_code,
+            fetched_at = NOW()
+        "#,
+    )
+    .bind(track_id)
+    .bind(observation.temperature_c)
+    .bind(observation.wind_speed_kmh)
+    .bind(observation.precipitation_mm)
+    .bind(observation.weather_code)
+    .execute(&**pool)
+    .await?;
+    metrics::observe_db_query("store_track_weather", start.elapsed().as_secs_f64());
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::metrics;
+use crate::models::{Webhook, WebhookInfo};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+pub async fn create_webhook(
+    pool: &Arc<PgPool>,
+    id: Uuid,
+    url: &str,
+    session_id: Uuid,
+    events: &[String],
+    secret: &str,
+) -> Result<(), sqlx::Error> {
+    let start = Instant::now();
+    sqlx::query(
+        r#"
+        INSERT INTO webhooks (id, url, session_id, events, secret)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(id)
+    .bind(url)
+    .bind(session_id)
+    .bind(events)
+    .bind(secret)
+    .execute(&**pool)
+    .await?;
+    metrics::observe_db_query("create_webhook", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// List a session's webhooks; secrets are never returned after creation
+pub async fn list_webhooks(
+    pool: &Arc<PgPool>,
+    session_id: Uuid,
+) -> Result<Vec<WebhookInfo>, sqlx::Error> {
+    let start = Instant::now();
+    let rows = sqlx::query(
+        r#"
+        SELECT id, url, events, created_at
+        FROM webhooks
+        WHERE session_id = $1
+        ORDER BY created_at
+        "#,
+    )
+    .bind(session_id)
+    .fetch_all(&**pool)
+    .await?;
+    metrics::observe_db_query("list_webhooks", start.elapsed().as_secs_f64());
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(WebhookInfo {
+                id: row.try_get("id")?,
+                url: row.try_get("url")?,
+                events: row.try_get("events")?,
+                created_at: row.try_get("created_at")?,
+            })
+        })
+        .collect()
+}
+
+/// Delete a webhook owned by `session_id`; returns the number of rows removed
+pub async fn delete_webhook(
+    pool: &Arc<PgPool>,
+    id: Uuid,
+    session_id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let start = Instant::now();
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = $1 AND session_id = $2")
+        .bind(id)
+        .bind(session_id)
+        .execute(&**pool)
+        .await?;
+    metrics::observe_db_query("delete_webhook", start.elapsed().as_secs_f64());
+    Ok(result.rows_affected())
+}
+
+/// Webhooks of the track owner that are subscribed to `event`
+pub async fn find_webhooks_for_track_event(
+    pool: &PgPool,
+    track_id: Uuid,
+    event: &str,
+) -> Result<Vec<Webhook>, sqlx::Error> {
+    let start = Instant::now();
+    let rows = sqlx::query(
+        r#"
+        SELECT w.id, w.url, w.secret
+        FROM webhooks w
+        JOIN tracks t ON t.session_id = w.session_id
+        WHERE t.id = $1 AND $2 = ANY(w.events)
+        "#,
+    )
+    .bind(track_id)
+    .bind(event)
+    .fetch_all(pool)
+    .await?;
+    metrics::observe_db_query(
+        "find_webhooks_for_track_event",
+        start.elapsed().as_secs_f64(),
+    );
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(Webhook {
+                id: row.try_get("id")?,
+                url: row.try_get("url")?,
+                secret: row.try_get("secret")?,
+            })
+        })
+        .collect()
+}
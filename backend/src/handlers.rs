@@ -1,36 +1,73 @@
+use crate::category_taxonomy::build_category_tree;
 use crate::db;
 use crate::input_validation::{
-    MAX_CATEGORIES, MAX_CATEGORY_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_FIELD_SIZE, MAX_NAME_LENGTH,
-    validate_file_size, validate_text_field,
+    MAX_BULK_DELETE_POIS, MAX_BULK_DELETE_TRACKS, MAX_CATEGORIES, MAX_CATEGORY_LENGTH,
+    MAX_COLLECTION_EXPORT_TRACKS, MAX_DESCRIPTION_LENGTH, MAX_FIELD_SIZE,
+    MAX_IDEMPOTENCY_KEY_LENGTH, MAX_NAME_LENGTH, normalize_language_code, sanitize_for_log,
+    sanitize_input, validate_category_chars, validate_check_exist_file_size, validate_file_size,
+    validate_text_field, validate_wgs84,
 };
 use crate::metrics;
 use crate::models::*;
+use crate::services::enrichment_progress::{self, EnrichmentProgress, ProgressReporter};
+use crate::services::enrichment_queue;
 use crate::services::gpx_export::GpxExportService;
-use crate::services::track_upload::{TrackUploadRequest, TrackUploadService};
+use crate::services::rate_limiter::{self, RateLimiter};
+use crate::services::thumbnail_service::{ThumbnailError, ThumbnailService};
+use crate::services::track_upload::{
+    TrackUploadOutcome, TrackUploadRequest, TrackUploadService, resolve_categories,
+};
+use crate::services::weather_service::{WeatherError, WeatherService};
+use crate::services::webhooks;
+use crate::track_classifier::{
+    ClassificationResult, TrackClassification, TrackClassifier, TrackMetrics, classify_track,
+    hr_variation,
+};
 use crate::track_utils::{
-    ElevationEnrichmentService, calculate_file_hash, extract_coordinates_from_geojson,
+    ElevationEnrichmentService, calculate_hash_streaming, cumulative_distances_m,
+    extract_coordinates_from_geojson, extract_segments_from_geojson, filter_pace_data,
+    metrics::{POWER_ZONE_UPPER_BOUNDS, power_zone_seconds},
+};
+use async_zip::base::write::ZipFileWriter;
+use async_zip::{Compression, ZipEntryBuilder};
+use axum::extract::rejection::JsonRejection;
+use axum::http::header::{
+    ACCEPT_LANGUAGE, CACHE_CONTROL, CONTENT_TYPE, ETAG, HeaderValue, IF_NONE_MATCH, REFERER, VARY,
 };
-use axum::http::header::REFERER;
 use axum::{
     Json,
-    extract::{Path, Query, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, KeepAliveStream, Sse},
+    },
 };
 use axum_extra::extract::multipart::Multipart as AxumMultipart;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sqlx::PgPool;
-use std::collections::{HashMap, HashSet};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::io::ReaderStream;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
+use xxhash_rust::xxh3::xxh3_64;
 
 // Safe error handling - don't expose internal details
 fn handle_db_error(err: sqlx::Error) -> StatusCode {
+    // A unique violation is a lost race between concurrent requests, not a server fault
+    if db::is_unique_violation(&err) {
+        warn!(error = ?err, "unique constraint violated");
+        return StatusCode::CONFLICT;
+    }
     error!(error = ?err, "database error occurred");
     match err {
         sqlx::Error::RowNotFound => StatusCode::NOT_FOUND,
@@ -39,84 +76,125 @@ fn handle_db_error(err: sqlx::Error) -> StatusCode {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/tracks/exist",
+    request_body(content = inline(crate::openapi::TrackFileForm), content_type = "multipart/form-data"),
+    params(("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; details of own private tracks are included")),
+    responses(
+        (status = 200, description = "Whether a track with the same file hash exists", body = TrackExistResponse),
+        (status = 400, description = "Missing or oversized file"),
+    ),
+    tag = "tracks",
+)]
 pub async fn check_track_exist(
     State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
     mut multipart: AxumMultipart,
 ) -> Result<Json<TrackExistResponse>, StatusCode> {
     let mut file_bytes = None;
     let mut file_name = None;
     // Gracefully handle multipart errors: if any error occurs, treat as no file provided
     while let Some(field_result) = multipart.next_field().await.transpose() {
-        let field = match field_result {
+        let mut field = match field_result {
             Ok(f) => f,
             Err(_) => {
                 // Malformed multipart, treat as no file
-                return Ok(Json(TrackExistResponse {
-                    is_exist: false,
-                    id: None,
-                }));
+                return Ok(Json(TrackExistResponse::default()));
             }
         };
         if let Some("file") = field.name() {
             file_name = field.file_name().map(|s| s.to_string());
-            file_bytes = match field.bytes().await {
-                Ok(bytes) => Some(bytes),
-                Err(_) => {
-                    // Malformed file part, treat as no file
-                    return Ok(Json(TrackExistResponse {
-                        is_exist: false,
-                        id: None,
-                    }));
+            // Read chunk by chunk so oversized files are rejected before being fully buffered
+            let mut bytes = Vec::new();
+            loop {
+                match field.chunk().await {
+                    Ok(Some(chunk)) => {
+                        validate_check_exist_file_size(bytes.len() + chunk.len())?;
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    Ok(None) => break,
+                    Err(_) => {
+                        // Malformed file part, treat as no file
+                        return Ok(Json(TrackExistResponse::default()));
+                    }
                 }
-            };
+            }
+            file_bytes = Some(bytes);
         }
     }
     let file_bytes = match file_bytes {
         Some(b) => b,
         None => {
-            return Ok(Json(TrackExistResponse {
-                is_exist: false,
-                id: None,
-            }));
+            return Ok(Json(TrackExistResponse::default()));
         }
     };
     let _file_name = match file_name {
         Some(f) => f,
         None => {
-            return Ok(Json(TrackExistResponse {
-                is_exist: false,
-                id: None,
-            }));
+            return Ok(Json(TrackExistResponse::default()));
         }
     };
     // Fast hash calculation without full parsing
-    // This is much faster for large files (27MB GPX with 94k points: <1s vs 26s)
-    let hash = calculate_file_hash(&file_bytes);
+    // This is much faster for large files (27MB GPX with 94k points: <1s vs 26s),
+    // but still CPU-bound, so keep it off the async runtime
+    let hash = tokio::task::spawn_blocking(move || calculate_hash_streaming(file_bytes.as_slice()))
+        .await
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "check_track_exist", "hash task failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "check_track_exist", "failed to read file for hashing");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    let id = db::track_exists(&pool, &hash)
+    let existing = db::track_exists_with_metadata(&pool, &hash)
         .await
         .map_err(handle_db_error)?;
-    if let Some(id) = id {
-        Ok(Json(TrackExistResponse {
+    Ok(Json(match existing {
+        Some(track) => existing_track_response(track, parse_session_header(&headers)),
+        None => TrackExistResponse::default(),
+    }))
+}
+
+/// Duplicate lookups reveal only the id of tracks the caller may not view
+fn existing_track_response(track: db::ExistingTrack, caller: Option<Uuid>) -> TrackExistResponse {
+    if !can_view_track(track.is_public, track.session_id, caller) {
+        return TrackExistResponse {
             is_exist: true,
-            id: Some(id),
-        }))
-    } else {
-        Ok(Json(TrackExistResponse {
-            is_exist: false,
-            id: None,
-        }))
+            id: Some(track.id),
+            ..Default::default()
+        };
+    }
+    TrackExistResponse {
+        is_exist: true,
+        id: Some(track.id),
+        name: Some(track.name),
+        length_km: Some(track.length_km),
+        created_at: track.created_at,
     }
 }
 
-static LAST_UPLOAD: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// Recent upload timestamps per session for sliding-window rate limiting
+static LAST_UPLOAD: Lazy<RwLock<HashMap<String, VecDeque<u64>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
 
-// Configurable rate limiting
+// Configurable rate limiting: at most UPLOAD_RATE_LIMIT_COUNT uploads per
+// UPLOAD_RATE_LIMIT_SECONDS sliding window
 static UPLOAD_RATE_LIMIT_SECONDS: Lazy<u64> = Lazy::new(|| {
     std::env::var("UPLOAD_RATE_LIMIT_SECONDS")
         .ok()
         .and_then(|s| s.parse().ok())
-        .unwrap_or(10) // Default 10 seconds
+        .unwrap_or(60) // Default 60 second window
+});
+
+static UPLOAD_RATE_LIMIT_COUNT: Lazy<usize> = Lazy::new(|| {
+    std::env::var("UPLOAD_RATE_LIMIT_COUNT")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(5) // Default 5 uploads per window
 });
 
 fn normalize_session_id(raw: &str) -> Result<(Uuid, String), StatusCode> {
@@ -132,7 +210,8 @@ fn normalize_session_id(raw: &str) -> Result<(Uuid, String), StatusCode> {
     match Uuid::parse_str(trimmed) {
         Ok(uuid) => Ok((uuid, trimmed.to_string())),
         Err(e) => {
-            warn!(reason = "invalid_session_id", session_id = %trimmed, error = ?e, "failed to parse session_id");
+            // The raw value is attacker-controlled, so only its length is logged
+            warn!(reason = "invalid_session_id", length = trimmed.len(), error = ?e, "failed to parse session_id");
             Err(StatusCode::BAD_REQUEST)
         }
     }
@@ -145,6 +224,103 @@ fn parse_session_header(headers: &HeaderMap) -> Option<Uuid> {
         .and_then(|v| Uuid::parse_str(v.trim()).ok())
 }
 
+// How long a stored upload response is replayed for a repeated Idempotency-Key
+static IDEMPOTENCY_TTL_SECS: Lazy<i64> = Lazy::new(|| {
+    std::env::var("IDEMPOTENCY_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &i64| n > 0)
+        .unwrap_or(24 * 60 * 60)
+});
+
+// How long a request holding an Idempotency-Key blocks retries with the same key,
+// in case it dies before storing its response
+const IDEMPOTENCY_LEASE_SECS: i64 = 5 * 60;
+
+/// Cache key for an upload retried with `key`. Scoped by session so clients cannot
+/// read each other's responses, and by a fingerprint of the request so a reused key
+/// with a different payload is processed as a new upload.
+fn upload_idempotency_cache_key(
+    key: &str,
+    session_id: Option<Uuid>,
+    file_bytes: &[u8],
+    name: Option<&str>,
+    description: Option<&str>,
+    categories: &[String],
+) -> String {
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    hasher.update(file_bytes);
+    for field in [name, description]
+        .into_iter()
+        .chain(categories.iter().map(|c| Some(c.as_str())))
+    {
+        hasher.update(&[0]);
+        hasher.update(field.unwrap_or_default().as_bytes());
+    }
+    let session = session_id.map_or_else(|| "anon".to_string(), |id| id.to_string());
+    format!("upload_track:{session}:{:016x}:{key}", hasher.digest())
+}
+
+/// `Idempotency-Key` header, if present. Keys must be non-empty visible ASCII of
+/// bounded length; anything else is rejected rather than silently ignored.
+fn parse_idempotency_key(headers: &HeaderMap) -> Result<Option<String>, StatusCode> {
+    let Some(value) = headers.get("idempotency-key") else {
+        return Ok(None);
+    };
+    let key = value.to_str().map_err(|_| StatusCode::BAD_REQUEST)?.trim();
+    if key.is_empty()
+        || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH
+        || !key.chars().all(|c| c.is_ascii_graphic())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(Some(key.to_string()))
+}
+
+/// Language tags from `Accept-Language`, most preferred first. Wildcards, zero weights
+/// and malformed tags are dropped.
+fn parse_accept_language(headers: &HeaderMap) -> Vec<String> {
+    let Some(value) = headers.get(ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()) else {
+        return Vec::new();
+    };
+    let mut weighted: Vec<(String, f32)> = value
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            if tag == "*" || quality.is_nan() || quality <= 0.0 {
+                return None;
+            }
+            Some((normalize_language_code(tag).ok()?, quality))
+        })
+        .collect();
+    // Stable sort keeps header order for equal weights
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weighted.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Translation for the most preferred language: an exact tag match wins, otherwise
+/// any translation sharing the primary language (`de-CH` accepts `de` and vice versa).
+fn best_translation<'a>(
+    translations: &'a [TrackTranslation],
+    accepted: &[String],
+) -> Option<&'a TrackTranslation> {
+    let primary = |tag: &str| tag.split('-').next().unwrap_or_default().to_string();
+    accepted.iter().find_map(|tag| {
+        translations
+            .iter()
+            .find(|t| t.language_code == *tag)
+            .or_else(|| {
+                translations
+                    .iter()
+                    .find(|t| primary(&t.language_code) == primary(tag))
+            })
+    })
+}
+
 fn derive_referrer(headers: &HeaderMap) -> &'static str {
     headers
         .get(REFERER)
@@ -167,6 +343,65 @@ fn classify_ownership(track_session: Option<Uuid>, request_session: Option<Uuid>
     }
 }
 
+/// Whether an `If-None-Match` header lists `etag` (or `*`); weak validators compare equal
+fn if_none_match_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Serialize `body` as JSON tagged with a hash of its content. Clients that send the same
+/// ETag back in `If-None-Match` get an empty `304 Not Modified` instead of the full track.
+/// Private tracks depend on the caller's session, so shared caches must not reuse the body.
+fn json_with_etag<T: Serialize>(
+    headers: &HeaderMap,
+    body: &T,
+) -> Result<axum::response::Response, StatusCode> {
+    let bytes = serde_json::to_vec(body).map_err(|e| {
+        error!(error = ?e, "failed to serialize response body");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let etag = format!("\"{:016x}\"", xxh3_64(&bytes));
+
+    let builder = axum::response::Response::builder()
+        .header(ETAG, &etag)
+        .header(CACHE_CONTROL, "private")
+        .header(VARY, "x-session-id");
+    let response = if if_none_match_matches(headers, &etag) {
+        builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(axum::body::Body::empty())
+    } else {
+        builder
+            .header(CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(bytes))
+    };
+    response.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Private tracks are only visible to the session that owns them
+fn can_view_track(
+    is_public: bool,
+    track_session: Option<Uuid>,
+    request_session: Option<Uuid>,
+) -> bool {
+    is_public || (track_session.is_some() && track_session == request_session)
+}
+
+/// Mutations are only allowed for the session that uploaded the track; tracks without an
+/// owner session cannot be modified at all
+fn check_track_owner(track_session: Option<Uuid>, session_id: Uuid) -> Result<(), StatusCode> {
+    if track_session == Some(session_id) {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
 fn bucket_zoom_level(zoom: Option<f64>) -> &'static str {
     match zoom {
         Some(z) if z < 10.0 => "low",
@@ -187,37 +422,43 @@ fn detect_search_query_type(query: &str) -> &'static str {
     }
 }
 
-fn record_session_upload_attempt(session_key: &str, now: u64) -> Result<(), StatusCode> {
-    let mut map = LAST_UPLOAD.lock().map_err(|e| {
-        error!(error = ?e, "LAST_UPLOAD mutex poisoned");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-    if let Some(&last) = map.get(session_key) {
-        // If the recorded last timestamp is in the future relative to the provided "now",
-        // treat it as stale and overwrite with current time to avoid spurious rate limits
-        // caused by tests running in parallel or clock skews in tests.
-        if last > now {
-            map.insert(session_key.to_string(), now);
-            return Ok(());
-        }
+async fn record_session_upload_attempt(session_key: &str, now: u64) -> Result<(), StatusCode> {
+    let window = *UPLOAD_RATE_LIMIT_SECONDS;
+    let mut map = LAST_UPLOAD.write().await;
+    let attempts = map.entry(session_key.to_string()).or_default();
 
-        if now < last + *UPLOAD_RATE_LIMIT_SECONDS {
-            let retry_after = last + *UPLOAD_RATE_LIMIT_SECONDS - now;
-            warn!(
-                reason = "upload_rate_limited",
-                session_id = session_key,
-                retry_after_seconds = retry_after,
-                "upload_track rate limit hit"
-            );
-            return Err(StatusCode::TOO_MANY_REQUESTS);
-        }
+    // If recorded timestamps are in the future relative to the provided "now",
+    // treat them as stale to avoid spurious rate limits caused by tests running
+    // in parallel or clock skews in tests.
+    if attempts.back().is_some_and(|&last| last > now) {
+        attempts.clear();
+    }
+
+    // Evict attempts that fell out of the sliding window
+    while attempts.front().is_some_and(|&ts| ts + window <= now) {
+        attempts.pop_front();
+    }
+
+    if attempts.len() >= *UPLOAD_RATE_LIMIT_COUNT {
+        let retry_after = attempts
+            .front()
+            .map(|&oldest| oldest + window - now)
+            .unwrap_or(window);
+        warn!(
+            reason = "upload_rate_limited",
+            session_id = session_key,
+            attempts_in_window = attempts.len(),
+            retry_after_seconds = retry_after,
+            "upload_track rate limit hit"
+        );
+        return Err(StatusCode::TOO_MANY_REQUESTS);
     }
     info!(
         session_id = session_key,
         timestamp = now,
         "recording upload attempt"
     );
-    map.insert(session_key.to_string(), now);
+    attempts.push_back(now);
     Ok(())
 }
 
@@ -264,12 +505,103 @@ fn record_session_export_attempt(session_key: &str, now: u64) -> Result<(), Stat
     Ok(())
 }
 
+/// Rate limit key for exports: the caller's session id, else the forwarded client IP
+fn export_session_key(headers: &HeaderMap) -> String {
+    headers
+        .get("x-session-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .or_else(|| {
+            headers
+                .get("x-forwarded-for")
+                .and_then(|v| v.to_str().ok())
+                .map(|s| format!("ip:{}", s.split(',').next().unwrap_or("").trim()))
+        })
+        .unwrap_or_else(|| "anon".to_string())
+}
+
+/// 429 response with `Retry-After` when `session_key` exported too recently
+fn export_rate_limited_response(
+    session_key: &str,
+) -> Result<Option<axum::response::Response>, StatusCode> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    if record_session_export_attempt(session_key, now).is_ok() {
+        return Ok(None);
+    }
+
+    let retry_after = {
+        let map = LAST_EXPORT.lock().map_err(|e| {
+            error!(error = ?e, "LAST_EXPORT mutex poisoned");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        match map.get(session_key) {
+            Some(&last) if now < last + *EXPORT_RATE_LIMIT_SECONDS => {
+                last + *EXPORT_RATE_LIMIT_SECONDS - now
+            }
+            _ => *EXPORT_RATE_LIMIT_SECONDS,
+        }
+    };
+
+    axum::response::Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header("Retry-After", retry_after.to_string())
+        .header(
+            "Access-Control-Expose-Headers",
+            "X-Export-Rate-Limit-Seconds, Retry-After",
+        )
+        .body(axum::body::Body::empty())
+        .map(Some)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// How often idle sessions are dropped from LAST_UPLOAD
+const UPLOAD_RATE_LIMIT_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Drop sessions whose latest upload is older than twice the rate limit window.
+/// Returns the number of sessions removed.
+fn purge_stale_upload_attempts(map: &mut HashMap<String, VecDeque<u64>>, now: u64) -> usize {
+    let max_age = UPLOAD_RATE_LIMIT_SECONDS.saturating_mul(2);
+    let before = map.len();
+    map.retain(|_, attempts| {
+        attempts
+            .back()
+            .is_some_and(|&last| now.saturating_sub(last) <= max_age)
+    });
+    before - map.len()
+}
+
+/// Periodically evict idle sessions from the upload rate limiter so the map does not
+/// grow with every session that has ever uploaded. Call once at startup.
+pub fn spawn_rate_limit_cleanup() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(UPLOAD_RATE_LIMIT_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let removed = purge_stale_upload_attempts(&mut *LAST_UPLOAD.write().await, now);
+            if removed > 0 {
+                debug!(removed, "purged idle upload rate limit entries");
+            }
+            let removed = SEARCH_RATE_LIMITER.purge_idle().await;
+            if removed > 0 {
+                debug!(removed, "purged idle search rate limit entries");
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 fn reset_rate_limit_state() {
     // Clear the LAST_UPLOAD and LAST_EXPORT maps for tests; if poisoned, log and skip the clear
-    match LAST_UPLOAD.lock() {
+    match LAST_UPLOAD.try_write() {
         Ok(mut m) => m.clear(),
-        Err(e) => error!(error = ?e, "LAST_UPLOAD mutex poisoned - clear skipped"),
+        Err(e) => error!(error = ?e, "LAST_UPLOAD lock busy - clear skipped"),
     }
     match LAST_EXPORT.lock() {
         Ok(mut m) => m.clear(),
@@ -277,17 +609,36 @@ fn reset_rate_limit_state() {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/tracks/upload",
+    request_body(content = inline(crate::openapi::TrackUploadForm), content_type = "multipart/form-data"),
+    params(("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key within 24 hours return the first response without uploading again")),
+    responses(
+        (status = 200, description = "Track uploaded", body = TrackUploadResponse),
+        (status = 400, description = "Invalid file, form fields or Idempotency-Key"),
+        (status = 409, description = "Track with the same content already exists, or a request with the same Idempotency-Key is still in progress"),
+        (status = 413, description = "File too large"),
+        (status = 415, description = "File encoding is not supported"),
+        (status = 422, description = "File could not be parsed"),
+        (status = 429, description = "Upload rate limit exceeded"),
+    ),
+    tag = "tracks",
+)]
 pub async fn upload_track(
     State(pool): State<Arc<PgPool>>,
+    headers: HeaderMap,
     mut multipart: AxumMultipart,
 ) -> Result<Json<TrackUploadResponse>, StatusCode> {
     info!(endpoint = "upload_track", "request received");
+    let idempotency_key = parse_idempotency_key(&headers)?;
     let mut name = None;
     let mut description = None;
     let mut categories = Vec::new();
     let mut session_id = None;
     let mut file_bytes = None;
     let mut file_name = None;
+    let mut normalized_session = None;
 
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         warn!(error = ?e, "multipart read failed");
@@ -349,21 +700,9 @@ pub async fn upload_track(
                         warn!(error = ?e, field = "session_id", "failed to read text field");
                         StatusCode::BAD_REQUEST
                     })?;
-                    let (parsed_session_id, normalized_session) = normalize_session_id(&sid_raw)?;
+                    let (parsed_session_id, normalized) = normalize_session_id(&sid_raw)?;
                     session_id = Some(parsed_session_id);
-                    // --- Rate limiting check ---
-                    let now = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    record_session_upload_attempt(&normalized_session, now).inspect_err(
-                        |&status| {
-                            if status == StatusCode::TOO_MANY_REQUESTS {
-                                metrics::record_track_upload_failure("rate_limit");
-                            }
-                        },
-                    )?;
-                    // --- End rate limiting ---
+                    normalized_session = Some(normalized);
                 }
                 "file" => {
                     file_name = field.file_name().map(|s| s.to_string());
@@ -424,6 +763,62 @@ pub async fn upload_track(
         validate_text_field(cat, MAX_CATEGORY_LENGTH, "category")?;
     }
 
+    let idempotency_key = idempotency_key.map(|key| {
+        upload_idempotency_cache_key(
+            &key,
+            session_id,
+            &file_bytes,
+            name.as_deref(),
+            description.as_deref(),
+            &categories,
+        )
+    });
+    if let Some(key) = &idempotency_key {
+        match db::claim_idempotency_key(&pool, key, IDEMPOTENCY_LEASE_SECS)
+            .await
+            .map_err(handle_db_error)?
+        {
+            db::IdempotencyClaim::Claimed => {}
+            db::IdempotencyClaim::InFlight => {
+                warn!(
+                    endpoint = "upload_track",
+                    "request with the same idempotency key still in progress"
+                );
+                return Err(StatusCode::CONFLICT);
+            }
+            db::IdempotencyClaim::Completed(body) => {
+                let response = serde_json::from_value(body).map_err(|e| {
+                    error!(error = ?e, "stored idempotency response is not an upload response");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+                info!(
+                    endpoint = "upload_track",
+                    "replaying stored response for idempotency key"
+                );
+                return Ok(Json(response));
+            }
+        }
+    }
+
+    // Retries replayed above do not count against the upload rate limit
+    if let Some(normalized_session) = &normalized_session {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let limited = record_session_upload_attempt(normalized_session, now)
+            .await
+            .inspect_err(|&status| {
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    metrics::record_track_upload_failure("rate_limit");
+                }
+            });
+        if let Err(status) = limited {
+            release_idempotency_claim(&pool, idempotency_key.as_deref()).await;
+            return Err(status);
+        }
+    }
+
     let service = TrackUploadService::new(Arc::clone(&pool));
     let request = TrackUploadRequest {
         name,
@@ -434,17 +829,243 @@ pub async fn upload_track(
         file_bytes,
     };
 
-    let response = service.upload_track(request).await?;
+    let response = match service.upload_track(request).await {
+        Ok(response) => response,
+        Err(status) => {
+            release_idempotency_claim(&pool, idempotency_key.as_deref()).await;
+            return Err(status);
+        }
+    };
     metrics::record_track_uploaded("anonymous");
     metrics::record_session_activity(session_id, "upload");
+    webhooks::dispatch_track_event(
+        Arc::clone(&pool),
+        webhooks::EVENT_TRACK_UPLOADED,
+        response.id,
+        json!({ "url": response.url }),
+    );
     info!(endpoint = "upload_track", track_id = %response.id, "track uploaded");
+    if let Some(key) = &idempotency_key {
+        // The upload already succeeded; a lost cache entry only costs retry safety
+        let stored = match serde_json::to_value(&response) {
+            Ok(body) => db::store_idempotency_response(&pool, key, &body, *IDEMPOTENCY_TTL_SECS)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = stored {
+            warn!(error = ?e, track_id = %response.id, "failed to store idempotency response");
+            release_idempotency_claim(&pool, Some(key)).await;
+        }
+    }
     Ok(Json(response))
 }
 
+/// Let a failed upload be retried with the same Idempotency-Key
+async fn release_idempotency_claim(pool: &Arc<PgPool>, key: Option<&str>) {
+    if let Some(key) = key
+        && let Err(e) = db::release_idempotency_key(pool, key).await
+    {
+        warn!(error = ?e, "failed to release idempotency key");
+    }
+}
+
+// Maximum number of files accepted by a single batch upload
+static BATCH_UPLOAD_MAX_FILES: Lazy<usize> = Lazy::new(|| {
+    std::env::var("BATCH_UPLOAD_MAX_FILES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(50)
+});
+
+fn batch_upload_error(filename: String, status: StatusCode) -> BatchUploadError {
+    BatchUploadError {
+        filename,
+        error: status
+            .canonical_reason()
+            .unwrap_or("Upload failed")
+            .to_string(),
+    }
+}
+
+/// Upload many GPX/KML files at once. Files are parsed and inserted in parallel; duplicates
+/// and per-file failures are reported instead of failing the whole batch.
+#[utoipa::path(
+    post,
+    path = "/tracks/batch-upload",
+    request_body(content = inline(crate::openapi::BatchUploadForm), content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Per-file upload results", body = BatchUploadResponse),
+        (status = 400, description = "No files, too many files, invalid categories or missing session_id"),
+        (status = 429, description = "Upload rate limit exceeded"),
+    ),
+    tag = "tracks",
+)]
+pub async fn batch_upload_tracks(
+    State(pool): State<Arc<PgPool>>,
+    mut multipart: AxumMultipart,
+) -> Result<Json<BatchUploadResponse>, StatusCode> {
+    let mut categories = Vec::new();
+    let mut session: Option<(Uuid, String)> = None;
+    let mut files: Vec<(String, bytes::Bytes)> = Vec::new();
+    let mut errors = Vec::new();
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        warn!(error = ?e, "multipart read failed");
+        StatusCode::BAD_REQUEST
+    })? {
+        match field.name() {
+            Some("categories") => {
+                let cats = field.text().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+                validate_text_field(&cats, MAX_FIELD_SIZE, "categories")?;
+                categories = cats
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+            Some("session_id") => {
+                let sid_raw = field.text().await.map_err(|_| StatusCode::BAD_REQUEST)?;
+                session = Some(normalize_session_id(&sid_raw)?);
+            }
+            Some("file") => {
+                if files.len() + errors.len() >= *BATCH_UPLOAD_MAX_FILES {
+                    warn!(
+                        max = *BATCH_UPLOAD_MAX_FILES,
+                        "batch upload exceeds file limit"
+                    );
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+                let file_name = field.file_name().unwrap_or("unnamed").to_string();
+                match field.bytes().await {
+                    Ok(bytes) => files.push((file_name, bytes)),
+                    Err(e) => {
+                        warn!(error = ?e, file_name = %sanitize_for_log(&file_name), "failed to read batch file bytes");
+                        metrics::record_track_upload_failure("read_error");
+                        errors.push(batch_upload_error(file_name, StatusCode::BAD_REQUEST));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if files.is_empty() && errors.is_empty() {
+        warn!(reason = "missing_file", "batch upload without files");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if categories.is_empty() || categories.len() > MAX_CATEGORIES {
+        metrics::record_track_upload_failure("validation");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    for cat in &categories {
+        validate_text_field(cat, MAX_CATEGORY_LENGTH, "category")?;
+    }
+    // Uploads are rate limited per session, so a batch without one is not accepted
+    let Some((session_id, normalized_session)) = session else {
+        warn!(
+            reason = "missing_session",
+            "batch upload without session_id"
+        );
+        metrics::record_track_upload_failure("validation");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    let session_id = Some(session_id);
+
+    // The whole batch counts as a single upload attempt, recorded once it is known to be valid
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    record_session_upload_attempt(&normalized_session, now)
+        .await
+        .inspect_err(|&status| {
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                metrics::record_track_upload_failure("rate_limit");
+            }
+        })?;
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (file_name, file_bytes) in files {
+        let service = TrackUploadService::new(Arc::clone(&pool));
+        let request = TrackUploadRequest {
+            name: None,
+            description: None,
+            categories: categories.clone(),
+            session_id,
+            file_name: file_name.clone(),
+            file_bytes,
+        };
+        tasks.spawn(async move { (file_name, service.upload_track_outcome(request).await) });
+    }
+
+    let mut uploaded = Vec::new();
+    let mut duplicates = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Ok(TrackUploadOutcome::Uploaded(response)))) => {
+                metrics::record_track_uploaded("anonymous");
+                webhooks::dispatch_track_event(
+                    Arc::clone(&pool),
+                    webhooks::EVENT_TRACK_UPLOADED,
+                    response.id,
+                    json!({ "url": response.url }),
+                );
+                uploaded.push(response);
+            }
+            Ok((_, Ok(TrackUploadOutcome::Duplicate(existing_id)))) => {
+                duplicates.push(existing_id);
+            }
+            Ok((file_name, Err(status))) => {
+                errors.push(batch_upload_error(file_name, status));
+            }
+            Err(e) => {
+                error!(error = ?e, endpoint = "batch_upload_tracks", "upload task failed");
+                errors.push(batch_upload_error(
+                    "unknown".to_string(),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ));
+            }
+        }
+    }
+
+    if !uploaded.is_empty() {
+        metrics::record_session_activity(session_id, "upload");
+    }
+    info!(
+        endpoint = "batch_upload_tracks",
+        uploaded = uploaded.len(),
+        duplicates = duplicates.len(),
+        errors = errors.len(),
+        "batch upload finished"
+    );
+    Ok(Json(BatchUploadResponse {
+        uploaded,
+        duplicates,
+        errors,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks",
+    params(TrackGeoJsonQuery),
+    responses(
+        (status = 200, description = "Tracks as a GeoJSON FeatureCollection, at most `max_tracks` of them", body = TrackGeoJsonCollection, headers(("X-Truncated" = String, description = "`true` when the result was cut off at `max_tracks`"))),
+        (status = 400, description = "Invalid filter parameters"),
+    ),
+    tag = "tracks",
+)]
 pub async fn list_tracks_geojson(
     State(pool): State<Arc<PgPool>>,
     Query(params): Query<TrackGeoJsonQuery>,
-) -> Result<Json<TrackGeoJsonCollection>, StatusCode> {
+) -> Result<axum::response::Response, StatusCode> {
+    if !is_valid_recorded_range(params.recorded_after, params.recorded_before)
+        || !params.has_finite_numeric_filters()
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
     let geojson = db::list_tracks_geojson(
         &pool,
         params.bbox.as_deref(),
@@ -454,16 +1075,78 @@ pub async fn list_tracks_geojson(
     )
     .await
     .map_err(handle_db_error)?;
-    Ok(Json(geojson))
+    Ok(geojson_response(geojson))
+}
+
+/// Flags a list cut off at `max_tracks` with `X-Truncated: true`
+fn geojson_response(collection: TrackGeoJsonCollection) -> axum::response::Response {
+    let truncated = collection.warning.is_some();
+    let mut response = Json(collection).into_response();
+    if truncated {
+        response
+            .headers_mut()
+            .insert("x-truncated", HeaderValue::from_static("true"));
+    }
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/my",
+    params(MyTracksQuery, TrackGeoJsonQuery, ("x-session-id" = Option<Uuid>, Header, description = "Alternative to the session_id query parameter")),
+    responses(
+        (status = 200, description = "Tracks of the session, private ones included, as a GeoJSON FeatureCollection, at most `max_tracks` of them", body = TrackGeoJsonCollection, headers(("X-Truncated" = String, description = "`true` when the result was cut off at `max_tracks`"))),
+        (status = 400, description = "Missing session id or invalid filter parameters"),
+    ),
+    tag = "tracks",
+)]
+pub async fn list_my_tracks(
+    State(pool): State<Arc<PgPool>>,
+    Query(session): Query<MyTracksQuery>,
+    Query(params): Query<TrackGeoJsonQuery>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, StatusCode> {
+    let Some(session_id) = session
+        .session_id
+        .or_else(|| parse_session_header(&headers))
+    else {
+        warn!(endpoint = "list_my_tracks", "missing session id");
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    if !is_valid_recorded_range(params.recorded_after, params.recorded_before)
+        || !params.has_finite_numeric_filters()
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let geojson = db::list_my_tracks(&pool, session_id, params)
+        .await
+        .map_err(handle_db_error)?;
+    metrics::record_session_activity(Some(session_id), "view");
+    Ok(geojson_response(geojson))
 }
 
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}",
+    params(("id" = Uuid, Path, description = "Track id"), TrackSimplificationQuery, ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks"), ("Accept-Language" = Option<String>, Header, description = "Preferred languages for the track name and description")),
+    responses(
+        (status = 200, description = "Track details", body = TrackDetail, headers(("ETag" = String, description = "Content hash of the response"))),
+        (status = 304, description = "Track unchanged since the ETag given in If-None-Match"),
+        (status = 400, description = "Zoom is not a finite number"),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
 pub async fn get_track(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
     Query(params): Query<TrackSimplificationQuery>,
     headers: HeaderMap,
-) -> Result<Json<TrackDetail>, StatusCode> {
+) -> Result<axum::response::Response, StatusCode> {
     debug!(track_id = %id, zoom = ?params.zoom, mode = ?params.mode, endpoint = "get_track", "request received");
+    if !params.has_finite_zoom() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
     // Use adaptive track detail if zoom/mode params are provided
     let result = if params.zoom.is_some() || params.mode.is_some() {
@@ -474,12 +1157,39 @@ pub async fn get_track(
 
     let session_id = parse_session_header(&headers);
     match result {
-        Ok(Some(track)) => {
+        Ok(Some(track)) if !can_view_track(track.is_public, track.session_id, session_id) => {
+            debug!(track_id = %id, endpoint = "get_track", "private track hidden from non-owner");
+            Err(StatusCode::NOT_FOUND)
+        }
+        Ok(Some(mut track)) => {
             let ownership = classify_ownership(track.session_id, session_id);
             let referrer = derive_referrer(&headers);
             metrics::record_track_view(ownership, referrer);
             metrics::record_session_activity(session_id, "view");
-            Ok(Json(track))
+
+            let accepted = parse_accept_language(&headers);
+            if !accepted.is_empty() {
+                match db::list_track_translations(&pool, id).await {
+                    Ok(translations) => {
+                        if let Some(t) = best_translation(&translations, &accepted) {
+                            track.name = t.name.clone();
+                            if t.description.is_some() {
+                                track.description = t.description.clone();
+                            }
+                        }
+                    }
+                    // Serve the primary name rather than failing the whole request
+                    Err(e) => {
+                        warn!(error = ?e, track_id = %id, "failed to load track translations")
+                    }
+                }
+            }
+            let mut response = json_with_etag(&headers, &track)?;
+            response.headers_mut().insert(
+                VARY,
+                HeaderValue::from_static("x-session-id, accept-language"),
+            );
+            Ok(response)
         }
         Ok(None) => {
             debug!(track_id = %id, endpoint = "get_track", "track not found");
@@ -492,17 +1202,36 @@ pub async fn get_track(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/simplified",
+    params(("id" = Uuid, Path, description = "Track id"), TrackSimplificationQuery, ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Track with simplified geometry", body = TrackSimplified, headers(("ETag" = String, description = "Content hash of the response"))),
+        (status = 304, description = "Track unchanged since the ETag given in If-None-Match"),
+        (status = 400, description = "Zoom is not a finite number"),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
 pub async fn get_track_simplified(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
     Query(params): Query<TrackSimplificationQuery>,
     headers: HeaderMap,
-) -> Result<Json<TrackSimplified>, StatusCode> {
+) -> Result<axum::response::Response, StatusCode> {
     debug!(track_id = %id, zoom = ?params.zoom, mode = ?params.mode, endpoint = "get_track_simplified", "request received");
+    if !params.has_finite_zoom() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
+    let session_id = parse_session_header(&headers);
     match db::get_track_detail_adaptive(&pool, id, params.zoom, params.mode.as_deref()).await {
-        Ok(Some(track)) => {
-            let session_id = parse_session_header(&headers);
+        Ok(Some(track)) if !can_view_track(track.is_public, track.session_id, session_id) => {
+            debug!(track_id = %id, endpoint = "get_track_simplified", "private track hidden from non-owner");
+            Err(StatusCode::NOT_FOUND)
+        }
+        Ok(Some(track)) => {
             let ownership = classify_ownership(track.session_id, session_id);
             let referrer = derive_referrer(&headers);
             metrics::record_track_view(ownership, referrer);
@@ -520,6 +1249,7 @@ pub async fn get_track_simplified(
                 elevation_profile: track.elevation_profile,
                 hr_data: track.hr_data,
                 temp_data: track.temp_data,
+                cadence_data: track.cadence_data,
                 time_data: track.time_data,
                 elevation_gain: track.elevation_gain,
                 elevation_loss: track.elevation_loss,
@@ -538,15 +1268,28 @@ pub async fn get_track_simplified(
                 avg_hr: track.avg_hr,
                 hr_min: track.hr_min,
                 hr_max: track.hr_max,
+                cadence_avg: track.cadence_avg,
+                cadence_min: track.cadence_min,
+                cadence_max: track.cadence_max,
+                power_avg: track.power_avg,
+                power_max: track.power_max,
+                power_normalized: track.power_normalized,
                 moving_time: track.moving_time,
                 pause_time: track.pause_time,
                 moving_avg_speed: track.moving_avg_speed,
                 moving_avg_pace: track.moving_avg_pace,
+                moving_distance_km: track.moving_distance_km,
+                is_loop: track.is_loop,
+                elevation_source: track.elevation_source,
+                classification_confidence: track.classification_confidence,
                 duration_seconds: track.duration_seconds,
                 recorded_at: track.recorded_at,
                 created_at: track.created_at,
                 updated_at: track.updated_at,
                 session_id: track.session_id,
+                metadata_author: track.metadata_author,
+                metadata_link: track.metadata_link,
+                metadata_copyright: track.metadata_copyright,
                 auto_classifications: track.auto_classifications,
                 speed_data: track.speed_data,
                 pace_data: track.pace_data,
@@ -561,7 +1304,7 @@ pub async fn get_track_simplified(
                 "adaptive optimization finished"
             );
 
-            Ok(Json(simplified))
+            json_with_etag(&headers, &simplified)
         }
         Ok(None) => {
             debug!(track_id = %id, endpoint = "get_track_simplified", "track not found");
@@ -574,6 +1317,387 @@ pub async fn get_track_simplified(
     }
 }
 
+/// Shortest pause offered as a split point; pauses under
+/// `PAUSE_GAP_THRESHOLD_SECS` are never detected in the first place
+static MIN_SPLIT_GAP_SECS: Lazy<i64> = Lazy::new(|| {
+    std::env::var("MIN_SPLIT_GAP_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(300)
+});
+
+/// Gaps worth splitting at, most severe first: segment gaps (teleports, which have no
+/// duration) by distance, then pauses of at least `min_pause_secs` by duration
+fn split_suggestions(
+    segment_gaps: Option<Vec<GapInfo>>,
+    pause_gaps: Option<Vec<GapInfo>>,
+    min_pause_secs: i64,
+) -> Vec<SplitSuggestion> {
+    let mut segments = segment_gaps.unwrap_or_default();
+    segments.sort_by(|a, b| b.distance_m.total_cmp(&a.distance_m));
+
+    let mut pauses: Vec<GapInfo> = pause_gaps
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|gap| gap.duration_seconds.unwrap_or(0) >= min_pause_secs)
+        .collect();
+    pauses.sort_by(|a, b| {
+        b.duration_seconds
+            .cmp(&a.duration_seconds)
+            .then(b.distance_m.total_cmp(&a.distance_m))
+    });
+
+    segments
+        .into_iter()
+        .chain(pauses)
+        .map(SplitSuggestion::from)
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/split-suggestions",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Suggested split points, most severe first", body = Vec<SplitSuggestion>),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_split_suggestions(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SplitSuggestion>>, StatusCode> {
+    // Full-resolution geometry so point indices match the stored track
+    let track = match db::get_track_detail(&pool, id).await {
+        Ok(Some(track)) => track,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_split_suggestions", "db error");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if !can_view_track(
+        track.is_public,
+        track.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(split_suggestions(
+        track.segment_gaps,
+        track.pause_gaps,
+        *MIN_SPLIT_GAP_SECS,
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/comparable-stats",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Speed and heart rate compared with similar public tracks", body = ComparableStats),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_comparable_stats(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ComparableStats>, StatusCode> {
+    let session_id = parse_session_header(&headers);
+    match db::get_comparable_stats(&pool, id, session_id).await {
+        Ok(Some(stats)) => Ok(Json(stats)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_comparable_stats", "db error");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/weather",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Historical weather at the track's midpoint when it was recorded", body = TrackWeather),
+        (status = 404, description = "Track not found or not visible, has no recording time, or no weather data is available"),
+        (status = 502, description = "Weather provider unavailable"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_weather(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<TrackWeather>, StatusCode> {
+    let location = db::get_track_weather_location(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !can_view_track(
+        location.is_public,
+        location.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let Some(recorded_at) = location.recorded_at else {
+        debug!(track_id = %id, endpoint = "get_track_weather", "track has no recording time");
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    if let Some(cached) = db::get_cached_track_weather(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+    {
+        return Ok(Json(TrackWeather::from(&cached)));
+    }
+
+    let observation = WeatherService::new()
+        .fetch_historical_weather(location.lat, location.lon, recorded_at)
+        .await
+        .map_err(|e| {
+            warn!(track_id = %id, error = %e, endpoint = "get_track_weather", "weather lookup failed");
+            match e {
+                WeatherError::NoData(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::BAD_GATEWAY,
+            }
+        })?;
+    // A failed cache write only costs another lookup next time
+    if let Err(e) = db::store_track_weather(&pool, id, &observation).await {
+        warn!(track_id = %id, error = ?e, "failed to cache track weather");
+    }
+    Ok(Json(TrackWeather::from(&observation)))
+}
+
+// Thumbnails only change when regenerated, so clients may keep them for a day
+const THUMBNAIL_MAX_AGE_SECS: u32 = 24 * 60 * 60;
+
+fn png_response(
+    png: Vec<u8>,
+    etag: &str,
+    cache_control: &str,
+) -> Result<axum::response::Response, StatusCode> {
+    axum::response::Response::builder()
+        .header(CONTENT_TYPE, "image/png")
+        .header(ETAG, etag)
+        .header(CACHE_CONTROL, cache_control)
+        .body(axum::body::Body::from(png))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/thumbnail",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = GenerateThumbnailRequest,
+    responses(
+        (status = 200, description = "Freshly rendered 256x256 thumbnail", body = Vec<u8>, content_type = "image/png"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+        (status = 502, description = "Map tile server unavailable"),
+    ),
+    tag = "tracks",
+)]
+pub async fn generate_track_thumbnail(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<GenerateThumbnailRequest>,
+) -> Result<axum::response::Response, StatusCode> {
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    let segments = extract_segments_from_geojson(&track.geom_geojson).map_err(|e| {
+        error!(track_id = %id, error = %e, "track geometry is not a line");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let png = ThumbnailService::new()
+        .render(&segments)
+        .await
+        .map_err(|e| {
+            warn!(track_id = %id, error = %e, endpoint = "generate_track_thumbnail", "thumbnail rendering failed");
+            match e {
+                ThumbnailError::EmptyTrack | ThumbnailError::Image(_) => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+                _ => StatusCode::BAD_GATEWAY,
+            }
+        })?;
+    db::store_track_thumbnail(&pool, id, &png)
+        .await
+        .map_err(handle_db_error)?;
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+
+    let etag = format!("\"{:016x}\"", xxh3_64(&png));
+    png_response(png, &etag, "no-cache")
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/thumbnail",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Cached thumbnail", body = Vec<u8>, content_type = "image/png", headers(("ETag" = String, description = "Content hash of the image"))),
+        (status = 304, description = "Thumbnail unchanged since the ETag given in If-None-Match"),
+        (status = 404, description = "Track not found or not visible, or no thumbnail generated yet"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_thumbnail(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, StatusCode> {
+    let thumbnail = db::get_track_thumbnail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !can_view_track(
+        thumbnail.is_public,
+        thumbnail.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    // Shared caches must not keep private tracks
+    let scope = if thumbnail.is_public {
+        "public"
+    } else {
+        "private"
+    };
+    let cache_control = format!("{scope}, max-age={THUMBNAIL_MAX_AGE_SECS}");
+    let etag = format!("\"{:016x}\"", xxh3_64(&thumbnail.png));
+    if if_none_match_matches(&headers, &etag) {
+        return axum::response::Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(ETAG, &etag)
+            .header(CACHE_CONTROL, &cache_control)
+            .body(axum::body::Body::empty())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    png_response(thumbnail.png, &etag, &cache_control)
+}
+
+const MAX_SHARE_EXPIRY_HOURS: u32 = 720;
+const SHARE_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// 32 bytes from the OS CSPRNG, hex encoded
+fn generate_share_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/share",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = CreateTrackShareRequest,
+    responses(
+        (status = 201, description = "Share link created", body = TrackShareResponse),
+        (status = 400, description = "expires_in_hours outside 1..=720"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
+pub async fn create_track_share(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateTrackShareRequest>,
+) -> Result<(StatusCode, Json<TrackShareResponse>), StatusCode> {
+    if !(1..=MAX_SHARE_EXPIRY_HOURS).contains(&payload.expires_in_hours) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    let token = generate_share_token().map_err(|e| {
+        error!(error = %e, "failed to generate share token");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let expires_at = db::create_track_share(&pool, &token, id, payload.expires_in_hours as i32)
+        .await
+        .map_err(handle_db_error)?;
+    info!(track_id = %id, expires_in_hours = payload.expires_in_hours, "track share link created");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TrackShareResponse {
+            url: format!("/tracks/shared/{token}"),
+            expires_at,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/shared/{token}",
+    params(("token" = String, Path, description = "Share token from POST /tracks/{id}/share")),
+    responses(
+        (status = 200, description = "Shared track, visible whether or not it is public", body = TrackDetail),
+        (status = 404, description = "Unknown or expired share token"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_shared_track(
+    State(pool): State<Arc<PgPool>>,
+    Path(token): Path<String>,
+) -> Result<Json<TrackDetail>, StatusCode> {
+    let track_id = db::get_shared_track_id(&pool, &token)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let mut track = db::get_track_detail(&pool, track_id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    // The owner's session id would let the recipient edit the track
+    track.session_id = None;
+    metrics::record_track_view("unknown", "link");
+    Ok(Json(track))
+}
+
+/// Periodically delete expired share tokens. Call once at startup, after migrations.
+pub fn spawn_track_share_cleanup(pool: Arc<PgPool>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SHARE_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match db::delete_expired_track_shares(&pool).await {
+                Ok(0) => {}
+                Ok(removed) => debug!(removed, "purged expired track share links"),
+                Err(e) => warn!(error = ?e, "failed to purge expired track share links"),
+            }
+        }
+    });
+}
+
+#[utoipa::path(
+    patch,
+    path = "/tracks/{id}/description",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpdateTrackDescriptionRequest,
+    responses(
+        (status = 204, description = "Description updated"),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
 pub async fn update_track_description(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -587,9 +1711,7 @@ pub async fn update_track_description(
         Some(t) => t,
         None => return Err(StatusCode::NOT_FOUND),
     };
-    if track.session_id != Some(payload.session_id) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+    check_track_owner(track.session_id, payload.session_id)?;
     db::update_track_description(&pool, id, &payload.description)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -598,6 +1720,19 @@ pub async fn update_track_description(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    patch,
+    path = "/tracks/{id}/name",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpdateTrackNameRequest,
+    responses(
+        (status = 204, description = "Name updated"),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
 pub async fn update_track_name(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -616,9 +1751,7 @@ pub async fn update_track_name(
         Some(t) => t,
         None => return Err(StatusCode::NOT_FOUND),
     };
-    if track.session_id != Some(payload.session_id) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+    check_track_owner(track.session_id, payload.session_id)?;
 
     db::update_track_name(&pool, id, payload.name.trim())
         .await
@@ -628,6 +1761,359 @@ pub async fn update_track_name(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// POST /tracks/{id}/clone - copy a visible track into a new private track of the caller
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/clone",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = CloneTrackRequest,
+    responses(
+        (status = 201, description = "Track cloned", body = TrackUploadResponse),
+        (status = 400, description = "Name empty or too long"),
+        (status = 404, description = "Track not found or private to another session"),
+    ),
+    tag = "tracks",
+)]
+pub async fn clone_track(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CloneTrackRequest>,
+) -> Result<(StatusCode, Json<TrackUploadResponse>), StatusCode> {
+    validate_text_field(&payload.name, MAX_NAME_LENGTH, "name")?;
+    let name = sanitize_input(&payload.name, MAX_NAME_LENGTH);
+    if name.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let new_id = Uuid::new_v4();
+    let cloned = db::clone_track(&pool, id, new_id, &name, payload.session_id)
+        .await
+        .map_err(handle_db_error)?;
+    if !cloned {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    info!(source_track_id = %id, track_id = %new_id, "track cloned");
+    metrics::record_session_activity(Some(payload.session_id), "upload");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TrackUploadResponse {
+            id: new_id,
+            url: format!("/tracks/{new_id}"),
+        }),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/translations",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpsertTrackTranslationRequest,
+    responses(
+        (status = 204, description = "Translation created or replaced"),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
+pub async fn upsert_track_translation(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpsertTrackTranslationRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let language_code = normalize_language_code(&payload.language_code)?;
+    let name = payload.name.trim();
+    if name.is_empty() || name.len() > 255 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Some(description) = &payload.description {
+        validate_text_field(description, MAX_DESCRIPTION_LENGTH, "description")?;
+    }
+
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    db::upsert_track_translation(
+        &pool,
+        id,
+        &language_code,
+        name,
+        payload.description.as_deref(),
+    )
+    .await
+    .map_err(handle_db_error)?;
+    metrics::record_track_edit("translation");
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/translations",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Translations of the track", body = [TrackTranslation]),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
+pub async fn list_track_translations(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<TrackTranslation>>, StatusCode> {
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !can_view_track(
+        track.is_public,
+        track.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let translations = db::list_track_translations(&pool, id)
+        .await
+        .map_err(handle_db_error)?;
+    Ok(Json(translations))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tracks/{id}/translations/{lang}",
+    params(("id" = Uuid, Path, description = "Track id"), ("lang" = String, Path, description = "Language code of the translation")),
+    request_body = DeleteTrackTranslationRequest,
+    responses(
+        (status = 204, description = "Translation deleted"),
+        (status = 400, description = "Invalid language code"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track or translation not found"),
+    ),
+    tag = "tracks",
+)]
+pub async fn delete_track_translation(
+    State(pool): State<Arc<PgPool>>,
+    Path((id, lang)): Path<(Uuid, String)>,
+    Json(payload): Json<DeleteTrackTranslationRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let language_code = normalize_language_code(&lang)?;
+
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    let affected = db::delete_track_translation(&pool, id, &language_code)
+        .await
+        .map_err(handle_db_error)?;
+    if affected == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    metrics::record_track_edit("translation");
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/tracks/{id}/visibility",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpdateTrackVisibilityRequest,
+    responses(
+        (status = 204, description = "Visibility updated"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
+pub async fn update_track_visibility(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTrackVisibilityRequest>,
+) -> Result<StatusCode, StatusCode> {
+    // Check that track exists and session_id matches owner
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let track = match track {
+        Some(t) => t,
+        None => return Err(StatusCode::NOT_FOUND),
+    };
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    db::update_track_visibility(&pool, id, payload.is_public)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    metrics::record_track_edit("visibility");
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Metrics the classifier needs, taken from the stored track
+fn classification_metrics(track: &TrackDetail) -> TrackMetrics {
+    TrackMetrics {
+        length_km: track.length_km,
+        avg_speed: track.avg_speed,
+        moving_avg_speed: track.moving_avg_speed,
+        elevation_gain: track.elevation_gain.map(|v| v as f64),
+        elevation_loss: track.elevation_loss.map(|v| v as f64),
+        moving_time: track.moving_time,
+        duration_seconds: track.duration_seconds,
+        is_loop: track.is_loop,
+        hr_variation: track.hr_data.as_ref().and_then(|hr| {
+            let hr: Vec<Option<i32>> = hr
+                .as_array()?
+                .iter()
+                .map(|v| v.as_i64().and_then(|v| i32::try_from(v).ok()))
+                .collect();
+            hr_variation(&hr)
+        }),
+    }
+}
+
+/// Attach confidence and reasoning to each label the classifier can explain
+fn build_classifications_response(
+    classifications: Vec<String>,
+    track_metrics: &TrackMetrics,
+) -> TrackClassificationsResponse {
+    let mut confidence = std::collections::BTreeMap::new();
+    let mut reasoning = std::collections::BTreeMap::new();
+    for label in &classifications {
+        let explanation = TrackClassification::from_label(label)
+            .and_then(|c| TrackClassifier::explain(&c, track_metrics));
+        if let Some(explanation) = explanation {
+            confidence.insert(label.clone(), explanation.confidence);
+            reasoning.insert(label.clone(), explanation.reasoning);
+        }
+    }
+    TrackClassificationsResponse {
+        classifications,
+        confidence,
+        reasoning,
+    }
+}
+
+/// Validate user-supplied activity types and store them as canonical slugs, without duplicates
+fn normalize_auto_classifications(labels: &[String]) -> Result<Vec<String>, StatusCode> {
+    if labels.len() > MAX_CATEGORIES {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let mut normalized: Vec<String> = Vec::with_capacity(labels.len());
+    for label in labels.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        validate_text_field(label, MAX_CATEGORY_LENGTH, "auto_classification")?;
+        validate_category_chars(label)?;
+        let slug = TrackClassification::from_label(label)
+            .ok_or_else(|| {
+                warn!(label, "unknown auto classification");
+                StatusCode::BAD_REQUEST
+            })?
+            .category_slug();
+        if !normalized.iter().any(|s| s == slug) {
+            normalized.push(slug.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/classifications",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Auto classifications with confidence", body = TrackClassificationsResponse),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_classifications(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<TrackClassificationsResponse>, StatusCode> {
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !can_view_track(
+        track.is_public,
+        track.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let track_metrics = classification_metrics(&track);
+    Ok(Json(build_classifications_response(
+        track.auto_classifications,
+        &track_metrics,
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/reclassify",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = ReclassifyTrackRequest,
+    responses(
+        (status = 200, description = "Updated classifications", body = TrackClassificationsResponse),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
+pub async fn reclassify_track(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReclassifyTrackRequest>,
+) -> Result<Json<TrackClassificationsResponse>, StatusCode> {
+    // Check that track exists and session_id matches owner
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let track = match track {
+        Some(t) => t,
+        None => return Err(StatusCode::NOT_FOUND),
+    };
+    check_track_owner(track.session_id, payload.session_id)?;
+
+    let track_metrics = classification_metrics(&track);
+    let results = classify_track(&track_metrics);
+    let classifications: Vec<String> = results.iter().map(|r| r.label.clone()).collect();
+    let confidence = ClassificationResult::confidence_map(&results);
+
+    db::update_track_auto_classifications(&pool, id, &classifications, Some(&confidence))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    metrics::record_track_edit("classifications");
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+    info!(track_id = %id, ?classifications, "track reclassified");
+
+    Ok(Json(build_classifications_response(
+        classifications,
+        &track_metrics,
+    )))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/tracks/{id}/categories",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpdateTrackCategoriesRequest,
+    responses(
+        (status = 204, description = "Categories updated"),
+        (status = 400, description = "Invalid or unknown category"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
 pub async fn update_track_categories(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -641,9 +2127,7 @@ pub async fn update_track_categories(
         Some(t) => t,
         None => return Err(StatusCode::NOT_FOUND),
     };
-    if track.session_id != Some(payload.session_id) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+    check_track_owner(track.session_id, payload.session_id)?;
 
     // Build sanitized new categories list
     let categories: Vec<String> = payload
@@ -663,7 +2147,16 @@ pub async fn update_track_categories(
     }
     for cat in &categories {
         validate_text_field(cat, MAX_CATEGORY_LENGTH, "category")?;
+        validate_category_chars(cat)?;
     }
+    // Same taxonomy rules as upload: known slugs only, parents added
+    let categories = resolve_categories(&pool, &categories).await?;
+
+    let auto_classifications = payload
+        .auto_classifications
+        .as_deref()
+        .map(normalize_auto_classifications)
+        .transpose()?;
 
     // Compute diffs for metric reporting
     let prev_set: HashSet<String> = track.categories.into_iter().collect();
@@ -674,6 +2167,13 @@ pub async fn update_track_categories(
     db::update_track_categories(&pool, id, &categories)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(auto_classifications) = &auto_classifications {
+        // Manually chosen labels carry no classifier confidence
+        db::update_track_auto_classifications(&pool, id, auto_classifications, None)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        metrics::record_track_edit("classifications");
+    }
 
     // Metrics: record each assigned category (as at upload)
     for cat in &categories {
@@ -694,29 +2194,378 @@ pub async fn update_track_categories(
     Ok(StatusCode::NO_CONTENT)
 }
 
+const DEFAULT_SEARCH_LIMIT: u32 = 50;
+const MAX_SEARCH_LIMIT: u32 = 500;
+const DEFAULT_SEARCH_SIMILARITY_THRESHOLD: f64 = 0.2;
+
+// Searches per client IP and minute; keeps the search from being used to list every track
+static SEARCH_RATE_LIMITER: Lazy<RateLimiter> = Lazy::new(|| {
+    let per_minute = std::env::var("SEARCH_RATE_LIMIT_PER_MINUTE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(60);
+    RateLimiter::new(per_minute, std::time::Duration::from_secs(60))
+});
+
+#[utoipa::path(
+    get,
+    path = "/tracks/search",
+    params(TrackSearchQuery, ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Matching tracks", body = TrackSearchResponse, headers(
+            ("X-RateLimit-Remaining" = u32, description = "Searches left in the current window"),
+            ("X-RateLimit-Reset" = u32, description = "Seconds until the window frees up again"),
+        )),
+        (status = 400, description = "Invalid search parameters, limit above 500 or similarity threshold outside 0.0-1.0"),
+        (status = 429, description = "More than SEARCH_RATE_LIMIT_PER_MINUTE (default 60) searches in the last minute", headers(("Retry-After" = u32, description = "Seconds until the next search is allowed"))),
+    ),
+    tag = "tracks",
+)]
 pub async fn search_tracks(
     State(pool): State<Arc<PgPool>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
     Query(params): Query<TrackSearchQuery>,
     headers: HeaderMap,
-) -> Result<Json<Vec<TrackSearchResult>>, StatusCode> {
-    if params.query.trim().is_empty() {
-        return Ok(Json(vec![]));
-    }
+) -> Result<axum::response::Response, StatusCode> {
+    let rate_limit = match SEARCH_RATE_LIMITER
+        .check(rate_limiter::client_ip(peer, &headers))
+        .await
+    {
+        Ok(status) => status,
+        Err(status) => {
+            warn!(endpoint = "search_tracks", "search rate limit exceeded");
+            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+            status.apply_rejection_headers(response.headers_mut());
+            return Ok(response);
+        }
+    };
+    let with_rate_limit = |body: TrackSearchResponse| {
+        let mut response = Json(body).into_response();
+        rate_limit.apply_headers(response.headers_mut());
+        response
+    };
+
+    let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if limit > MAX_SEARCH_LIMIT {
+        warn!(limit, endpoint = "search_tracks", "limit out of range");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let similarity_threshold = params
+        .similarity_threshold
+        .unwrap_or(DEFAULT_SEARCH_SIMILARITY_THRESHOLD);
+    if !(0.0..=1.0).contains(&similarity_threshold) {
+        warn!(
+            similarity_threshold,
+            endpoint = "search_tracks",
+            "similarity threshold out of range"
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if params.query.trim().is_empty() {
+        return Ok(with_rate_limit(TrackSearchResponse {
+            tracks: vec![],
+            total_count: 0,
+        }));
+    }
+    if !is_valid_recorded_range(params.recorded_after, params.recorded_before) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
     let session_id = parse_session_header(&headers);
-    let tracks = db::search_tracks(&pool, &params.query).await.map_err(|e| {
+    let tracks = db::search_tracks(
+        &pool,
+        &params.query,
+        params.recorded_after,
+        params.recorded_before,
+        similarity_threshold,
+        limit,
+        params.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| {
         error!(error = ?e, endpoint = "search_tracks", "db error searching tracks");
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    let result_type = if tracks.is_empty() { "zero" } else { "success" };
+    let result_type = if tracks.tracks.is_empty() {
+        "zero"
+    } else {
+        "success"
+    };
     let query_type = detect_search_query_type(&params.query);
     metrics::record_track_search(result_type, query_type);
     metrics::record_session_activity(session_id, "search");
 
+    Ok(with_rate_limit(tracks))
+}
+
+const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.95;
+
+const DEFAULT_HEATMAP_RESOLUTION: u32 = 100;
+const MAX_HEATMAP_RESOLUTION: u32 = 1000;
+/// Finest grid we aggregate to (~1 m), so tiny bboxes do not explode the cell count
+const MIN_HEATMAP_GRID_DEGREES: f64 = 1e-5;
+
+/// Most heatmaps kept at once; each can hold up to `MAX_HEATMAP_RESOLUTION`² cells
+const MAX_HEATMAP_CACHE_ENTRIES: usize = 64;
+
+struct CachedHeatmap {
+    computed_at: Instant,
+    last_used: Instant,
+    collection: TrackGeoJsonCollection,
+}
+
+/// Heatmaps by request key, dropped once older than the TTL and least recently used
+/// first when full
+struct HeatmapCache {
+    entries: HashMap<String, CachedHeatmap>,
+    capacity: usize,
+}
+
+impl HeatmapCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &str, ttl: std::time::Duration) -> Option<TrackGeoJsonCollection> {
+        let entry = self.entries.get_mut(key)?;
+        if entry.computed_at.elapsed() >= ttl {
+            self.entries.remove(key);
+            return None;
+        }
+        entry.last_used = Instant::now();
+        Some(entry.collection.clone())
+    }
+
+    fn insert(
+        &mut self,
+        key: String,
+        collection: TrackGeoJsonCollection,
+        ttl: std::time::Duration,
+    ) {
+        self.entries
+            .retain(|_, entry| entry.computed_at.elapsed() < ttl);
+        while self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        let now = Instant::now();
+        self.entries.insert(
+            key,
+            CachedHeatmap {
+                computed_at: now,
+                last_used: now,
+                collection,
+            },
+        );
+    }
+}
+
+// Heatmaps aggregate every public track, so recent results are reused per bbox and resolution
+static HEATMAP_CACHE: Lazy<Mutex<HeatmapCache>> =
+    Lazy::new(|| Mutex::new(HeatmapCache::new(MAX_HEATMAP_CACHE_ENTRIES)));
+
+static HEATMAP_CACHE_TTL_SECONDS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("HEATMAP_CACHE_TTL_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(300)
+});
+
+/// Grid cell size in degrees: the queried extent (the bbox, or the whole world) split into
+/// `resolution` cells along its longer side. `zoom` can coarsen the grid to one tile split
+/// into `resolution` cells, but never refines it, so the cell count stays bounded.
+fn heatmap_grid_size_degrees(bbox: Option<[f64; 4]>, zoom: Option<f64>, resolution: u32) -> f64 {
+    let extent = match bbox {
+        Some([min_lon, min_lat, max_lon, max_lat]) => {
+            (max_lon - min_lon).abs().max((max_lat - min_lat).abs())
+        }
+        None => 360.0,
+    };
+    let tile_width = zoom
+        .filter(|z| z.is_finite())
+        .map_or(0.0, |z| 360.0 / 2f64.powf(z.clamp(0.0, 22.0)));
+    (extent.max(tile_width) / resolution as f64).max(MIN_HEATMAP_GRID_DEGREES)
+}
+
+/// GET /tracks/heatmap - density of public tracks aggregated into a square grid
+#[utoipa::path(
+    get,
+    path = "/tracks/heatmap",
+    params(TrackHeatmapQuery),
+    responses(
+        (status = 200, description = "Grid cells as GeoJSON polygons with a `density` property", body = TrackGeoJsonCollection),
+        (status = 400, description = "Invalid bbox or resolution"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_heatmap(
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<TrackHeatmapQuery>,
+) -> Result<Json<TrackGeoJsonCollection>, StatusCode> {
+    let resolution = params.resolution.unwrap_or(DEFAULT_HEATMAP_RESOLUTION);
+    if resolution == 0 || resolution > MAX_HEATMAP_RESOLUTION {
+        warn!(
+            resolution,
+            endpoint = "get_track_heatmap",
+            "resolution out of range"
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let bbox = match &params.bbox {
+        Some(bbox_str) => {
+            let parts: Vec<f64> = bbox_str
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect();
+            if parts.len() != 4 || parts[0] >= parts[2] || parts[1] >= parts[3] {
+                warn!(bbox = %bbox_str, endpoint = "get_track_heatmap", "invalid bbox");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            Some([parts[0], parts[1], parts[2], parts[3]])
+        }
+        None => None,
+    };
+    let grid_size = heatmap_grid_size_degrees(bbox, params.zoom, resolution);
+    let cache_key = format!(
+        "{}|{resolution}|{grid_size}",
+        params.bbox.as_deref().unwrap_or("")
+    );
+    let ttl = std::time::Duration::from_secs(*HEATMAP_CACHE_TTL_SECONDS);
+
+    let cached = HEATMAP_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&cache_key, ttl);
+    if let Some(collection) = cached {
+        return Ok(Json(collection));
+    }
+
+    let collection = db::get_track_heatmap(&pool, bbox, grid_size)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "get_track_heatmap", "db error building heatmap");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    HEATMAP_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(cache_key, collection.clone(), ttl);
+    Ok(Json(collection))
+}
+
+/// Extract a LineString/MultiLineString geometry from a bare GeoJSON geometry or a Feature
+fn extract_line_geometry(body: &serde_json::Value) -> Option<&serde_json::Value> {
+    let geometry = match body.get("type").and_then(|t| t.as_str()) {
+        Some("Feature") => body.get("geometry")?,
+        _ => body,
+    };
+    match geometry.get("type").and_then(|t| t.as_str()) {
+        Some("LineString") | Some("MultiLineString") => Some(geometry),
+        _ => None,
+    }
+}
+
+/// GET /tracks/similar - find public tracks geometrically similar to a GeoJSON line.
+/// Lets clients warn about near-duplicate uploads even when the file hashes differ.
+#[utoipa::path(
+    get,
+    path = "/tracks/similar",
+    params(SimilarTracksQuery),
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "Public tracks similar to the given geometry", body = Vec<TrackListItem>),
+        (status = 400, description = "Invalid geometry or threshold"),
+    ),
+    tag = "tracks",
+)]
+pub async fn find_similar_tracks(
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<SimilarTracksQuery>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<Vec<TrackListItem>>, StatusCode> {
+    let threshold = params.threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD);
+    if !(threshold > 0.0 && threshold <= 1.0) {
+        warn!(
+            threshold,
+            endpoint = "find_similar_tracks",
+            "threshold out of range"
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let geometry = extract_line_geometry(&body).ok_or_else(|| {
+        warn!(
+            endpoint = "find_similar_tracks",
+            "body is not a LineString or MultiLineString"
+        );
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let tracks = db::find_geometrically_similar_tracks(&pool, geometry, threshold)
+        .await
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "find_similar_tracks", "db error finding similar tracks");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
     Ok(Json(tracks))
 }
 
+/// Full category taxonomy as a nested tree
+#[utoipa::path(
+    get,
+    path = "/categories",
+    responses(
+        (status = 200, description = "Category taxonomy", body = Vec<CategoryNode>),
+    ),
+    tag = "categories",
+)]
+pub async fn get_categories(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<Json<Vec<CategoryNode>>, StatusCode> {
+    let categories = db::list_categories(&pool).await.map_err(handle_db_error)?;
+    Ok(Json(build_category_tree(&categories)))
+}
+
+/// GET /tracks/stats - aggregate statistics across all public tracks
+#[utoipa::path(
+    get,
+    path = "/tracks/stats",
+    responses(
+        (status = 200, description = "Aggregate statistics", body = TrackStats),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_stats(
+    State(pool): State<Arc<PgPool>>,
+) -> Result<Json<TrackStats>, StatusCode> {
+    let stats = db::get_track_stats(&pool).await.map_err(|e| {
+        error!(error = ?e, endpoint = "get_track_stats", "db error computing track stats");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    post,
+    path = "/observability/map-interactions",
+    request_body = MapInteractionEvent,
+    responses(
+        (status = 204, description = "Interaction recorded"),
+    ),
+    tag = "observability",
+)]
 pub async fn record_map_interaction(
     Json(event): Json<MapInteractionEvent>,
 ) -> Result<StatusCode, StatusCode> {
@@ -732,12 +2581,276 @@ pub async fn record_map_interaction(
     Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn health() -> &'static str {
-    debug!(endpoint = "health", "health check");
-    "ok"
+/// How long the health check waits for `SELECT 1`
+const HEALTH_DB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Unhealthy without a database; degraded when every pool connection is busy,
+/// since new requests will queue for one
+fn health_status(connected: bool, pool_size: u32, idle: u32, max_connections: u32) -> &'static str {
+    if !connected {
+        "unhealthy"
+    } else if idle == 0 && pool_size >= max_connections {
+        "degraded"
+    } else {
+        "healthy"
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Service is healthy or degraded", body = HealthResponse),
+        (status = 503, description = "Database is unreachable", body = HealthResponse),
+    ),
+    tag = "system",
+)]
+pub async fn health(State(pool): State<Arc<PgPool>>) -> (StatusCode, Json<HealthResponse>) {
+    let connected = matches!(
+        tokio::time::timeout(HEALTH_DB_TIMEOUT, sqlx::query("SELECT 1").execute(&*pool)).await,
+        Ok(Ok(_))
+    );
+    let pool_size = pool.size();
+    let idle_connections = pool.num_idle() as u32;
+    let status = health_status(
+        connected,
+        pool_size,
+        idle_connections,
+        pool.options().get_max_connections(),
+    );
+    debug!(endpoint = "health", status, "health check");
+
+    let code = if status == "unhealthy" {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (
+        code,
+        Json(HealthResponse {
+            status: status.to_string(),
+            db: DbHealth {
+                connected,
+                pool_size,
+                idle_connections,
+            },
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }),
+    )
+}
+
+// Session allowed to call admin endpoints; admin access is disabled when unset
+static ADMIN_SESSION_ID: Lazy<Option<Uuid>> = Lazy::new(|| {
+    std::env::var("ADMIN_SESSION_ID")
+        .ok()
+        .and_then(|s| Uuid::parse_str(s.trim()).ok())
+});
+
+const DEFAULT_API_USAGE_DAYS: i32 = 30;
+const MAX_API_USAGE_DAYS: i32 = 365;
+
+fn check_admin_session(admin: Option<Uuid>, session_id: Uuid) -> Result<(), StatusCode> {
+    if admin == Some(session_id) {
+        Ok(())
+    } else {
+        warn!(session_id = %session_id, "admin endpoint denied");
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/api-usage",
+    params(AdminQuery, ApiUsageQuery),
+    responses(
+        (status = 200, description = "Daily external API calls, newest first", body = [ApiUsageStat]),
+        (status = 400, description = "Invalid service or days"),
+        (status = 403, description = "Session is not the configured admin session"),
+    ),
+    tag = "system",
+)]
+pub async fn get_api_usage(
+    State(pool): State<Arc<PgPool>>,
+    Query(admin): Query<AdminQuery>,
+    Query(params): Query<ApiUsageQuery>,
+) -> Result<Json<Vec<ApiUsageStat>>, StatusCode> {
+    check_admin_session(*ADMIN_SESSION_ID, admin.session_id)?;
+
+    let days = params.days.unwrap_or(DEFAULT_API_USAGE_DAYS);
+    if !(1..=MAX_API_USAGE_DAYS).contains(&days) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let service = params.service.trim();
+    if service.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    validate_text_field(service, MAX_NAME_LENGTH, "service")?;
+
+    let stats = db::get_api_usage_stats(&pool, service, days)
+        .await
+        .map_err(handle_db_error)?;
+    Ok(Json(stats))
+}
+
+/// GET /admin/tracks/orphaned-pois - POIs no longer linked to any track
+#[utoipa::path(
+    get,
+    path = "/admin/tracks/orphaned-pois",
+    params(AdminQuery),
+    responses(
+        (status = 200, description = "POIs without track links, oldest first", body = Vec<Poi>),
+        (status = 403, description = "Session is not the configured admin session"),
+    ),
+    tag = "system",
+)]
+pub async fn list_orphaned_pois(
+    State(pool): State<Arc<PgPool>>,
+    Query(admin): Query<AdminQuery>,
+) -> Result<Json<Vec<Poi>>, StatusCode> {
+    check_admin_session(*ADMIN_SESSION_ID, admin.session_id)?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            p.id, p.name, p.description, p.category, p.elevation,
+            ST_AsGeoJSON(p.geom::geometry)::jsonb as geom,
+            p.session_id, p.created_at, p.updated_at
+        FROM pois p
+        WHERE NOT EXISTS (SELECT 1 FROM track_pois tp WHERE tp.poi_id = p.id)
+        ORDER BY p.created_at, p.id
+        "#,
+    )
+    .fetch_all(&*pool)
+    .await
+    .map_err(handle_db_error)?;
+
+    let pois = rows
+        .into_iter()
+        .map(|row| {
+            use sqlx::Row;
+            Poi {
+                id: row.get("id"),
+                name: row.get("name"),
+                description: row.get("description"),
+                category: row.get("category"),
+                elevation: row.get("elevation"),
+                geom: row.get("geom"),
+                session_id: row.get("session_id"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            }
+        })
+        .collect();
+    Ok(Json(pois))
+}
+
+/// DELETE /admin/tracks/orphaned-pois - drop unowned POIs that no track links to
+#[utoipa::path(
+    delete,
+    path = "/admin/tracks/orphaned-pois",
+    params(AdminQuery),
+    responses(
+        (status = 200, description = "Orphaned auto-imported POIs deleted", body = BulkDeletePoisResponse),
+        (status = 403, description = "Session is not the configured admin session"),
+    ),
+    tag = "system",
+)]
+pub async fn delete_orphaned_pois(
+    State(pool): State<Arc<PgPool>>,
+    Query(admin): Query<AdminQuery>,
+) -> Result<Json<BulkDeletePoisResponse>, StatusCode> {
+    check_admin_session(*ADMIN_SESSION_ID, admin.session_id)?;
+
+    // POIs created by a user are kept even without tracks; only auto-imported ones go
+    let result = sqlx::query(
+        r#"
+        DELETE FROM pois p
+        WHERE p.session_id IS NULL
+            AND NOT EXISTS (SELECT 1 FROM track_pois tp WHERE tp.poi_id = p.id)
+        "#,
+    )
+    .execute(&*pool)
+    .await
+    .map_err(handle_db_error)?;
+
+    let deleted_count = result.rows_affected();
+    info!(deleted_count, "orphaned POIs deleted");
+    Ok(Json(BulkDeletePoisResponse { deleted_count }))
+}
+
+/// GET /admin/enrichment-failures - tracks whose last elevation enrichment failed
+#[utoipa::path(
+    get,
+    path = "/admin/enrichment-failures",
+    params(AdminQuery),
+    responses(
+        (status = 200, description = "Failed enrichments, oldest attempt first", body = Vec<EnrichmentFailure>),
+        (status = 403, description = "Session is not the configured admin session"),
+    ),
+    tag = "system",
+)]
+pub async fn list_enrichment_failures(
+    State(pool): State<Arc<PgPool>>,
+    Query(admin): Query<AdminQuery>,
+) -> Result<Json<Vec<EnrichmentFailure>>, StatusCode> {
+    check_admin_session(*ADMIN_SESSION_ID, admin.session_id)?;
+
+    // Include failures past the startup retry limit; those need a manual retry most
+    let failures = db::list_enrichment_failures(&pool)
+        .await
+        .map_err(handle_db_error)?;
+    Ok(Json(failures))
+}
+
+/// POST /admin/enrichment-failures/{track_id}/retry - queue a new enrichment attempt
+#[utoipa::path(
+    post,
+    path = "/admin/enrichment-failures/{track_id}/retry",
+    params(
+        ("track_id" = Uuid, Path, description = "Track whose enrichment failed"),
+        AdminQuery,
+    ),
+    responses(
+        (status = 202, description = "Enrichment queued"),
+        (status = 403, description = "Session is not the configured admin session"),
+        (status = 404, description = "No recorded failure for the track"),
+    ),
+    tag = "system",
+)]
+pub async fn retry_enrichment_failure(
+    State(pool): State<Arc<PgPool>>,
+    Path(track_id): Path<Uuid>,
+    Query(admin): Query<AdminQuery>,
+) -> Result<StatusCode, StatusCode> {
+    check_admin_session(*ADMIN_SESSION_ID, admin.session_id)?;
+
+    if db::get_enrichment_failure(&pool, track_id)
+        .await
+        .map_err(handle_db_error)?
+        .is_none()
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let queued = enrichment_queue::retry_enrichment(&pool, track_id)
+        .await
+        .map_err(handle_db_error)?;
+    if !queued {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!(%track_id, "manual enrichment retry queued");
+    Ok(StatusCode::ACCEPTED)
 }
 
 /// Generate sitemap.xml from public tracks
+#[utoipa::path(
+    get,
+    path = "/sitemap.xml",
+    responses(
+        (status = 200, description = "Sitemap of public tracks", body = String, content_type = "application/xml"),
+    ),
+    tag = "system",
+)]
 pub async fn sitemap(
     State(pool): State<Arc<PgPool>>,
 ) -> Result<axum::response::Response<axum::body::Body>, StatusCode> {
@@ -777,6 +2890,16 @@ pub async fn sitemap(
 
 /// Debug endpoint: spawn a background task that holds a BackgroundTaskGuard for `duration` seconds.
 /// Enabled only when `ENABLE_DEBUG_ENDPOINTS` env var is set to `1`.
+#[utoipa::path(
+    get,
+    path = "/debug/background_task",
+    params(("duration" = Option<u64>, Query, description = "Seconds to hold the guard")),
+    responses(
+        (status = 200, description = "Task spawned", body = serde_json::Value),
+        (status = 404, description = "Debug endpoints are disabled"),
+    ),
+    tag = "system",
+)]
 pub async fn debug_background_task(
     Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
 ) -> Result<axum::response::Json<serde_json::Value>, axum::http::StatusCode> {
@@ -802,6 +2925,17 @@ pub async fn debug_background_task(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/export",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Track as a GPX file", body = String, content_type = "application/gpx+xml"),
+        (status = 404, description = "Track not found or not visible"),
+        (status = 429, description = "Export rate limit exceeded"),
+    ),
+    tag = "tracks",
+)]
 pub async fn export_track_gpx(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -811,57 +2945,15 @@ pub async fn export_track_gpx(
     let start = Instant::now();
     let session_id = parse_session_header(&headers);
 
-    // --- Rate limiting for exports ---
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    let session_key = headers
-        .get("x-session-id")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.trim().to_string())
-        .or_else(|| {
-            headers
-                .get("x-forwarded-for")
-                .and_then(|v| v.to_str().ok())
-                .map(|s| format!("ip:{}", s.split(',').next().unwrap_or("").trim()))
-        })
-        .unwrap_or_else(|| "anon".to_string());
-
-    if record_session_export_attempt(&session_key, now).is_err() {
-        // compute retry_after for header
-        let retry_after = {
-            let map = LAST_EXPORT.lock().map_err(|e| {
-                error!(error = ?e, "LAST_EXPORT mutex poisoned");
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-            if let Some(&last) = map.get(&session_key) {
-                if now < last + *EXPORT_RATE_LIMIT_SECONDS {
-                    last + *EXPORT_RATE_LIMIT_SECONDS - now
-                } else {
-                    *EXPORT_RATE_LIMIT_SECONDS
-                }
-            } else {
-                *EXPORT_RATE_LIMIT_SECONDS
-            }
-        };
-
-        let resp = axum::response::Response::builder()
-            .status(StatusCode::TOO_MANY_REQUESTS)
-            .header("Retry-After", retry_after.to_string())
-            .header(
-                "Access-Control-Expose-Headers",
-                "X-Export-Rate-Limit-Seconds, Retry-After",
-            )
-            .body(axum::body::Body::empty())
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
+    if let Some(resp) = export_rate_limited_response(&export_session_key(&headers))? {
         return Ok(resp);
     }
-    // --- End rate limiting ---
 
     match db::get_track_detail(&pool, id).await {
+        Ok(Some(track)) if !can_view_track(track.is_public, track.session_id, session_id) => {
+            debug!(track_id = %id, endpoint = "export_track_gpx", "private track hidden from non-owner");
+            Err(StatusCode::NOT_FOUND)
+        }
         Ok(Some(track)) => {
             let gpx_service = GpxExportService::new();
             let gpx_content = gpx_service.generate_gpx(&track);
@@ -903,6 +2995,173 @@ pub async fn export_track_gpx(
     }
 }
 
+// Bytes of ZIP output buffered between the archive writer and the response body
+const ZIP_STREAM_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Parse a comma separated list of track ids, dropping duplicates but keeping order
+fn parse_track_id_list(raw: &str) -> Result<Vec<Uuid>, StatusCode> {
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let id = Uuid::parse_str(part).map_err(|_| StatusCode::BAD_REQUEST)?;
+        if seen.insert(id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() || ids.len() > MAX_COLLECTION_EXPORT_TRACKS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(ids)
+}
+
+/// Write one GPX entry per visible track into a ZIP archive on `writer`, one track at a
+/// time. Missing and hidden tracks are skipped. Returns the number of tracks written.
+async fn write_gpx_collection<W>(
+    pool: &Arc<PgPool>,
+    ids: &[Uuid],
+    session_id: Option<Uuid>,
+    writer: W,
+) -> Result<usize, async_zip::error::ZipError>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    let gpx_service = GpxExportService::new();
+    let mut zip = ZipFileWriter::with_tokio(writer);
+    let mut written = 0;
+    for &id in ids {
+        let track = match db::get_track_detail(pool, id).await {
+            Ok(Some(track)) if can_view_track(track.is_public, track.session_id, session_id) => {
+                track
+            }
+            Ok(_) => {
+                debug!(track_id = %id, endpoint = "export_track_collection", "track missing or not visible, skipped");
+                continue;
+            }
+            Err(e) => {
+                warn!(track_id = %id, error = ?e, endpoint = "export_track_collection", "db error, track skipped");
+                continue;
+            }
+        };
+        let gpx = gpx_service.generate_gpx(&track);
+        // The id keeps names unique when several tracks share a name
+        let filename = format!("{}-{id}.gpx", gpx_service.sanitize_filename(&track.name));
+        zip.write_entry_whole(
+            ZipEntryBuilder::new(filename.into(), Compression::Deflate),
+            gpx.as_bytes(),
+        )
+        .await?;
+        metrics::record_track_export("gpx");
+        written += 1;
+    }
+    zip.close().await?;
+    Ok(written)
+}
+
+#[utoipa::path(
+    get,
+    path = "/tracks/export/collection",
+    params(TrackCollectionExportQuery),
+    responses(
+        (status = 200, description = "ZIP archive with one GPX file per visible track; others are left out", body = Vec<u8>, content_type = "application/zip"),
+        (status = 400, description = "Missing, malformed or more than 100 track ids"),
+        (status = 429, description = "Export rate limit exceeded; see Retry-After"),
+    ),
+    tag = "tracks",
+)]
+pub async fn export_track_collection(
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<TrackCollectionExportQuery>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, StatusCode> {
+    let ids = parse_track_id_list(&params.ids)?;
+    let session_id = params.session_id;
+
+    let session_key = session_id
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| export_session_key(&headers));
+    if let Some(resp) = export_rate_limited_response(&session_key)? {
+        return Ok(resp);
+    }
+
+    // The archive is produced while it is sent, so memory stays bounded by one track
+    let (writer, reader) = tokio::io::duplex(ZIP_STREAM_BUFFER_BYTES);
+    tokio::spawn(async move {
+        match write_gpx_collection(&pool, &ids, session_id, writer).await {
+            Ok(written) => {
+                debug!(
+                    requested = ids.len(),
+                    written,
+                    endpoint = "export_track_collection",
+                    "archive complete"
+                )
+            }
+            // Headers are already sent, so the client sees a truncated archive
+            Err(e) => warn!(error = ?e, endpoint = "export_track_collection", "archive aborted"),
+        }
+    });
+    metrics::record_session_activity(session_id, "export");
+
+    axum::response::Response::builder()
+        .header(CONTENT_TYPE, "application/zip")
+        .header("Content-Disposition", "attachment; filename=\"tracks.zip\"")
+        .body(axum::body::Body::from_stream(ReaderStream::new(reader)))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Diagnostic: which fields survive exporting the track to GPX and importing it again
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/gpx-roundtrip-diff",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Preserved, lost and degraded fields", body = GpxRoundtripDiff),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_gpx_roundtrip_diff(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<GpxRoundtripDiff>, StatusCode> {
+    debug!(track_id = %id, endpoint = "get_track_gpx_roundtrip_diff", "request received");
+    let session_id = parse_session_header(&headers);
+
+    let track = match db::get_track_detail(&pool, id).await {
+        Ok(Some(track)) if can_view_track(track.is_public, track.session_id, session_id) => track,
+        Ok(_) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(?e, "[get_track_gpx_roundtrip_diff] db error");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    // Generating and re-parsing a large track is CPU-bound, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || GpxExportService::new().roundtrip_diff(&track))
+        .await
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "get_track_gpx_roundtrip_diff", "roundtrip task failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(Json)
+        .map_err(|e| {
+            error!(track_id = %id, error = %e, "exported GPX failed to parse");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tracks/{id}",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = UpdateTrackNameRequest,
+    responses(
+        (status = 204, description = "Track deleted"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "tracks",
+)]
 pub async fn delete_track(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -916,9 +3175,7 @@ pub async fn delete_track(
         return Err(StatusCode::NOT_FOUND);
     };
     // Ownership check
-    if track.session_id != Some(payload.session_id) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+    check_track_owner(track.session_id, payload.session_id)?;
     // Delete
     let affected = db::delete_track(&pool, id)
         .await
@@ -930,7 +3187,157 @@ pub async fn delete_track(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/tracks/bulk",
+    request_body = BulkDeleteTracksRequest,
+    responses(
+        (status = 200, description = "All tracks deleted", body = BulkDeleteTracksResponse),
+        (status = 400, description = "Empty list or more than 100 ids"),
+        (status = 403, description = "At least one track is not owned by the session; nothing was deleted"),
+    ),
+    tag = "tracks",
+)]
+pub async fn bulk_delete_tracks(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<BulkDeleteTracksRequest>,
+) -> Result<Json<BulkDeleteTracksResponse>, StatusCode> {
+    let mut track_ids = payload.track_ids;
+    track_ids.sort_unstable();
+    track_ids.dedup();
+    if track_ids.is_empty() || track_ids.len() > MAX_BULK_DELETE_TRACKS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let deleted = db::bulk_delete_tracks(&pool, &track_ids, payload.session_id)
+        .await
+        .map_err(handle_db_error)?;
+    let Some(deleted_count) = deleted else {
+        warn!(
+            requested = track_ids.len(),
+            endpoint = "bulk_delete_tracks",
+            "bulk delete rejected: not all tracks owned by session"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    };
+
+    metrics::record_tracks_deleted("success", deleted_count);
+    metrics::record_session_activity(Some(payload.session_id), "edit");
+    Ok(Json(BulkDeleteTracksResponse { deleted_count }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = CreateWebhookResponse),
+        (status = 400, description = "Invalid or non-public URL, or unsupported event"),
+    ),
+    tag = "webhooks",
+)]
+pub async fn create_webhook(
+    State(pool): State<Arc<PgPool>>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateWebhookResponse>), StatusCode> {
+    let url = payload.url.trim().to_string();
+    if !webhooks::is_valid_webhook_url(&url) || !webhooks::is_public_webhook_target(&url).await {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let mut events: Vec<String> = payload
+        .events
+        .iter()
+        .map(|e| e.trim().to_string())
+        .collect();
+    events.sort();
+    events.dedup();
+    if events.is_empty() || !events.iter().all(|e| webhooks::is_supported_event(e)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let secret = match payload.secret.map(|s| s.trim().to_string()) {
+        Some(s) if !s.is_empty() => {
+            validate_text_field(&s, MAX_FIELD_SIZE, "secret")?;
+            s
+        }
+        _ => format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+    };
+
+    let id = Uuid::new_v4();
+    db::create_webhook(&pool, id, &url, payload.session_id, &events, &secret)
+        .await
+        .map_err(handle_db_error)?;
+    info!(webhook_id = %id, "webhook registered");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateWebhookResponse {
+            id,
+            url,
+            events,
+            secret,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    params(WebhookSessionQuery),
+    responses(
+        (status = 200, description = "Webhooks of the session", body = Vec<WebhookInfo>),
+    ),
+    tag = "webhooks",
+)]
+pub async fn list_webhooks(
+    State(pool): State<Arc<PgPool>>,
+    Query(params): Query<WebhookSessionQuery>,
+) -> Result<Json<Vec<WebhookInfo>>, StatusCode> {
+    let hooks = db::list_webhooks(&pool, params.session_id)
+        .await
+        .map_err(handle_db_error)?;
+    Ok(Json(hooks))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(("id" = Uuid, Path, description = "Webhook id")),
+    request_body = DeleteWebhookRequest,
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found"),
+    ),
+    tag = "webhooks",
+)]
+pub async fn delete_webhook(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<DeleteWebhookRequest>,
+) -> Result<StatusCode, StatusCode> {
+    // Only the owning session can delete; others get 404 so ids are not probeable
+    let affected = db::delete_webhook(&pool, id, payload.session_id)
+        .await
+        .map_err(handle_db_error)?;
+    if affected == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Enrich track with elevation data from OpenTopoData API
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/enrich-elevation",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = EnrichElevationRequest,
+    responses(
+        (status = 200, description = "Elevation enriched", body = EnrichElevationResponse),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+        (status = 429, description = "Elevation API rate limit exceeded"),
+    ),
+    tag = "elevation",
+)]
 pub async fn enrich_elevation(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
@@ -949,10 +3356,9 @@ pub async fn enrich_elevation(
         })?;
 
     // Check ownership
-    if track.session_id != Some(payload.session_id) {
+    check_track_owner(track.session_id, payload.session_id).inspect_err(|_| {
         warn!(track_id = %id, endpoint = "enrich_elevation", "permission denied: session mismatch");
-        return Err(StatusCode::FORBIDDEN);
-    }
+    })?;
 
     // Check if enrichment is needed
     let enrichment_service = ElevationEnrichmentService::new();
@@ -963,6 +3369,7 @@ pub async fn enrich_elevation(
         payload.force.unwrap_or(false),
     ) {
         debug!(track_id = %id, endpoint = "enrich_elevation", "skipping: already enriched");
+        metrics::record_track_enrich_status("skipped_already_enriched");
         metrics::record_session_activity(Some(payload.session_id), "enrich");
         return Ok(Json(EnrichElevationResponse {
             id,
@@ -991,14 +3398,18 @@ pub async fn enrich_elevation(
 
     info!(track_id = %id, points = coordinates.len(), endpoint = "enrich_elevation", "starting elevation enrichment");
 
-    // Enrich elevation data
+    // Enrich elevation data; progress is streamed to /tracks/{id}/enrichment-status
+    let progress = ProgressReporter::start(id, coordinates.len());
     let enrichment_result = match enrichment_service
-        .enrich_track_elevation(coordinates.clone())
+        .enrich_track_elevation_with_progress(coordinates.clone(), &|done, total| {
+            progress.update(done, total)
+        })
         .await
     {
         Ok(result) => result,
         Err(e) => {
             error!("Failed to enrich elevation for track {}: {}", id, e);
+            metrics::record_track_enrich_status("failed_remote");
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
@@ -1022,6 +3433,7 @@ pub async fn enrich_elevation(
     .await
     {
         error!(track_id = %id, error = ?e, endpoint = "enrich_elevation", "failed to update elevation data");
+        metrics::record_track_enrich_status("failed_update_db");
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
@@ -1031,36 +3443,46 @@ pub async fn enrich_elevation(
 
         // Use universal slope calculation function
         let slope_start = Instant::now();
-        let slope_result =
-            recalculate_slope_metrics(&coordinates, elevation_profile, &format!("Track {}", id));
-        let slope_duration = slope_start.elapsed().as_secs_f64();
-
-        // Update track with slope data
-        if let Err(e) = db::update_track_slope(
-            &pool,
-            id,
-            db::UpdateSlopeParams {
-                slope_min: slope_result.slope_min,
-                slope_max: slope_result.slope_max,
-                slope_avg: slope_result.slope_avg,
-                slope_histogram: slope_result.slope_histogram,
-                slope_segments: slope_result.slope_segments,
-            },
-        )
-        .await
-        {
-            error!(track_id = %id, error = ?e, endpoint = "enrich_elevation", "failed to update slope data");
-            metrics::observe_slope_recalc("db_error", slope_duration);
-        } else {
-            metrics::observe_slope_recalc("success", slope_duration);
-            info!(
-                track_id = %id,
-                slope_min = slope_result.slope_min.unwrap_or(0.0),
-                slope_max = slope_result.slope_max.unwrap_or(0.0),
-                slope_avg = slope_result.slope_avg.unwrap_or(0.0),
-                endpoint = "enrich_elevation",
-                "slope metrics updated"
-            );
+        let track_name = format!("Track {id}");
+        match recalculate_slope_metrics(&coordinates, elevation_profile, &track_name) {
+            Err(e) => {
+                e.log(&track_name);
+                metrics::observe_slope_recalc(
+                    "calculation_error",
+                    slope_start.elapsed().as_secs_f64(),
+                );
+            }
+            Ok(slope_result) => {
+                let slope_duration = slope_start.elapsed().as_secs_f64();
+
+                // Update track with slope data
+                if let Err(e) = db::update_track_slope(
+                    &pool,
+                    id,
+                    db::UpdateSlopeParams {
+                        slope_min: slope_result.slope_min,
+                        slope_max: slope_result.slope_max,
+                        slope_avg: slope_result.slope_avg,
+                        slope_histogram: slope_result.slope_histogram,
+                        slope_segments: slope_result.slope_segments,
+                    },
+                )
+                .await
+                {
+                    error!(track_id = %id, error = ?e, endpoint = "enrich_elevation", "failed to update slope data");
+                    metrics::observe_slope_recalc("db_error", slope_duration);
+                } else {
+                    metrics::observe_slope_recalc("success", slope_duration);
+                    info!(
+                        track_id = %id,
+                        slope_min = slope_result.slope_min.unwrap_or(0.0),
+                        slope_max = slope_result.slope_max.unwrap_or(0.0),
+                        slope_avg = slope_result.slope_avg.unwrap_or(0.0),
+                        endpoint = "enrich_elevation",
+                        "slope metrics updated"
+                    );
+                }
+            }
         }
     }
 
@@ -1072,7 +3494,22 @@ pub async fn enrich_elevation(
         "elevation enrichment completed"
     );
 
+    progress.complete(
+        enrichment_result.metrics.elevation_gain.map(f64::from),
+        enrichment_result.metrics.elevation_loss.map(f64::from),
+    );
+    metrics::record_track_enrich_status("success");
     metrics::record_session_activity(Some(payload.session_id), "enrich");
+    webhooks::dispatch_track_event(
+        Arc::clone(&pool),
+        webhooks::EVENT_ELEVATION_ENRICHED,
+        id,
+        json!({
+            "elevation_gain": enrichment_result.metrics.elevation_gain,
+            "elevation_loss": enrichment_result.metrics.elevation_loss,
+            "elevation_dataset": enrichment_result.dataset,
+        }),
+    );
 
     Ok(Json(EnrichElevationResponse {
         id,
@@ -1086,52 +3523,924 @@ pub async fn enrich_elevation(
     }))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    use uuid::Uuid;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn normalize_session_id_accepts_trimmed_uuid() {
+        let raw = " 11111111-1111-4111-8111-111111111111 \n";
+        let (uuid, normalized) = normalize_session_id(raw).expect("should parse");
+
+        assert_eq!(
+            uuid,
+            Uuid::parse_str("11111111-1111-4111-8111-111111111111").unwrap()
+        );
+        assert_eq!(normalized, "11111111-1111-4111-8111-111111111111");
+    }
+
+    #[test]
+    fn normalize_session_id_rejects_empty_field() {
+        let err = normalize_session_id("   ").unwrap_err();
+        assert_eq!(err, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn normalize_session_id_rejects_invalid_uuid() {
+        let err = normalize_session_id("not-a-uuid").unwrap_err();
+        assert_eq!(err, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn check_track_owner_requires_matching_session() {
+        let owner = Uuid::new_v4();
+        assert_eq!(check_track_owner(Some(owner), owner), Ok(()));
+        assert_eq!(
+            check_track_owner(Some(owner), Uuid::new_v4()),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(check_track_owner(None, owner), Err(StatusCode::FORBIDDEN));
+    }
+
+    #[derive(Debug)]
+    struct FakePgError(&'static str);
+
+    impl std::fmt::Display for FakePgError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SQLSTATE {}", self.0)
+        }
+    }
+
+    impl std::error::Error for FakePgError {}
+
+    impl sqlx::error::DatabaseError for FakePgError {
+        fn message(&self) -> &str {
+            "fake database error"
+        }
+        fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+            Some(self.0.into())
+        }
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    #[test]
+    fn handle_db_error_maps_unique_violation_to_conflict() {
+        let duplicate = sqlx::Error::Database(Box::new(FakePgError("23505")));
+        assert_eq!(handle_db_error(duplicate), StatusCode::CONFLICT);
+        let other = sqlx::Error::Database(Box::new(FakePgError("40001")));
+        assert_eq!(handle_db_error(other), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            handle_db_error(sqlx::Error::RowNotFound),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn enrichment_status_hides_private_tracks() {
+        let pool = setup_test_pool().await;
+        let id = Uuid::new_v4();
+        let owner = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash, session_id, is_public)
+            VALUES ($1, 'Private track', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(37.0 55.0, 37.01 55.01)', 4326)), 1.3, $2, $3, FALSE)
+            "#,
+        )
+        .bind(id)
+        .bind(format!("enrichment-status-test-{id}"))
+        .bind(owner)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        let session = |sid: Uuid| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-session-id", sid.to_string().parse().unwrap());
+            headers
+        };
+        let res =
+            get_track_enrichment_status(State(pool.clone()), Path(id), HeaderMap::new()).await;
+        assert!(matches!(res, Err(StatusCode::NOT_FOUND)));
+        let res =
+            get_track_enrichment_status(State(pool.clone()), Path(id), session(Uuid::new_v4()))
+                .await;
+        assert!(matches!(res, Err(StatusCode::NOT_FOUND)));
+        let res = get_track_enrichment_status(State(pool.clone()), Path(id), session(owner)).await;
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn update_categories_rejects_unknown_category() {
+        let pool = setup_test_pool().await;
+        let id = Uuid::new_v4();
+        let owner = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash, session_id)
+            VALUES ($1, 'Categorised track', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(37.0 55.0, 37.01 55.01)', 4326)), 1.3, $2, $3)
+            "#,
+        )
+        .bind(id)
+        .bind(format!("categories-test-{id}"))
+        .bind(owner)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        let res = update_track_categories(
+            State(pool.clone()),
+            Path(id),
+            Json(UpdateTrackCategoriesRequest {
+                categories: vec!["not_a_real_category".to_string()],
+                session_id: owner,
+                auto_classifications: None,
+            }),
+        )
+        .await;
+        assert_eq!(res, Err(StatusCode::BAD_REQUEST));
+
+        db::delete_track(&pool, id).await.ok();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn recalculate_slopes_with_window_replaces_existing_slopes() {
+        let pool = setup_test_pool().await;
+        let id = Uuid::new_v4();
+        let owner = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash, session_id,
+                elevation_profile, slope_segments)
+            VALUES ($1, 'Sloped track', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(37.0 55.0, 37.0 55.002, 37.0 55.004, 37.0 55.006, 37.0 55.008, 37.0 55.01)', 4326)),
+                1.1, $2, $3, '[100.0, 104.0, 110.0, 112.0, 118.0, 125.0]'::jsonb, '[]'::jsonb)
+            "#,
+        )
+        .bind(id)
+        .bind(format!("slopes-window-test-{id}"))
+        .bind(owner)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        // A window without any skip or force flag recalculates the stored slopes
+        let response = recalculate_track_slopes(
+            State(pool.clone()),
+            Path(id),
+            Ok(Json(RecalculateSlopesRequest {
+                session_id: owner,
+                skip_if_calculated: None,
+                smoothing_window_m: Some(40.0),
+            })),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["skipped"], json!(false), "{body}");
+
+        let segments: serde_json::Value =
+            sqlx::query_scalar("SELECT slope_segments FROM tracks WHERE id = $1")
+                .bind(id)
+                .fetch_one(&*pool)
+                .await
+                .unwrap();
+        assert!(!segments.as_array().unwrap().is_empty());
+
+        db::delete_track(&pool, id).await.ok();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn track_mutations_reject_foreign_session() {
+        let pool = setup_test_pool().await;
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash, session_id)
+            VALUES ($1, 'Owned track', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(37.0 55.0, 37.01 55.01)', 4326)), 1.3, $2, $3)
+            "#,
+        )
+        .bind(id)
+        .bind(format!("ownership-test-{id}"))
+        .bind(Uuid::new_v4())
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        let other = Uuid::new_v4();
+        let forbidden = Some(StatusCode::FORBIDDEN);
+        let state = || State(pool.clone());
+
+        assert_eq!(
+            generate_track_thumbnail(
+                state(),
+                Path(id),
+                Json(GenerateThumbnailRequest { session_id: other })
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            create_track_share(
+                state(),
+                Path(id),
+                Json(CreateTrackShareRequest {
+                    session_id: other,
+                    expires_in_hours: 1,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            update_track_description(
+                state(),
+                Path(id),
+                Json(UpdateTrackDescriptionRequest {
+                    description: "x".into(),
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            update_track_name(
+                state(),
+                Path(id),
+                Json(UpdateTrackNameRequest {
+                    name: "x".into(),
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            upsert_track_translation(
+                state(),
+                Path(id),
+                Json(UpsertTrackTranslationRequest {
+                    language_code: "de".into(),
+                    name: "x".into(),
+                    description: None,
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            delete_track_translation(
+                state(),
+                Path((id, "de".to_string())),
+                Json(DeleteTrackTranslationRequest { session_id: other }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            update_track_visibility(
+                state(),
+                Path(id),
+                Json(UpdateTrackVisibilityRequest {
+                    is_public: false,
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            reclassify_track(
+                state(),
+                Path(id),
+                Json(ReclassifyTrackRequest { session_id: other })
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            update_track_categories(
+                state(),
+                Path(id),
+                Json(UpdateTrackCategoriesRequest {
+                    categories: vec![],
+                    session_id: other,
+                    auto_classifications: None,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            enrich_elevation(
+                state(),
+                Path(id),
+                Json(EnrichElevationRequest {
+                    force: None,
+                    dataset: None,
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            recalculate_track_slopes(
+                state(),
+                Path(id),
+                Ok(Json(RecalculateSlopesRequest {
+                    session_id: other,
+                    skip_if_calculated: None,
+                    smoothing_window_m: None,
+                })),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+        assert_eq!(
+            delete_track(
+                state(),
+                Path(id),
+                Json(UpdateTrackNameRequest {
+                    name: String::new(),
+                    session_id: other,
+                }),
+            )
+            .await
+            .err(),
+            forbidden
+        );
+
+        sqlx::query("DELETE FROM tracks WHERE id = $1")
+            .bind(id)
+            .execute(&*pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn can_view_track_hides_private_tracks_from_other_sessions() {
+        let owner = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        assert!(can_view_track(true, Some(owner), None));
+        assert!(can_view_track(false, Some(owner), Some(owner)));
+        assert!(!can_view_track(false, Some(owner), Some(other)));
+        assert!(!can_view_track(false, Some(owner), None));
+        assert!(!can_view_track(false, None, None));
+    }
+
+    #[test]
+    fn classifications_response_skips_unknown_labels() {
+        let track_metrics = TrackMetrics {
+            length_km: 21.1,
+            avg_speed: Some(10.0),
+            moving_avg_speed: Some(10.0),
+            elevation_gain: Some(50.0),
+            elevation_loss: Some(50.0),
+            moving_time: Some(7600),
+            duration_seconds: Some(7600),
+            is_loop: false,
+            hr_variation: None,
+        };
+        let response = build_classifications_response(
+            vec!["half_marathon".to_string(), "running".to_string()],
+            &track_metrics,
+        );
+
+        assert_eq!(response.classifications.len(), 2);
+        assert!(response.confidence.contains_key("half_marathon"));
+        assert!(response.reasoning.contains_key("half_marathon"));
+        assert!(!response.confidence.contains_key("running"));
+    }
+
+    #[test]
+    fn poi_request_prefers_geojson_geometry() {
+        let request = |body: serde_json::Value| -> CreatePoiRequest {
+            serde_json::from_value(body).expect("valid request body")
+        };
+
+        let flat = request(json!({"name": "Spring", "lat": 55.75, "lon": 37.61}));
+        assert_eq!(poi_request_coordinates(&flat), Ok((55.75, 37.61)));
+
+        let geojson = request(json!({
+            "name": "Spring",
+            "lat": 1.0,
+            "lon": 1.0,
+            "geometry": {"type": "Point", "coordinates": [37.61, 55.75]}
+        }));
+        assert_eq!(poi_request_coordinates(&geojson), Ok((55.75, 37.61)));
+
+        for geometry in [
+            json!({"type": "LineString", "coordinates": [[37.61, 55.75], [37.62, 55.76]]}),
+            json!({"type": "Point", "coordinates": [37.61]}),
+            json!({"type": "Point", "coordinates": ["37.61", "55.75"]}),
+            json!({"type": "Point", "coordinates": [200.0, 55.75]}),
+        ] {
+            let malformed = request(json!({"name": "Spring", "geometry": geometry}));
+            assert_eq!(
+                poi_request_coordinates(&malformed),
+                Err(StatusCode::BAD_REQUEST)
+            );
+        }
+
+        let missing = request(json!({"name": "Spring", "lat": 55.75}));
+        assert_eq!(
+            poi_request_coordinates(&missing),
+            Err(StatusCode::BAD_REQUEST)
+        );
+    }
+
+    #[test]
+    fn poi_filters_combine_bbox_categories_and_track() {
+        let params = PoiQuery {
+            bbox: Some("1,2,3,4".to_string()),
+            categories: Some(vec!["spring".to_string(), "summit".to_string()]),
+            track_id: Some(Uuid::nil()),
+            search: None,
+            limit: None,
+            offset: None,
+        };
+        let mut query = QueryBuilder::<Postgres>::new(build_poi_select_sql());
+        push_poi_filters(&mut query, &params, Some([1.0, 2.0, 3.0, 4.0]));
+        let sql = query.sql();
+
+        assert!(sql.contains("JOIN track_pois tp ON p.id = tp.poi_id AND tp.track_id = $1"));
+        assert!(sql.contains("ST_MakeEnvelope($2, $3, $4, $5, 4326)"));
+        assert!(sql.contains("p.category = ANY($6)"));
+    }
+
+    #[test]
+    fn share_tokens_are_random_hex() {
+        let token = generate_share_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(token.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_ne!(token, generate_share_token().unwrap());
+    }
+
+    #[test]
+    fn poi_count_uses_list_filters() {
+        let params = PoiQuery {
+            bbox: Some("1,2,3,4".to_string()),
+            categories: None,
+            track_id: Some(Uuid::nil()),
+            search: None,
+            limit: None,
+            offset: None,
+        };
+        let count = build_poi_count_query(&params, Some([1.0, 2.0, 3.0, 4.0]));
+        let sql = count.sql();
+
+        assert!(sql.starts_with("SELECT COUNT(*) FROM pois p JOIN track_pois tp"));
+        assert!(sql.contains("ST_MakeEnvelope($2, $3, $4, $5, 4326)"));
+    }
+
+    #[test]
+    fn json_with_etag_returns_not_modified_for_matching_tag() {
+        let body = serde_json::json!({"id": 1, "name": "Loop"});
+        let first = json_with_etag(&HeaderMap::new(), &body).unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(ETAG).unwrap().clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, etag.clone());
+        let cached = json_with_etag(&headers, &body).unwrap();
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(cached.headers().get(ETAG), Some(&etag));
+        for response in [&first, &cached] {
+            assert_eq!(response.headers().get(CACHE_CONTROL).unwrap(), "private");
+            assert_eq!(response.headers().get(VARY).unwrap(), "x-session-id");
+        }
+
+        let changed = serde_json::json!({"id": 1, "name": "Renamed loop"});
+        assert_eq!(
+            json_with_etag(&headers, &changed).unwrap().status(),
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn parse_track_id_list_dedupes_and_limits() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        assert_eq!(
+            parse_track_id_list(&format!(" {a}, {b},{a},")),
+            Ok(vec![a, b])
+        );
+        assert_eq!(parse_track_id_list(""), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            parse_track_id_list(&format!("{a},not-a-uuid")),
+            Err(StatusCode::BAD_REQUEST)
+        );
+        let too_many: Vec<String> = (0..=MAX_COLLECTION_EXPORT_TRACKS)
+            .map(|_| Uuid::new_v4().to_string())
+            .collect();
+        assert_eq!(
+            parse_track_id_list(&too_many.join(",")),
+            Err(StatusCode::BAD_REQUEST)
+        );
+    }
+
+    #[test]
+    fn admin_gate_requires_configured_session() {
+        let admin = Uuid::new_v4();
+        assert_eq!(check_admin_session(Some(admin), admin), Ok(()));
+        assert_eq!(
+            check_admin_session(Some(admin), Uuid::new_v4()),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(check_admin_session(None, admin), Err(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn accept_language_is_ordered_by_quality() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT_LANGUAGE,
+            "fr;q=0.5, de-ch, *;q=0.1, en;q=0.8, xx-!!, es;q=0"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(parse_accept_language(&headers), vec!["de-CH", "en", "fr"]);
+        assert!(parse_accept_language(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn best_translation_prefers_exact_then_primary_language() {
+        let translation = |code: &str| TrackTranslation {
+            language_code: code.to_string(),
+            name: format!("name-{code}"),
+            description: None,
+        };
+        let translations = vec![translation("de"), translation("pt-BR"), translation("fr")];
+        let pick = |accepted: &[&str]| {
+            let accepted: Vec<String> = accepted.iter().map(|s| s.to_string()).collect();
+            best_translation(&translations, &accepted).map(|t| t.language_code.clone())
+        };
+
+        assert_eq!(pick(&["fr", "de"]).as_deref(), Some("fr"));
+        assert_eq!(pick(&["de-CH"]).as_deref(), Some("de"));
+        assert_eq!(pick(&["pt"]).as_deref(), Some("pt-BR"));
+        assert_eq!(pick(&["it", "de"]).as_deref(), Some("de"));
+        assert_eq!(pick(&["it"]), None);
+    }
+
+    #[test]
+    fn upload_idempotency_key_is_scoped_by_session_and_payload() {
+        let session = Some(Uuid::new_v4());
+        let categories = vec!["hiking".to_string()];
+        let key = |session, file: &[u8], name| {
+            upload_idempotency_cache_key("retry-42", session, file, name, None, &categories)
+        };
+
+        let base = key(session, b"<gpx/>", Some("Ridge"));
+        assert_eq!(base, key(session, b"<gpx/>", Some("Ridge")));
+        assert!(base.starts_with("upload_track:") && base.ends_with(":retry-42"));
+        assert_ne!(base, key(Some(Uuid::new_v4()), b"<gpx/>", Some("Ridge")));
+        assert_ne!(base, key(None, b"<gpx/>", Some("Ridge")));
+        assert_ne!(base, key(session, b"<gpx></gpx>", Some("Ridge")));
+        assert_ne!(base, key(session, b"<gpx/>", Some("Valley")));
+    }
+
+    #[test]
+    fn existing_track_details_hidden_from_other_sessions() {
+        let owner = Uuid::new_v4();
+        let track = |is_public| db::ExistingTrack {
+            id: Uuid::nil(),
+            name: "Dawn Patrol".to_string(),
+            length_km: 12.5,
+            created_at: None,
+            is_public,
+            session_id: Some(owner),
+        };
+
+        let hidden = existing_track_response(track(false), Some(Uuid::new_v4()));
+        assert!(hidden.is_exist);
+        assert_eq!(hidden.id, Some(Uuid::nil()));
+        assert_eq!(
+            serde_json::to_value(&hidden).unwrap(),
+            json!({"is_exist": true, "id": Uuid::nil()})
+        );
+
+        let own = existing_track_response(track(false), Some(owner));
+        assert_eq!(own.name.as_deref(), Some("Dawn Patrol"));
+        let public = existing_track_response(track(true), None);
+        assert_eq!(public.length_km, Some(12.5));
+    }
+
+    #[test]
+    fn parse_idempotency_key_validates_header() {
+        assert_eq!(parse_idempotency_key(&HeaderMap::new()), Ok(None));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("idempotency-key", " retry-42 ".parse().unwrap());
+        assert_eq!(
+            parse_idempotency_key(&headers),
+            Ok(Some("retry-42".to_string()))
+        );
+
+        for bad in ["", "has space", &"k".repeat(MAX_IDEMPOTENCY_KEY_LENGTH + 1)] {
+            headers.insert("idempotency-key", bad.parse().unwrap());
+            assert_eq!(
+                parse_idempotency_key(&headers),
+                Err(StatusCode::BAD_REQUEST)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn enrichment_progress_stream_ends_after_completion() {
+        let track_id = Uuid::new_v4();
+        let reporter = ProgressReporter::start(track_id, 100);
+        let (tx, mut rx) = mpsc::channel(16);
+        let task = tokio::spawn(stream_enrichment_progress(
+            enrichment_progress::subscribe(track_id),
+            tx,
+            ENRICHMENT_STATUS_TIMEOUT,
+        ));
+
+        assert!(rx.recv().await.is_some()); // initial progress
+        reporter.complete(Some(50.0), Some(40.0));
+        let mut events = 1;
+        while rx.recv().await.is_some() {
+            events += 1;
+        }
+        assert_eq!(events, 2);
+        task.await.unwrap();
+
+        // Nothing running: one idle event, then the stream closes
+        let (tx, mut rx) = mpsc::channel(16);
+        stream_enrichment_progress(None, tx, ENRICHMENT_STATUS_TIMEOUT).await;
+        assert!(rx.recv().await.is_some());
+        assert!(rx.recv().await.is_none());
+    }
+
+    /// Multipart extractor over form fields given as `(name, filename, value)`
+    async fn multipart_form(fields: &[(&str, Option<&str>, &str)]) -> AxumMultipart {
+        use axum::extract::FromRequest;
+        let mut body = String::new();
+        for (name, filename, value) in fields {
+            body.push_str("--BOUNDARY\r\n");
+            match filename {
+                Some(filename) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\n\r\n"
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{name}\"\r\n\r\n"
+                )),
+            }
+            body.push_str(value);
+            body.push_str("\r\n");
+        }
+        body.push_str("--BOUNDARY--\r\n");
+        let request = axum::http::Request::builder()
+            .header(CONTENT_TYPE, "multipart/form-data; boundary=BOUNDARY")
+            .body(axum::body::Body::from(body))
+            .unwrap();
+        AxumMultipart::from_request(request, &()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn batch_upload_requires_session_and_counts_only_valid_batches() {
+        // Rejected before any query, so a lazy pool is never connected
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
+        );
+        let gpx = "<gpx></gpx>";
+
+        let without_session = multipart_form(&[
+            ("categories", None, "running"),
+            ("file", Some("a.gpx"), gpx),
+        ])
+        .await;
+        let res = batch_upload_tracks(State(pool.clone()), without_session).await;
+        assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+
+        // A batch failing validation does not use up the session's upload quota
+        let session = Uuid::new_v4().to_string();
+        let without_categories =
+            multipart_form(&[("session_id", None, &session), ("file", Some("a.gpx"), gpx)]).await;
+        let res = batch_upload_tracks(State(pool.clone()), without_categories).await;
+        assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        assert!(!LAST_UPLOAD.read().await.contains_key(&session));
+    }
+
+    #[tokio::test]
+    async fn list_tracks_geojson_rejects_non_finite_filters() {
+        // Rejected before any query, so a lazy pool is never connected
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
+        );
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut params: TrackGeoJsonQuery = serde_json::from_str("{}").unwrap();
+            params.slope_min = Some(bad);
+            let res = list_tracks_geojson(State(pool.clone()), Query(params)).await;
+            assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        }
+    }
+
+    #[tokio::test]
+    async fn clone_track_rejects_blank_or_oversized_names() {
+        // Rejected before any query, so a lazy pool is never connected
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
+        );
+        for name in [
+            "   ".to_string(),
+            "\u{7}\n\t".to_string(),
+            "x".repeat(MAX_NAME_LENGTH + 1),
+        ] {
+            let payload = CloneTrackRequest {
+                name,
+                session_id: Uuid::new_v4(),
+            };
+            let res = clone_track(State(pool.clone()), Path(Uuid::new_v4()), Json(payload)).await;
+            assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        }
+    }
 
-    #[test]
-    fn normalize_session_id_accepts_trimmed_uuid() {
-        let raw = " 11111111-1111-4111-8111-111111111111 \n";
-        let (uuid, normalized) = normalize_session_id(raw).expect("should parse");
+    #[tokio::test]
+    async fn search_rejects_similarity_threshold_out_of_range() {
+        // Rejected before any query, so a lazy pool is never connected
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
+        );
+        let peer: SocketAddr = "192.0.2.44:50000".parse().unwrap();
+        for bad in [-0.1, 1.5] {
+            let params: TrackSearchQuery =
+                serde_json::from_value(json!({ "query": "ridge", "similarity_threshold": bad }))
+                    .unwrap();
+            let res = search_tracks(
+                State(pool.clone()),
+                ConnectInfo(peer),
+                Query(params),
+                HeaderMap::new(),
+            )
+            .await;
+            assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        }
+    }
 
-        assert_eq!(
-            uuid,
-            Uuid::parse_str("11111111-1111-4111-8111-111111111111").unwrap()
+    #[tokio::test]
+    async fn bulk_delete_rejects_empty_and_oversized_requests() {
+        // Validation happens before any query, so a lazy pool is never connected
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
         );
-        assert_eq!(normalized, "11111111-1111-4111-8111-111111111111");
+        for count in [0, MAX_BULK_DELETE_TRACKS + 1] {
+            let payload = BulkDeleteTracksRequest {
+                track_ids: (0..count).map(|_| Uuid::new_v4()).collect(),
+                session_id: Uuid::new_v4(),
+            };
+            let res = bulk_delete_tracks(State(pool.clone()), Json(payload)).await;
+            assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        }
     }
 
-    #[test]
-    fn normalize_session_id_rejects_empty_field() {
-        let err = normalize_session_id("   ").unwrap_err();
-        assert_eq!(err, StatusCode::BAD_REQUEST);
+    #[tokio::test]
+    async fn bulk_delete_pois_rejects_empty_and_oversized_requests() {
+        let pool = Arc::new(
+            sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://localhost/unused")
+                .unwrap(),
+        );
+        for count in [0, MAX_BULK_DELETE_POIS + 1] {
+            let payload = BulkDeletePoisRequest {
+                poi_ids: (0..count as i32).collect(),
+                session_id: Uuid::new_v4(),
+            };
+            let res = bulk_delete_pois(State(pool.clone()), Json(payload)).await;
+            assert!(matches!(res, Err(StatusCode::BAD_REQUEST)));
+        }
     }
 
     #[test]
-    fn normalize_session_id_rejects_invalid_uuid() {
-        let err = normalize_session_id("not-a-uuid").unwrap_err();
-        assert_eq!(err, StatusCode::BAD_REQUEST);
+    fn auto_classification_overrides_are_normalized() {
+        let labels = vec![
+            "trail".to_string(),
+            " trail_running ".to_string(),
+            "hiking".to_string(),
+        ];
+        assert_eq!(
+            normalize_auto_classifications(&labels).unwrap(),
+            vec!["trail_running".to_string(), "hiking".to_string()]
+        );
+        assert_eq!(
+            normalize_auto_classifications(&["skydiving".to_string()]),
+            Err(StatusCode::BAD_REQUEST)
+        );
     }
 
-    #[test]
-    fn record_session_upload_allows_first_attempt() {
+    #[tokio::test]
+    async fn record_session_upload_allows_first_attempt() {
         reset_rate_limit_state();
-        record_session_upload_attempt("session", 100).expect("first upload should pass");
+        record_session_upload_attempt("session", 100)
+            .await
+            .expect("first upload should pass");
     }
 
-    #[test]
-    fn record_session_upload_blocks_fast_retries() {
+    #[tokio::test]
+    async fn record_session_upload_uses_sliding_window() {
         reset_rate_limit_state();
-        record_session_upload_attempt("session", 200).expect("initial upload ok");
+        let key = "session-sliding-window";
+        let window = *UPLOAD_RATE_LIMIT_SECONDS;
+        let limit = *UPLOAD_RATE_LIMIT_COUNT as u64;
+
+        for i in 0..limit {
+            record_session_upload_attempt(key, 1000 + i)
+                .await
+                .expect("uploads within the limit should pass");
+        }
 
-        let err = record_session_upload_attempt("session", 205).expect_err("should rate limit");
+        // One more upload inside the window is rejected
+        let err = record_session_upload_attempt(key, 1000 + limit)
+            .await
+            .expect_err("should rate limit");
         assert_eq!(err, StatusCode::TOO_MANY_REQUESTS);
 
-        // After enough time passes, uploads are allowed again
-        record_session_upload_attempt("session", 212).expect("rate limit window expired");
+        // Once the oldest upload leaves the window, the next one is accepted
+        record_session_upload_attempt(key, 1000 + window)
+            .await
+            .expect("oldest attempt evicted from window");
+    }
+
+    #[test]
+    fn purge_stale_upload_attempts_drops_idle_sessions() {
+        let window = *UPLOAD_RATE_LIMIT_SECONDS;
+        let now = 10_000_000 + window * 10;
+        let mut map = HashMap::from([
+            ("idle".to_string(), VecDeque::from([now - window * 2 - 1])),
+            (
+                "recent".to_string(),
+                VecDeque::from([now - window * 3, now - 1]),
+            ),
+            ("empty".to_string(), VecDeque::new()),
+        ]);
+
+        assert_eq!(purge_stale_upload_attempts(&mut map, now), 2);
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["recent"]);
+    }
+
+    #[test]
+    fn extract_line_geometry_accepts_lines_and_features() {
+        let line = json!({"type": "LineString", "coordinates": [[37.0, 55.0], [37.1, 55.1]]});
+        assert!(extract_line_geometry(&line).is_some());
+
+        let feature = json!({"type": "Feature", "geometry": line, "properties": {}});
+        assert_eq!(
+            extract_line_geometry(&feature).and_then(|g| g.get("type")),
+            Some(&json!("LineString"))
+        );
+
+        let point = json!({"type": "Point", "coordinates": [37.0, 55.0]});
+        assert!(extract_line_geometry(&point).is_none());
     }
 
     #[test]
@@ -1201,9 +4510,12 @@ mod tests {
             elevation_profile: Some(serde_json::json!(elevation)),
             hr_data: Some(serde_json::json!(hr)),
             temp_data: Some(serde_json::json!(temp)),
+            cadence_data: None,
+            power_data: None,
             time_data: None,
             segment_gaps: None,
             pause_gaps: None,
+            segments: None,
             elevation_gain: Some(100.0),
             elevation_loss: Some(90.0),
             elevation_min: Some(200.0),
@@ -1221,15 +4533,29 @@ mod tests {
             avg_hr: Some(130),
             hr_min: Some(110),
             hr_max: Some(170),
+            cadence_avg: None,
+            cadence_min: None,
+            cadence_max: None,
+            power_avg: None,
+            power_max: None,
+            power_normalized: None,
             moving_time: Some(3600),
             pause_time: Some(0),
             moving_avg_speed: Some(10.5),
             moving_avg_pace: Some(5.7),
+            moving_distance_km: Some(10.2),
+            is_loop: false,
+            elevation_source: Some("barometric".to_string()),
+            classification_confidence: None,
             duration_seconds: Some(3700),
             recorded_at: None,
             created_at: None,
             updated_at: None,
             session_id: None,
+            metadata_author: None,
+            metadata_link: None,
+            metadata_copyright: None,
+            is_public: true,
             speed_data: Some(json!([8.0, 9.0, 10.0, 11.0])),
             pace_data: Some(json!([7.5, 6.7, 6.0, 5.5])),
         };
@@ -1279,6 +4605,50 @@ mod tests {
         assert_eq!(temp_simpl.as_array().unwrap().len(), simplified.len());
     }
 
+    #[test]
+    fn recalculate_slopes_request_rejects_empty_body() {
+        let payload = Json::<RecalculateSlopesRequest>::from_bytes(b"{}");
+        let err = parse_recalculate_slopes_request(payload).unwrap_err();
+        assert_eq!(err, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn recalculate_slopes_request_rejects_nil_session() {
+        let payload = Json::<RecalculateSlopesRequest>::from_bytes(
+            br#"{"session_id":"00000000-0000-0000-0000-000000000000"}"#,
+        );
+        let err = parse_recalculate_slopes_request(payload).unwrap_err();
+        assert_eq!(err, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn recalculate_slopes_request_skips_only_on_opt_in() {
+        let session = Uuid::new_v4();
+        let request = |body: serde_json::Value| {
+            let mut body = body;
+            body["session_id"] = json!(session);
+            parse_recalculate_slopes_request(Json::from_bytes(body.to_string().as_bytes())).unwrap()
+        };
+        assert!(!request(json!({})).skips_calculated_track());
+        assert!(!request(json!({"skip_if_calculated": false})).skips_calculated_track());
+        assert!(request(json!({"skip_if_calculated": true})).skips_calculated_track());
+    }
+
+    #[test]
+    fn recalculate_slopes_request_with_window_always_recalculates() {
+        let session = Uuid::new_v4();
+        for body in [
+            json!({"session_id": session, "smoothing_window_m": 40.0}),
+            json!({"session_id": session, "smoothing_window_m": 40.0, "skip_if_calculated": true}),
+        ] {
+            let request =
+                parse_recalculate_slopes_request(Json::from_bytes(body.to_string().as_bytes()))
+                    .unwrap();
+            assert_eq!(request.smoothing_window_m, Some(40.0));
+            assert!(!request.skips_calculated_track());
+        }
+    }
+
     // Integration tests would go here for testing the full enrich_elevation handler
     // However, they require database setup and external API mocking, so we'll
     // keep them in the existing test files under tests/ directory for now
@@ -1314,6 +4684,130 @@ mod tests {
         assert_eq!(segment.slope, 5.5);
     }
 
+    #[test]
+    fn test_heatmap_grid_size_degrees() {
+        let bbox = Some([10.0, 40.0, 12.0, 42.0]);
+        assert!((heatmap_grid_size_degrees(bbox, None, 100) - 0.02).abs() < 1e-12);
+        // A tall bbox is split along its latitude span
+        let tall = Some([10.0, 0.0, 10.5, 50.0]);
+        assert!((heatmap_grid_size_degrees(tall, None, 100) - 0.5).abs() < 1e-12);
+        // Without a bbox the whole world is queried, so zoom cannot shrink the cells
+        assert!((heatmap_grid_size_degrees(None, Some(20.0), 90) - 4.0).abs() < 1e-12);
+        assert!((heatmap_grid_size_degrees(None, Some(f64::NAN), 90) - 4.0).abs() < 1e-12);
+        // Zoom coarsens a grid over a bbox narrower than one tile
+        assert!((heatmap_grid_size_degrees(bbox, Some(1.0), 90) - 2.0).abs() < 1e-12);
+        assert_eq!(
+            heatmap_grid_size_degrees(Some([10.0, 40.0, 10.0001, 40.0001]), None, 1000),
+            MIN_HEATMAP_GRID_DEGREES
+        );
+    }
+
+    #[test]
+    fn test_heatmap_cache_evicts_expired_and_least_recently_used() {
+        let ttl = std::time::Duration::from_secs(60);
+        let collection = || TrackGeoJsonCollection {
+            type_field: "FeatureCollection".to_string(),
+            features: vec![],
+            total: 0,
+            warning: None,
+        };
+        let mut cache = HeatmapCache::new(2);
+        cache.insert("a".to_string(), collection(), ttl);
+        cache.insert("b".to_string(), collection(), ttl);
+        assert!(cache.get("a", ttl).is_some());
+        cache.insert("c".to_string(), collection(), ttl);
+        assert!(cache.get("b", ttl).is_none());
+        assert!(cache.get("a", ttl).is_some());
+        assert!(cache.get("c", ttl).is_some());
+
+        assert!(cache.get("a", std::time::Duration::ZERO).is_none());
+        assert!(!cache.entries.contains_key("a"));
+    }
+
+    #[test]
+    fn test_slope_data_status() {
+        let segments = json!([]);
+        assert_eq!(
+            SlopeDataStatus::of(Some(&segments), Some(false), false),
+            SlopeDataStatus::Available
+        );
+        assert_eq!(
+            SlopeDataStatus::of(None, Some(true), false),
+            SlopeDataStatus::Pending
+        );
+        assert_eq!(
+            SlopeDataStatus::of(None, Some(true), true),
+            SlopeDataStatus::Failed
+        );
+        assert_eq!(
+            SlopeDataStatus::of(None, Some(false), false),
+            SlopeDataStatus::Unavailable
+        );
+        assert_eq!(
+            SlopeDataStatus::of(None, None, false),
+            SlopeDataStatus::Unavailable
+        );
+    }
+
+    #[test]
+    fn test_health_status() {
+        assert_eq!(health_status(false, 0, 0, 5), "unhealthy");
+        assert_eq!(health_status(true, 5, 0, 5), "degraded");
+        assert_eq!(health_status(true, 5, 1, 5), "healthy");
+        assert_eq!(health_status(true, 2, 0, 5), "healthy");
+    }
+
+    #[test]
+    fn test_split_suggestions_orders_by_severity() {
+        fn gap(kind: &str, distance_m: f64, duration_seconds: Option<i64>) -> GapInfo {
+            let endpoint = |point_index| GapEndpoint {
+                lat: 0.0,
+                lon: 0.0,
+                segment_index: 0,
+                point_index,
+            };
+            GapInfo {
+                kind: kind.to_string(),
+                from: endpoint(0),
+                to: endpoint(1),
+                distance_m,
+                duration_seconds,
+            }
+        }
+
+        let suggestions = split_suggestions(
+            Some(vec![
+                gap("segment", 800.0, None),
+                gap("segment", 2500.0, None),
+            ]),
+            Some(vec![
+                gap("pause", 5.0, Some(240)),
+                gap("pause", 10.0, Some(900)),
+                gap("pause", 3.0, Some(300)),
+            ]),
+            300,
+        );
+
+        let summary: Vec<(&str, f64)> = suggestions
+            .iter()
+            .map(|s| (s.kind.as_str(), s.distance_m))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("segment", 2500.0),
+                ("segment", 800.0),
+                ("pause", 10.0),
+                ("pause", 3.0)
+            ]
+        );
+
+        let json = serde_json::to_value(&suggestions[2]).unwrap();
+        assert_eq!(json["type"], "pause");
+        assert_eq!(json["duration_seconds"], 900);
+        assert!(split_suggestions(None, None, 300).is_empty());
+    }
+
     #[test]
     fn test_slope_profile_point_creation() {
         let segment = SlopeSegment {
@@ -1380,103 +4874,677 @@ mod tests {
         assert_eq!(profile[2].length_m, 50.0);
     }
 
-    #[test]
-    fn test_slope_profile_point_vec_serialization() {
-        let points = vec![
-            SlopeProfilePoint {
-                distance_m: 0.0,
-                slope_percent: 5.0,
-                length_m: 100.0,
-            },
-            SlopeProfilePoint {
-                distance_m: 100.0,
-                slope_percent: -2.5,
-                length_m: 150.0,
+    #[test]
+    fn test_slope_profile_point_vec_serialization() {
+        let points = vec![
+            SlopeProfilePoint {
+                distance_m: 0.0,
+                slope_percent: 5.0,
+                length_m: 100.0,
+            },
+            SlopeProfilePoint {
+                distance_m: 100.0,
+                slope_percent: -2.5,
+                length_m: 150.0,
+            },
+        ];
+
+        let json = serde_json::to_string(&points).unwrap();
+        let deserialized: Vec<SlopeProfilePoint> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.len(), 2);
+        assert_eq!(deserialized[0].distance_m, 0.0);
+        assert_eq!(deserialized[1].slope_percent, -2.5);
+    }
+
+    #[test]
+    fn test_empty_slope_segments() {
+        let empty_segments: Vec<SlopeSegment> = vec![];
+        let profile: Vec<SlopeProfilePoint> = empty_segments
+            .into_iter()
+            .map(|segment| SlopeProfilePoint {
+                distance_m: segment.start_distance,
+                slope_percent: segment.slope,
+                length_m: segment.end_distance - segment.start_distance,
+            })
+            .collect();
+
+        assert!(profile.is_empty());
+    }
+
+    #[test]
+    fn test_build_pace_chart_aligns_pace_with_distance() {
+        let start = chrono::Utc::now();
+        let times = (0..5)
+            .map(|i| Some(start + chrono::Duration::seconds(i * 30)))
+            .collect();
+        let chart = build_pace_chart(db::TrackPaceSeries {
+            distance_m: vec![0.0, 100.0, 200.0, 300.0],
+            pace: vec![None, Some(5.0), Some(5.2), Some(4.9), Some(5.1)],
+            times,
+        });
+
+        // Extra pace values beyond the geometry are dropped
+        assert_eq!(chart.distance_m, vec![0.0, 100.0, 200.0, 300.0]);
+        assert_eq!(chart.pace_min_per_km.len(), 4);
+        assert_eq!(chart.pace_min_per_km[0], None);
+        assert!(chart.pace_min_per_km[1..].iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn test_build_elevation_histogram_bins_points_and_distance() {
+        let elevations = [Some(100.0), Some(120.0), None, Some(190.0), Some(200.0)];
+        let distances = [0.0, 50.0, 100.0, 180.0, 200.0];
+        let buckets = build_elevation_histogram(&elevations, &distances, 100.0, 200.0, 4);
+
+        assert_eq!(buckets.len(), 4);
+        assert_eq!((buckets[0].from_m, buckets[0].to_m), (100.0, 125.0));
+        assert_eq!(buckets[3].to_m, 200.0);
+        assert_eq!(
+            buckets.iter().map(|b| b.count).collect::<Vec<_>>(),
+            vec![2, 0, 0, 2]
+        );
+        // 0-50 m from the first point, 50-100 m from the second; the last point has no next
+        assert_eq!(buckets[0].distance_m, 100.0);
+        assert_eq!(buckets[3].distance_m, 20.0);
+
+        let flat =
+            build_elevation_histogram(&[Some(50.0), Some(50.0)], &[0.0, 10.0], 50.0, 50.0, 3);
+        assert_eq!(flat[0].count, 2);
+    }
+
+    #[test]
+    fn test_build_3d_geometry_resamples_profile() {
+        let geom = json!({
+            "type": "LineString",
+            "coordinates": [[37.0, 55.0], [37.1, 55.0], [37.2, 55.0], [37.3, 55.0], [37.4, 55.0]]
+        });
+        // Profile from before simplification, with a gap
+        let profile = [Some(100.0), None, Some(140.0)];
+        let geometry = build_3d_geometry(&geom, &profile).unwrap();
+
+        assert_eq!(geometry["type"], "LineString");
+        let z: Vec<f64> = geometry["coordinates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c[2].as_f64().unwrap())
+            .collect();
+        assert_eq!(z, vec![100.0, 110.0, 120.0, 130.0, 140.0]);
+        assert_eq!(geometry["coordinates"][1][0], 37.1);
+
+        assert!(build_3d_geometry(&geom, &[]).is_none());
+        assert!(build_3d_geometry(&geom, &[None, None]).is_none());
+    }
+
+    #[test]
+    fn test_build_power_zones_bounds_and_percentages() {
+        let response = build_power_zones(200, [30, 10, 0, 0, 0, 0, 10]);
+        assert_eq!(response.total_seconds, 50);
+        assert_eq!(response.zones.len(), 7);
+
+        let z1 = &response.zones[0];
+        assert_eq!((z1.min_watts, z1.max_watts), (0, Some(110)));
+        assert_eq!(z1.percent, 60.0);
+        assert_eq!(response.zones[1].min_watts, 111);
+
+        let z7 = &response.zones[6];
+        assert_eq!((z7.zone, z7.min_watts, z7.max_watts), (7, 301, None));
+        assert_eq!(z7.percent, 20.0);
+    }
+
+    #[test]
+    fn test_slope_segment_edge_cases() {
+        // Test zero-length segment
+        let zero_segment = SlopeSegment {
+            start_distance: 100.0,
+            end_distance: 100.0,
+            slope: 5.0,
+        };
+
+        let point = SlopeProfilePoint {
+            distance_m: zero_segment.start_distance,
+            slope_percent: zero_segment.slope,
+            length_m: zero_segment.end_distance - zero_segment.start_distance,
+        };
+
+        assert_eq!(point.length_m, 0.0);
+
+        // Test extreme slope values
+        let extreme_segment = SlopeSegment {
+            start_distance: 0.0,
+            end_distance: 100.0,
+            slope: 60.0, // Maximum expected slope
+        };
+
+        let extreme_point = SlopeProfilePoint {
+            distance_m: extreme_segment.start_distance,
+            slope_percent: extreme_segment.slope,
+            length_m: extreme_segment.end_distance - extreme_segment.start_distance,
+        };
+
+        assert_eq!(extreme_point.slope_percent, 60.0);
+    }
+}
+
+// Enrichment status streams are closed after this long even if the run never finishes
+const ENRICHMENT_STATUS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+fn enrichment_event(state: &EnrichmentProgress) -> Event {
+    Event::default()
+        .event(state.event_name())
+        .data(serde_json::to_string(state).unwrap_or_default())
+}
+
+/// Forward enrichment progress to an SSE client until the run finishes, the client
+/// disconnects or the timeout passes. Without a running enrichment a single `idle` event
+/// is sent.
+async fn stream_enrichment_progress(
+    progress: Option<watch::Receiver<EnrichmentProgress>>,
+    tx: mpsc::Sender<Result<Event, Infallible>>,
+    timeout: std::time::Duration,
+) {
+    let Some(mut progress) = progress else {
+        let _ = tx
+            .send(Ok(enrichment_event(&EnrichmentProgress::Idle)))
+            .await;
+        return;
+    };
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    loop {
+        let state = progress.borrow_and_update().clone();
+        if tx.send(Ok(enrichment_event(&state))).await.is_err() || state.is_finished() {
+            return;
+        }
+        tokio::select! {
+            changed = progress.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+            _ = tx.closed() => return,
+            _ = &mut deadline => return,
+        }
+    }
+}
+
+/// GET /tracks/{id}/enrichment-status - server-sent progress of a running elevation enrichment
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/enrichment-status",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Stream of `progress` events followed by a `complete` or `failed` event, or a single `idle` event when no enrichment is running", body = EnrichmentProgress, content_type = "text/event-stream"),
+        (status = 404, description = "Track not found or not visible"),
+    ),
+    tag = "elevation",
+)]
+pub async fn get_track_enrichment_status(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Sse<KeepAliveStream<ReceiverStream<Result<Event, Infallible>>>>, StatusCode> {
+    // Progress events carry elevation results, so private tracks stay hidden from others
+    let session_id = parse_session_header(&headers);
+    match db::get_track_detail(&pool, id).await {
+        Ok(Some(track)) if can_view_track(track.is_public, track.session_id, session_id) => {}
+        Ok(_) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_enrichment_status", "db error");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(stream_enrichment_progress(
+        enrichment_progress::subscribe(id),
+        tx,
+        ENRICHMENT_STATUS_TIMEOUT,
+    ));
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default()))
+}
+
+/// GET /tracks/{id}/elevation-profile - lightweight elevation chart data with distance axis
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/elevation-profile",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Elevation chart data", body = ElevationProfileResponse),
+        (status = 404, description = "Track not found or has no elevation data"),
+    ),
+    tag = "elevation",
+)]
+pub async fn get_track_elevation_profile(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ElevationProfileResponse>, StatusCode> {
+    let session_id = parse_session_header(&headers);
+    match db::get_track_elevation_profile(&pool, id, session_id).await {
+        Ok(Some(profile)) => Ok(Json(profile)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_elevation_profile", "db error");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Align stored pace with the distance axis and run it through the track-wide pace filter.
+/// Stored pace carries no activity type, so the filter falls back to speed-based detection
+/// with speeds derived from the pace itself.
+fn build_pace_chart(series: db::TrackPaceSeries) -> PaceChartResponse {
+    let len = series.distance_m.len().min(series.pace.len());
+    let pace = &series.pace[..len];
+    let speeds: Vec<Option<f64>> = pace
+        .iter()
+        .map(|p| p.filter(|p| *p > 0.0).map(|p| 60.0 / p))
+        .collect();
+    let time_diffs: Vec<Option<f64>> = (0..len)
+        .map(|i| {
+            let current = series.times.get(i).copied().flatten()?;
+            let previous = series.times.get(i.checked_sub(1)?).copied().flatten()?;
+            Some((current - previous).num_milliseconds() as f64 / 1000.0)
+        })
+        .collect();
+
+    let mut distance_m = series.distance_m;
+    distance_m.truncate(len);
+    PaceChartResponse {
+        distance_m,
+        pace_min_per_km: filter_pace_data(pace, &speeds, &time_diffs, None, &[]),
+    }
+}
+
+/// GET /tracks/{id}/pace-chart - filtered pace with a server-side distance axis
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/pace-chart",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Pace chart data", body = PaceChartResponse),
+        (status = 404, description = "Track not found or has no pace data"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_pace_chart(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<PaceChartResponse>, StatusCode> {
+    let session_id = parse_session_header(&headers);
+    let series = match db::get_track_pace_series(&pool, id, session_id).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_pace_chart", "db error");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if !series.pace.iter().any(Option::is_some) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(build_pace_chart(series)))
+}
+
+const DEFAULT_ELEVATION_HISTOGRAM_BUCKETS: usize = 20;
+const MAX_ELEVATION_HISTOGRAM_BUCKETS: usize = 100;
+
+/// Bin elevations into `bucket_count` equal-width bands between `min` and `max`. The distance
+/// from each point to the next is credited to the band of the starting point.
+fn build_elevation_histogram(
+    elevations: &[Option<f64>],
+    distances: &[f64],
+    min: f64,
+    max: f64,
+    bucket_count: usize,
+) -> Vec<ElevationHistogramBucket> {
+    let width = (max - min) / bucket_count as f64;
+    let mut buckets: Vec<ElevationHistogramBucket> = (0..bucket_count)
+        .map(|i| ElevationHistogramBucket {
+            from_m: min + width * i as f64,
+            to_m: if i + 1 == bucket_count {
+                max
+            } else {
+                min + width * (i + 1) as f64
             },
-        ];
+            count: 0,
+            distance_m: 0.0,
+        })
+        .collect();
 
-        let json = serde_json::to_string(&points).unwrap();
-        let deserialized: Vec<SlopeProfilePoint> = serde_json::from_str(&json).unwrap();
+    for (i, elevation) in elevations.iter().enumerate() {
+        let Some(elevation) = elevation else {
+            continue;
+        };
+        // Stored min/max can lag behind the profile, so out-of-range values go to the edge bins
+        let index = if width > 0.0 {
+            (((elevation - min) / width).floor().max(0.0) as usize).min(bucket_count - 1)
+        } else {
+            0
+        };
+        buckets[index].count += 1;
+        if let (Some(from), Some(to)) = (distances.get(i), distances.get(i + 1)) {
+            buckets[index].distance_m += to - from;
+        }
+    }
+    buckets
+}
 
-        assert_eq!(deserialized.len(), 2);
-        assert_eq!(deserialized[0].distance_m, 0.0);
-        assert_eq!(deserialized[1].slope_percent, -2.5);
+/// GET /tracks/{id}/elevation-histogram - distribution of points and distance over elevation bands
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/elevation-histogram",
+    params(("id" = Uuid, Path, description = "Track id"), ElevationHistogramQuery, ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Elevation histogram", body = ElevationHistogramResponse),
+        (status = 400, description = "buckets outside 1..=100"),
+        (status = 404, description = "Track not found or has no elevation data"),
+    ),
+    tag = "elevation",
+)]
+pub async fn get_track_elevation_histogram(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ElevationHistogramQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ElevationHistogramResponse>, StatusCode> {
+    let bucket_count = query.buckets.unwrap_or(DEFAULT_ELEVATION_HISTOGRAM_BUCKETS);
+    if !(1..=MAX_ELEVATION_HISTOGRAM_BUCKETS).contains(&bucket_count) {
+        return Err(StatusCode::BAD_REQUEST);
     }
 
-    #[test]
-    fn test_empty_slope_segments() {
-        let empty_segments: Vec<SlopeSegment> = vec![];
-        let profile: Vec<SlopeProfilePoint> = empty_segments
-            .into_iter()
-            .map(|segment| SlopeProfilePoint {
-                distance_m: segment.start_distance,
-                slope_percent: segment.slope,
-                length_m: segment.end_distance - segment.start_distance,
-            })
-            .collect();
+    let session_id = parse_session_header(&headers);
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .filter(|t| can_view_track(t.is_public, t.session_id, session_id))
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let elevations: Vec<Option<f64>> = track
+        .elevation_profile
+        .and_then(|profile| serde_json::from_value(profile).ok())
+        .unwrap_or_default();
+    let profile_range = elevations
+        .iter()
+        .flatten()
+        .fold(None, |range, &e| match range {
+            None => Some((e, e)),
+            Some((lo, hi)) => Some((f64::min(lo, e), f64::max(hi, e))),
+        });
+    let Some((profile_min, profile_max)) = profile_range else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let min = track.elevation_min.map_or(profile_min, f64::from);
+    let max = track.elevation_max.map_or(profile_max, f64::from);
 
-        assert!(profile.is_empty());
-    }
+    let segments = extract_segments_from_geojson(&track.geom_geojson).unwrap_or_default();
+    let distances = cumulative_distances_m(&segments);
+    Ok(Json(ElevationHistogramResponse {
+        buckets: build_elevation_histogram(&elevations, &distances, min, max, bucket_count),
+    }))
+}
 
-    #[test]
-    fn test_slope_segment_edge_cases() {
-        // Test zero-length segment
-        let zero_segment = SlopeSegment {
-            start_distance: 100.0,
-            end_distance: 100.0,
-            slope: 5.0,
-        };
+/// Elevation for each of `count` points, linearly resampled from `profile` so a profile
+/// of a different length (e.g. from before simplification) still spans the whole track.
+/// Missing values are bridged from their neighbours; `None` if there are no values at all.
+fn resample_elevations(profile: &[Option<f64>], count: usize) -> Option<Vec<f64>> {
+    let known: Vec<(usize, f64)> = profile
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| e.map(|e| (i, e)))
+        .collect();
+    let last = *known.last()?;
+    let step = if count > 1 {
+        (profile.len() - 1) as f64 / (count - 1) as f64
+    } else {
+        0.0
+    };
 
-        let point = SlopeProfilePoint {
-            distance_m: zero_segment.start_distance,
-            slope_percent: zero_segment.slope,
-            length_m: zero_segment.end_distance - zero_segment.start_distance,
-        };
+    let elevations = (0..count)
+        .map(|j| {
+            let pos = j as f64 * step;
+            let after = known.partition_point(|&(i, _)| (i as f64) < pos);
+            let (i1, e1) = known.get(after).copied().unwrap_or(last);
+            let (i0, e0) = if after == 0 {
+                known[0]
+            } else {
+                known[after - 1]
+            };
+            if i1 <= i0 {
+                return e0;
+            }
+            e0 + (e1 - e0) * ((pos - i0 as f64) / (i1 - i0) as f64).clamp(0.0, 1.0)
+        })
+        .collect();
+    Some(elevations)
+}
 
-        assert_eq!(point.length_m, 0.0);
+/// Track geometry with elevation as the third coordinate. A single segment becomes a
+/// LineString; tracks split at gaps keep their parts as a MultiLineString.
+fn build_3d_geometry(
+    geom_geojson: &serde_json::Value,
+    profile: &[Option<f64>],
+) -> Option<serde_json::Value> {
+    let segments = extract_segments_from_geojson(geom_geojson).ok()?;
+    let point_count = segments.iter().map(Vec::len).sum();
+    let mut elevations = resample_elevations(profile, point_count)?.into_iter();
+    let mut lines: Vec<Vec<[f64; 3]>> = segments
+        .iter()
+        .map(|segment| {
+            segment
+                .iter()
+                .map(|&(lat, lon)| [lon, lat, elevations.next().unwrap_or_default()])
+                .collect()
+        })
+        .collect();
 
-        // Test extreme slope values
-        let extreme_segment = SlopeSegment {
-            start_distance: 0.0,
-            end_distance: 100.0,
-            slope: 60.0, // Maximum expected slope
-        };
+    Some(if lines.len() == 1 {
+        json!({ "type": "LineString", "coordinates": lines.remove(0) })
+    } else {
+        json!({ "type": "MultiLineString", "coordinates": lines })
+    })
+}
 
-        let extreme_point = SlopeProfilePoint {
-            distance_m: extreme_segment.start_distance,
-            slope_percent: extreme_segment.slope,
-            length_m: extreme_segment.end_distance - extreme_segment.start_distance,
-        };
+/// GET /tracks/{id}/3d-profile - geometry with `[lon, lat, elevation]` coordinates for 3D maps
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/3d-profile",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "GeoJSON LineString (MultiLineString for tracks with gaps) with elevation as Z", body = serde_json::Value),
+        (status = 404, description = "Track not found or has no elevation profile"),
+    ),
+    tag = "elevation",
+)]
+pub async fn get_track_3d_profile(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<axum::response::Response, StatusCode> {
+    let session_id = parse_session_header(&headers);
+    let track = db::get_track_detail(&pool, id)
+        .await
+        .map_err(handle_db_error)?
+        .filter(|t| can_view_track(t.is_public, t.session_id, session_id))
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let profile: Vec<Option<f64>> = track
+        .elevation_profile
+        .and_then(|profile| serde_json::from_value(profile).ok())
+        .unwrap_or_default();
+    match build_3d_geometry(&track.geom_geojson, &profile) {
+        Some(geometry) => Ok(Json(geometry).into_response()),
+        None => Ok((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Track has no elevation profile" })),
+        )
+            .into_response()),
+    }
+}
 
-        assert_eq!(extreme_point.slope_percent, 60.0);
+const MAX_FTP_WATTS: u32 = 2000;
+const POWER_ZONE_NAMES: [&str; 7] = [
+    "Active Recovery",
+    "Endurance",
+    "Tempo",
+    "Threshold",
+    "VO2max",
+    "Anaerobic Capacity",
+    "Neuromuscular Power",
+];
+
+/// Build the zone table with integer watt bounds matching how `power_zone_seconds` bins readings
+fn build_power_zones(ftp: u32, seconds: [u32; 7]) -> PowerZonesResponse {
+    let total_seconds: u32 = seconds.iter().sum();
+    let upper_watts = |i: usize| {
+        POWER_ZONE_UPPER_BOUNDS
+            .get(i)
+            .map(|bound| (bound * ftp as f64).floor() as i32)
+    };
+    let zones = (0..POWER_ZONE_NAMES.len())
+        .map(|i| PowerZone {
+            zone: i as u8 + 1,
+            name: POWER_ZONE_NAMES[i].to_string(),
+            min_watts: if i == 0 {
+                0
+            } else {
+                upper_watts(i - 1).unwrap_or(0) + 1
+            },
+            max_watts: upper_watts(i),
+            seconds: seconds[i],
+            percent: if total_seconds == 0 {
+                0.0
+            } else {
+                (seconds[i] as f64 / total_seconds as f64 * 1000.0).round() / 10.0
+            },
+        })
+        .collect();
+    PowerZonesResponse {
+        ftp,
+        total_seconds,
+        zones,
+    }
+}
+
+/// GET /tracks/{id}/power-zones - time spent in each Coggan power zone for a given FTP
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/power-zones",
+    params(("id" = Uuid, Path, description = "Track id"), PowerZonesQuery, ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Seconds spent in each power zone", body = PowerZonesResponse),
+        (status = 400, description = "FTP outside 1..=2000 watts"),
+        (status = 404, description = "Track not found, has no power data or no timestamps"),
+    ),
+    tag = "tracks",
+)]
+pub async fn get_track_power_zones(
+    State(pool): State<Arc<PgPool>>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<PowerZonesQuery>,
+    headers: HeaderMap,
+) -> Result<Json<PowerZonesResponse>, StatusCode> {
+    if !(1..=MAX_FTP_WATTS).contains(&query.ftp) {
+        return Err(StatusCode::BAD_REQUEST);
     }
+    let session_id = parse_session_header(&headers);
+    let series = match db::get_track_power_series(&pool, id, session_id).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!(track_id = %id, error = ?e, endpoint = "get_track_power_zones", "db error");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let seconds =
+        power_zone_seconds(&series.power, &series.times, query.ftp).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(build_power_zones(query.ftp, seconds)))
 }
 
 /// Get detailed slope profile for track visualization
 ///
 /// Returns slope segments in format: [{distance_m: float, slope_percent: float, length_m: float}]
 /// This endpoint provides the data needed for detailed slope visualization in ElevationChart.vue
+#[utoipa::path(
+    get,
+    path = "/tracks/{id}/slope-profile",
+    params(("id" = Uuid, Path, description = "Track id"), ("x-session-id" = Option<Uuid>, Header, description = "Session id of the caller; grants access to own private tracks")),
+    responses(
+        (status = 200, description = "Slope segments along the track", body = serde_json::Value),
+        (status = 202, description = "Elevation was enriched and slopes are still being calculated"),
+        (status = 404, description = "Track not found, or `slope_data_unavailable` when it has no slope data"),
+        (status = 422, description = "`slope_calculation_failed`: slopes could not be calculated from the enriched elevation"),
+    ),
+    tag = "elevation",
+)]
 pub async fn get_track_slope_profile(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, StatusCode> {
     // Get track with slope data
-    let track = match db::get_track_detail_adaptive(&pool, id, None, None)
+    let track = match db::get_track_detail(&pool, id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
     {
         Some(track) => track,
         None => return Err(StatusCode::NOT_FOUND),
     };
+    if !can_view_track(
+        track.is_public,
+        track.session_id,
+        parse_session_header(&headers),
+    ) {
+        return Err(StatusCode::NOT_FOUND);
+    }
 
-    // Check if slope data is available
-    let slope_segments = match track.slope_segments {
-        Some(segments) => segments,
-        None => {
-            // If no slope segments, try to calculate from existing data
-            return Ok(Json(json!({
-                "error": "Slope data not available for this track. Track may not have elevation data or slope calculation may be pending."
-            })).into_response());
+    // Only an enriched track without slopes can have a failed calculation behind it
+    let calculation_failed = track.slope_segments.is_none()
+        && track.elevation_enriched == Some(true)
+        && db::slope_calculation_failed(&pool, id)
+            .await
+            .map_err(handle_db_error)?;
+    let slope_segments = match (
+        SlopeDataStatus::of(
+            track.slope_segments.as_ref(),
+            track.elevation_enriched,
+            calculation_failed,
+        ),
+        track.slope_segments,
+    ) {
+        (SlopeDataStatus::Available, Some(segments)) => segments,
+        (SlopeDataStatus::Failed, _) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "code": "slope_calculation_failed",
+                    "message": "Slopes could not be calculated from this track's elevation data",
+                })),
+            )
+                .into_response());
+        }
+        (SlopeDataStatus::Pending, _) => {
+            return Ok((
+                StatusCode::ACCEPTED,
+                Json(json!({
+                    "code": "slope_data_pending",
+                    "message": "Slope calculation for this track is in progress",
+                })),
+            )
+                .into_response());
+        }
+        _ => {
+            return Ok((
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "code": "slope_data_unavailable",
+                    "message": "Slope data is not available for this track; it may not have elevation data",
+                })),
+            )
+                .into_response());
         }
     };
 
@@ -1502,6 +5570,32 @@ pub async fn get_track_slope_profile(
     Ok(Json(profile).into_response())
 }
 
+/// Whether a track's slope profile can be served yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlopeDataStatus {
+    Available,
+    Unavailable,
+    Pending,
+    Failed,
+}
+
+impl SlopeDataStatus {
+    /// Enrichment stores elevation before slopes, so an enriched track without segments
+    /// is still being processed unless its slope calculation is known to have failed
+    fn of(
+        slope_segments: Option<&serde_json::Value>,
+        elevation_enriched: Option<bool>,
+        calculation_failed: bool,
+    ) -> Self {
+        match (slope_segments, elevation_enriched) {
+            (Some(_), _) => SlopeDataStatus::Available,
+            (None, Some(true)) if calculation_failed => SlopeDataStatus::Failed,
+            (None, Some(true)) => SlopeDataStatus::Pending,
+            (None, _) => SlopeDataStatus::Unavailable,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct SlopeProfilePoint {
     distance_m: f64,
@@ -1517,20 +5611,58 @@ struct SlopeSegment {
     slope: f64,
 }
 
+/// Unwrap and validate the JSON body of a slope recalculation request.
+/// Malformed bodies (including missing `session_id`) are reported as 400 rather than 422.
+fn parse_recalculate_slopes_request(
+    payload: Result<Json<RecalculateSlopesRequest>, JsonRejection>,
+) -> Result<RecalculateSlopesRequest, StatusCode> {
+    let Json(request) = payload.map_err(|rejection| {
+        warn!(error = %rejection, endpoint = "recalculate_track_slopes", "invalid request body");
+        StatusCode::BAD_REQUEST
+    })?;
+    request.validate().map_err(|reason| {
+        warn!(
+            reason,
+            endpoint = "recalculate_track_slopes",
+            "invalid request body"
+        );
+        StatusCode::BAD_REQUEST
+    })?;
+    Ok(request)
+}
+
 /// Recalculate slopes for a track with improved algorithm
 /// This endpoint allows recalculating slopes with the updated algorithm that includes:
 /// - Better noise filtering
 /// - Anomaly detection and smoothing
 /// - More realistic slope limits
+#[utoipa::path(
+    post,
+    path = "/tracks/{id}/recalculate-slopes",
+    params(("id" = Uuid, Path, description = "Track id")),
+    request_body = RecalculateSlopesRequest,
+    responses(
+        (status = 200, description = "Slopes recalculated, or left as they were when `skipped` is true", body = serde_json::Value),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Session does not own the track"),
+        (status = 404, description = "Track not found"),
+    ),
+    tag = "elevation",
+)]
 pub async fn recalculate_track_slopes(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<Uuid>,
-    Json(request): Json<UpdateTrackNameRequest>, // Reuse existing struct for session_id
+    payload: Result<Json<RecalculateSlopesRequest>, JsonRejection>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    use crate::track_utils::slope::recalculate_slope_metrics;
+    use crate::track_utils::slope::{
+        recalculate_slope_metrics_with_window, smoothing_window_for_source,
+    };
+
+    let request = parse_recalculate_slopes_request(payload)?;
 
-    // Get track with geometry and elevation data
-    let track = match db::get_track_detail_adaptive(&pool, id, None, None)
+    // Full-resolution geometry: simplified coordinates would no longer line up point for
+    // point with the stored elevation profile
+    let track = match db::get_track_detail(&pool, id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
     {
@@ -1539,30 +5671,29 @@ pub async fn recalculate_track_slopes(
     };
 
     // Check session ownership (reuse existing auth logic)
-    if track.session_id != Some(request.session_id) {
-        return Err(StatusCode::FORBIDDEN);
-    }
+    check_track_owner(track.session_id, request.session_id)?;
 
-    // Extract coordinates from geometry
-    let geom_str = match track.geom_geojson.get("coordinates") {
-        Some(coords) => coords.to_string(),
-        None => return Err(StatusCode::BAD_REQUEST),
-    };
+    // Callers may opt in to leaving already calculated tracks alone; `skipped` tells
+    // them apart from a recalculation
+    if track.slope_segments.is_some() && request.skips_calculated_track() {
+        debug!(track_id = %id, endpoint = "recalculate_track_slopes", "skipping: slopes already calculated");
+        return Ok(Json(json!({
+            "id": id,
+            "skipped": true,
+            "message": "Track already has slope data",
+            "slope_min": track.slope_min,
+            "slope_max": track.slope_max,
+            "slope_avg": track.slope_avg
+        }))
+        .into_response());
+    }
 
-    // Parse coordinates - for LineString GeoJSON format
-    let coordinates: Vec<(f64, f64)> = match serde_json::from_str::<Vec<Vec<f64>>>(&geom_str) {
-        Ok(coords) => coords
-            .into_iter()
-            .filter_map(|coord| {
-                if coord.len() >= 2 {
-                    Some((coord[1], coord[0])) // Convert from [lon, lat] to (lat, lon)
-                } else {
-                    None
-                }
-            })
-            .collect(),
-        Err(_) => return Err(StatusCode::BAD_REQUEST),
-    };
+    // Extract coordinates from geometry; MultiLineString segments are concatenated so
+    // they line up with the per-point elevation profile
+    let coordinates = extract_coordinates_from_geojson(&track.geom_geojson).map_err(|e| {
+        error!(track_id = %id, error = %e, endpoint = "recalculate_track_slopes", "failed to parse track coordinates");
+        StatusCode::BAD_REQUEST
+    })?;
 
     if coordinates.len() < 2 {
         return Ok(Json(json!({
@@ -1597,9 +5728,33 @@ pub async fn recalculate_track_slopes(
         .into_response());
     }
 
-    // Recalculate slopes with improved algorithm
+    // Recalculate slopes with improved algorithm; without an explicit window the
+    // smoothing follows the quality of the stored elevation data
+    let smoothing_window_m = request.smoothing_window_m.or_else(|| {
+        smoothing_window_for_source(
+            track
+                .elevation_source
+                .as_deref()
+                .and_then(crate::track_utils::ElevationSource::from_label),
+        )
+    });
     let slope_start = Instant::now();
-    let slope_metrics = recalculate_slope_metrics(&coordinates, &elevation_profile, &track.name);
+    let slope_metrics = match recalculate_slope_metrics_with_window(
+        &coordinates,
+        &elevation_profile,
+        &track.name,
+        smoothing_window_m,
+    ) {
+        Ok(slope_metrics) => slope_metrics,
+        Err(e) => {
+            e.log(&track.name);
+            metrics::observe_slope_recalc("calculation_error", slope_start.elapsed().as_secs_f64());
+            return Ok(Json(json!({
+                "error": format!("Slope calculation failed: {e}")
+            }))
+            .into_response());
+        }
+    };
     let slope_duration = slope_start.elapsed().as_secs_f64();
 
     // Update track in database
@@ -1612,6 +5767,7 @@ pub async fn recalculate_track_slopes(
             slope_avg = $3,
             slope_histogram = $4,
             slope_segments = $5,
+            slope_calculation_failed = FALSE,
             updated_at = CURRENT_TIMESTAMP
         WHERE id = $6
         "#,
@@ -1631,6 +5787,7 @@ pub async fn recalculate_track_slopes(
             tracing::info!("Successfully recalculated slopes for track {}", id);
             Ok(Json(json!({
                 "id": id,
+                "skipped": false,
                 "message": "Slopes recalculated successfully with improved algorithm",
                 "slope_min": slope_metrics.slope_min,
                 "slope_max": slope_metrics.slope_max,
@@ -1650,7 +5807,68 @@ pub async fn recalculate_track_slopes(
 // POI Handlers
 // ============================================================================
 
+/// Column list and source shared by every POI listing query
+fn build_poi_select_sql() -> &'static str {
+    r#"
+    SELECT
+        p.id, p.name, p.description, p.category, p.elevation,
+        ST_AsGeoJSON(p.geom::geometry)::jsonb as geom,
+        p.session_id, p.created_at, p.updated_at
+    FROM pois p
+    "#
+}
+
+/// Append the track join and `WHERE` clause for the optional POI list filters
+fn push_poi_filters<'a>(
+    query: &mut QueryBuilder<'a, Postgres>,
+    params: &'a PoiQuery,
+    bbox: Option<[f64; 4]>,
+) {
+    if let Some(track_id) = params.track_id {
+        query.push(" JOIN track_pois tp ON p.id = tp.poi_id AND tp.track_id = ");
+        query.push_bind(track_id);
+    }
+    query.push(" WHERE TRUE");
+    if let Some([min_lon, min_lat, max_lon, max_lat]) = bbox {
+        query.push(" AND ST_Intersects(p.geom::geometry, ST_MakeEnvelope(");
+        query.push_bind(min_lon);
+        query.push(", ");
+        query.push_bind(min_lat);
+        query.push(", ");
+        query.push_bind(max_lon);
+        query.push(", ");
+        query.push_bind(max_lat);
+        query.push(", 4326))");
+    }
+    if let Some(categories) = params.categories.as_ref().filter(|c| !c.is_empty()) {
+        query.push(" AND p.category = ANY(");
+        query.push_bind(categories);
+        query.push(")");
+    }
+}
+
+/// `total` for the POI list; counts with the same filters as the page query so a bbox or
+/// track filter does not report every POI in the database
+fn build_poi_count_query<'a>(
+    params: &'a PoiQuery,
+    bbox: Option<[f64; 4]>,
+) -> QueryBuilder<'a, Postgres> {
+    let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM pois p");
+    push_poi_filters(&mut count, params, bbox);
+    count
+}
+
 /// GET /pois - List POIs with optional filtering
+#[utoipa::path(
+    get,
+    path = "/pois",
+    params(PoiQuery),
+    responses(
+        (status = 200, description = "Matching POIs", body = PoiListResponse),
+        (status = 400, description = "Invalid filter parameters"),
+    ),
+    tag = "pois",
+)]
 pub async fn get_pois(
     State(pool): State<Arc<PgPool>>,
     Query(params): Query<PoiQuery>,
@@ -1658,94 +5876,40 @@ pub async fn get_pois(
     let limit = params.limit.unwrap_or(100).min(1000);
     let offset = params.offset.unwrap_or(0);
 
-    // Build query based on filters
-    let pois = if let Some(bbox_str) = &params.bbox {
-        // Parse bbox: "minLon,minLat,maxLon,maxLat"
-        let bbox_parts: Vec<f64> = bbox_str.split(',').filter_map(|s| s.parse().ok()).collect();
-
-        if bbox_parts.len() != 4 {
-            error!("Invalid bbox format: {}", bbox_str);
-            return Err(StatusCode::BAD_REQUEST);
+    let bbox = match &params.bbox {
+        Some(bbox_str) => {
+            // Parse bbox: "minLon,minLat,maxLon,maxLat"
+            let bbox_parts: Vec<f64> = bbox_str.split(',').filter_map(|s| s.parse().ok()).collect();
+            if bbox_parts.len() != 4 {
+                error!("Invalid bbox format: {}", bbox_str);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            Some([bbox_parts[0], bbox_parts[1], bbox_parts[2], bbox_parts[3]])
         }
+        None => None,
+    };
 
-        sqlx::query_as::<_, Poi>(
-            r#"
-            SELECT 
-                id, name, description, category, elevation,
-                ST_AsGeoJSON(geom::geometry)::jsonb as geom,
-                session_id, created_at, updated_at
-            FROM pois
-            WHERE ST_Intersects(
-                geom::geometry, 
-                ST_MakeEnvelope($1, $2, $3, $4, 4326)
-            )
-            ORDER BY created_at DESC
-            LIMIT $5
-            OFFSET $6
-            "#,
-        )
-        .bind(bbox_parts[0])
-        .bind(bbox_parts[1])
-        .bind(bbox_parts[2])
-        .bind(bbox_parts[3])
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&*pool)
-        .await
-        .map_err(|e| {
-            error!("Failed to fetch POIs: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-    } else if let Some(track_id) = params.track_id {
-        // Get POIs for a specific track
-        sqlx::query_as::<_, Poi>(
-            r#"
-            SELECT 
-                p.id, p.name, p.description, p.category, p.elevation,
-                ST_AsGeoJSON(p.geom::geometry)::jsonb as geom,
-                p.session_id, p.created_at, p.updated_at
-            FROM pois p
-            JOIN track_pois tp ON p.id = tp.poi_id
-            WHERE tp.track_id = $1
-            ORDER BY tp.sequence_order
-            LIMIT $2
-            OFFSET $3
-            "#,
-        )
-        .bind(track_id)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&*pool)
-        .await
-        .map_err(|e| {
-            error!("Failed to fetch track POIs: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
+    let mut query = QueryBuilder::<Postgres>::new(build_poi_select_sql());
+    push_poi_filters(&mut query, &params, bbox);
+    if params.track_id.is_some() {
+        query.push(" ORDER BY tp.sequence_order");
     } else {
-        // Get all POIs (with limit)
-        sqlx::query_as::<_, Poi>(
-            r#"
-            SELECT 
-                id, name, description, category, elevation,
-                ST_AsGeoJSON(geom::geometry)::jsonb as geom,
-                session_id, created_at, updated_at
-            FROM pois
-            ORDER BY created_at DESC
-            LIMIT $1
-            OFFSET $2
-            "#,
-        )
-        .bind(limit)
-        .bind(offset)
+        query.push(" ORDER BY p.created_at DESC");
+    }
+    query.push(" LIMIT ").push_bind(limit);
+    query.push(" OFFSET ").push_bind(offset);
+
+    let pois = query
+        .build_query_as::<Poi>()
         .fetch_all(&*pool)
         .await
         .map_err(|e| {
             error!("Failed to fetch POIs: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
-        })?
-    };
+        })?;
 
-    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM pois")
+    let total = build_poi_count_query(&params, bbox)
+        .build_query_scalar::<i64>()
         .fetch_one(&*pool)
         .await
         .map_err(|e| {
@@ -1757,6 +5921,16 @@ pub async fn get_pois(
 }
 
 /// GET /pois/:id - Get POI details
+#[utoipa::path(
+    get,
+    path = "/pois/{id}",
+    params(("id" = i32, Path, description = "POI id")),
+    responses(
+        (status = 200, description = "POI details", body = Poi),
+        (status = 404, description = "POI not found"),
+    ),
+    tag = "pois",
+)]
 pub async fn get_poi(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<i32>,
@@ -1784,6 +5958,15 @@ pub async fn get_poi(
 }
 
 /// GET /tracks/:track_id/pois - Get POIs for a track with distance info
+#[utoipa::path(
+    get,
+    path = "/tracks/{track_id}/pois",
+    params(("track_id" = Uuid, Path, description = "Track id")),
+    responses(
+        (status = 200, description = "POIs along the track ordered by distance", body = Vec<PoiWithDistance>),
+    ),
+    tag = "pois",
+)]
 pub async fn get_track_pois(
     State(pool): State<Arc<PgPool>>,
     Path(track_id): Path<Uuid>,
@@ -1835,6 +6018,16 @@ pub async fn get_track_pois(
 }
 
 /// POST /pois - Create manual POI
+#[utoipa::path(
+    post,
+    path = "/pois",
+    request_body = CreatePoiRequest,
+    responses(
+        (status = 200, description = "POI created", body = Poi),
+        (status = 400, description = "Invalid POI"),
+    ),
+    tag = "pois",
+)]
 pub async fn create_poi(
     State(pool): State<Arc<PgPool>>,
     Json(request): Json<CreatePoiRequest>,
@@ -1845,6 +6038,8 @@ pub async fn create_poi(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    let (lat, lon) = poi_request_coordinates(&request)?;
+
     validate_text_field(&request.name, MAX_NAME_LENGTH, "name")?;
 
     if let Some(ref desc) = request.description {
@@ -1865,8 +6060,8 @@ pub async fn create_poi(
     .bind(request.description)
     .bind(request.category)
     .bind(request.elevation)
-    .bind(request.lon)
-    .bind(request.lat)
+    .bind(lon)
+    .bind(lat)
     .bind(request.session_id)
     .fetch_one(&*pool)
     .await
@@ -1880,7 +6075,50 @@ pub async fn create_poi(
     Ok(Json(poi))
 }
 
+/// Resolve the (lat, lon) of a POI create request. A GeoJSON `geometry` wins over the
+/// flat `lat`/`lon` fields; either way the position must be a valid WGS-84 coordinate.
+fn poi_request_coordinates(request: &CreatePoiRequest) -> Result<(f64, f64), StatusCode> {
+    let (lat, lon) = match &request.geometry {
+        Some(geometry) => {
+            if geometry.get("type").and_then(|t| t.as_str()) != Some("Point") {
+                error!("POI geometry must be a GeoJSON Point");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            let coordinates = geometry
+                .get("coordinates")
+                .and_then(|c| c.as_array())
+                .filter(|c| c.len() >= 2);
+            match coordinates.map(|c| (c[1].as_f64(), c[0].as_f64())) {
+                Some((Some(lat), Some(lon))) => (lat, lon),
+                _ => {
+                    error!("POI geometry has no numeric [lon, lat] coordinates");
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+            }
+        }
+        None => match (request.lat, request.lon) {
+            (Some(lat), Some(lon)) => (lat, lon),
+            _ => {
+                error!("POI requires either geometry or lat/lon");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+        },
+    };
+    validate_wgs84(lat, lon)?;
+    Ok((lat, lon))
+}
+
 /// DELETE /tracks/:track_id/pois/:poi_id - Unlink POI from track
+#[utoipa::path(
+    delete,
+    path = "/tracks/{track_id}/pois/{poi_id}",
+    params(("track_id" = Uuid, Path, description = "Track id"), ("poi_id" = i32, Path, description = "POI id")),
+    responses(
+        (status = 204, description = "POI unlinked"),
+        (status = 404, description = "Link not found"),
+    ),
+    tag = "pois",
+)]
 pub async fn unlink_track_poi(
     State(pool): State<Arc<PgPool>>,
     Path((track_id, poi_id)): Path<(Uuid, i32)>,
@@ -1905,6 +6143,19 @@ pub async fn unlink_track_poi(
 }
 
 /// DELETE /pois/:id - Delete POI (only if not used and user is owner)
+#[utoipa::path(
+    delete,
+    path = "/pois/{id}",
+    params(("id" = i32, Path, description = "POI id")),
+    request_body = DeletePoiRequest,
+    responses(
+        (status = 204, description = "POI deleted"),
+        (status = 403, description = "Session does not own the POI"),
+        (status = 404, description = "POI not found"),
+        (status = 409, description = "POI is still linked to tracks"),
+    ),
+    tag = "pois",
+)]
 pub async fn delete_poi(
     State(pool): State<Arc<PgPool>>,
     Path(id): Path<i32>,
@@ -1961,3 +6212,96 @@ pub async fn delete_poi(
     metrics::record_poi_deleted("delete_poi");
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// DELETE /pois/bulk - Delete several unused POIs at once, all or nothing
+#[utoipa::path(
+    delete,
+    path = "/pois/bulk",
+    request_body = BulkDeletePoisRequest,
+    responses(
+        (status = 200, description = "All POIs deleted", body = BulkDeletePoisResponse),
+        (status = 400, description = "Empty list or more than 200 ids"),
+        (status = 403, description = "At least one POI belongs to another session; nothing was deleted"),
+        (status = 404, description = "At least one POI does not exist; nothing was deleted"),
+        (status = 409, description = "At least one POI is still linked to tracks; nothing was deleted"),
+    ),
+    tag = "pois",
+)]
+pub async fn bulk_delete_pois(
+    State(pool): State<Arc<PgPool>>,
+    Json(request): Json<BulkDeletePoisRequest>,
+) -> Result<Json<BulkDeletePoisResponse>, StatusCode> {
+    let mut poi_ids = request.poi_ids;
+    poi_ids.sort_unstable();
+    poi_ids.dedup();
+    if poi_ids.is_empty() || poi_ids.len() > MAX_BULK_DELETE_POIS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut tx = pool.begin().await.map_err(handle_db_error)?;
+
+    // Lock the rows so no track can link them between the checks and the delete
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            p.id,
+            p.session_id,
+            (SELECT COUNT(*) FROM track_pois WHERE poi_id = p.id) as usage_count
+        FROM pois p
+        WHERE p.id = ANY($1)
+        FOR UPDATE OF p
+        "#,
+    )
+    .bind(&poi_ids)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(handle_db_error)?;
+
+    use sqlx::Row;
+    if rows.len() != poi_ids.len() {
+        warn!(
+            requested = poi_ids.len(),
+            found = rows.len(),
+            endpoint = "bulk_delete_pois",
+            "bulk delete rejected: unknown POI ids"
+        );
+        return Err(StatusCode::NOT_FOUND);
+    }
+    for row in &rows {
+        let id: i32 = row.get("id");
+        let owner_id: Option<Uuid> = row.get("session_id");
+        let usage_count: i64 = row.get("usage_count");
+        if let Some(owner_session_id) = owner_id
+            && owner_session_id != request.session_id
+        {
+            warn!(
+                poi_id = id,
+                endpoint = "bulk_delete_pois",
+                "bulk delete rejected: not the owner"
+            );
+            return Err(StatusCode::FORBIDDEN);
+        }
+        if usage_count > 0 {
+            warn!(
+                poi_id = id,
+                usage_count,
+                endpoint = "bulk_delete_pois",
+                "bulk delete rejected: POI in use"
+            );
+            return Err(StatusCode::CONFLICT);
+        }
+    }
+
+    let result = sqlx::query("DELETE FROM pois WHERE id = ANY($1)")
+        .bind(&poi_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(handle_db_error)?;
+    tx.commit().await.map_err(handle_db_error)?;
+
+    let deleted_count = result.rows_affected();
+    info!(deleted_count, "Bulk deleted POIs");
+    metrics::record_pois_deleted("bulk_delete_pois", deleted_count);
+    metrics::record_session_activity(Some(request.session_id), "edit");
+    Ok(Json(BulkDeletePoisResponse { deleted_count }))
+}
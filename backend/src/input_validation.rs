@@ -9,12 +9,25 @@ pub static MAX_FILE_SIZE: Lazy<usize> = Lazy::new(|| {
         .unwrap_or(50 * 1024 * 1024)
 });
 
+// Duplicate checks only hash the file, so they may use their own (defaulting to upload) limit
+pub static CHECK_EXIST_MAX_FILE_SIZE: Lazy<usize> = Lazy::new(|| {
+    std::env::var("CHECK_EXIST_MAX_FILE_SIZE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(*MAX_FILE_SIZE)
+});
+
 pub const MAX_FIELD_SIZE: usize = 10 * 1024;
 pub const MAX_CATEGORIES: usize = 50;
+pub const MAX_BULK_DELETE_TRACKS: usize = 100;
+pub const MAX_BULK_DELETE_POIS: usize = 200;
+pub const MAX_COLLECTION_EXPORT_TRACKS: usize = 100;
 pub const MAX_CATEGORY_LENGTH: usize = 100;
 pub const MAX_NAME_LENGTH: usize = 256;
 pub const MAX_DESCRIPTION_LENGTH: usize = 50000;
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
 pub const ALLOWED_EXTENSIONS: &[&str] = &["gpx", "kml"];
+pub const MAX_LOG_VALUE_CHARS: usize = 200;
 
 pub fn validate_file_size(size: usize) -> Result<(), StatusCode> {
     if size > *MAX_FILE_SIZE {
@@ -24,6 +37,18 @@ pub fn validate_file_size(size: usize) -> Result<(), StatusCode> {
     Ok(())
 }
 
+/// Size check for duplicate lookups: oversized files are a bad request rather than "not found"
+pub fn validate_check_exist_file_size(size: usize) -> Result<(), StatusCode> {
+    if size > *CHECK_EXIST_MAX_FILE_SIZE {
+        error!(
+            "File size {} exceeds duplicate check maximum {}",
+            size, *CHECK_EXIST_MAX_FILE_SIZE
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
 pub fn validate_text_field(text: &str, max_len: usize, field_name: &str) -> Result<(), StatusCode> {
     if text.len() > max_len {
         error!(
@@ -40,12 +65,39 @@ pub fn validate_text_field(text: &str, max_len: usize, field_name: &str) -> Resu
 pub fn validate_file_extension(filename: &str) -> Result<String, StatusCode> {
     let ext = filename.split('.').next_back().unwrap_or("").to_lowercase();
     if !ALLOWED_EXTENSIONS.contains(&ext.as_str()) {
-        error!("File extension '{}' not allowed", ext);
+        error!("File extension '{}' not allowed", sanitize_for_log(&ext));
         return Err(StatusCode::BAD_REQUEST);
     }
     Ok(ext)
 }
 
+/// Category and classification labels are limited to letters, digits, spaces, `_` and `-`
+pub fn validate_category_chars(category: &str) -> Result<(), StatusCode> {
+    if !category
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ' '))
+    {
+        error!(
+            "Category '{}' contains disallowed characters",
+            sanitize_for_log(category)
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
+/// Coordinates must be finite and inside the WGS-84 range
+pub fn validate_wgs84(lat: f64, lon: f64) -> Result<(), StatusCode> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        error!(
+            "Coordinates lat={} lon={} are outside WGS-84 range",
+            lat, lon
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
 pub fn validate_categories_non_empty(categories: &[String]) -> Result<(), StatusCode> {
     if categories.is_empty() {
         error!("No categories provided");
@@ -54,18 +106,112 @@ pub fn validate_categories_non_empty(categories: &[String]) -> Result<(), Status
     Ok(())
 }
 
-pub fn sanitize_input(input: &str) -> String {
+/// Normalize a BCP 47 language tag such as `de`, `pt-br` or `zh_Hant` to its canonical
+/// case (`de`, `pt-BR`, `zh-hant`). Only the shape is checked, not IANA registration.
+pub fn normalize_language_code(code: &str) -> Result<String, StatusCode> {
+    let mut subtags = code.trim().split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default();
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut normalized = primary.to_ascii_lowercase();
+    for (i, subtag) in subtags.enumerate() {
+        if i >= 3 || subtag.is_empty() || subtag.len() > 8 {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        if !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        normalized.push('-');
+        if subtag.len() == 2 {
+            normalized.push_str(&subtag.to_ascii_uppercase());
+        } else {
+            normalized.push_str(&subtag.to_ascii_lowercase());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Sanitize single-line text such as track names and categories.
+///
+/// Strips control characters and truncates to `max_chars` characters while preserving all
+/// printable Unicode (accents, Cyrillic, CJK, emoji). HTML is not interpreted here: values are
+/// rendered as text by the frontend, and free-form descriptions go through ammonia instead.
+pub fn sanitize_input(input: &str, max_chars: usize) -> String {
     input
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
         .trim()
         .chars()
-        .filter(|c| c.is_alphanumeric() || " .,;:!?-_()[]{}".contains(*c))
+        .take(max_chars)
         .collect()
 }
 
+/// Make a user-supplied value safe to embed in a log line.
+///
+/// Removes ANSI escape sequences (CSI and OSC) and all other control characters, so values
+/// cannot forge log lines or recolor terminals, and truncates to [`MAX_LOG_VALUE_CHARS`].
+pub fn sanitize_for_log(input: &str) -> String {
+    let mut out = String::new();
+    let mut kept = 0;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            match chars.next() {
+                // CSI: parameters up to a final byte in '@'..='~'
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        if c.is_control() {
+            continue;
+        }
+        if kept == MAX_LOG_VALUE_CHARS {
+            out.push('…');
+            break;
+        }
+        out.push(c);
+        kept += 1;
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn normalize_language_code_canonicalizes_case() {
+        assert_eq!(normalize_language_code("DE").unwrap(), "de");
+        assert_eq!(normalize_language_code(" pt_br ").unwrap(), "pt-BR");
+        assert_eq!(normalize_language_code("zh-Hant-TW").unwrap(), "zh-hant-TW");
+        assert!(normalize_language_code("").is_err());
+        assert!(normalize_language_code("english").is_err());
+        assert!(normalize_language_code("en-").is_err());
+        assert!(normalize_language_code("en-<b>").is_err());
+    }
+
     #[test]
     fn validate_categories_non_empty_ok() {
         let cats = vec!["hiking".to_string()];
@@ -77,4 +223,68 @@ mod tests {
         let cats: Vec<String> = vec![];
         assert!(validate_categories_non_empty(&cats).is_err());
     }
+
+    #[test]
+    fn validate_category_chars_rejects_markup() {
+        assert!(validate_category_chars("trail_running").is_ok());
+        assert!(validate_category_chars("Горный велосипед").is_ok());
+        assert!(validate_category_chars("hiking<script>").is_err());
+        assert!(validate_category_chars("a,b").is_err());
+    }
+
+    #[test]
+    fn validate_check_exist_file_size_rejects_oversized_files() {
+        assert!(validate_check_exist_file_size(1024).is_ok());
+        assert_eq!(
+            validate_check_exist_file_size(*CHECK_EXIST_MAX_FILE_SIZE + 1),
+            Err(StatusCode::BAD_REQUEST)
+        );
+    }
+
+    #[test]
+    fn sanitize_for_log_strips_escapes_and_line_breaks() {
+        assert_eq!(
+            sanitize_for_log("run.gpx\n2024-01-01 INFO admin logged in"),
+            "run.gpx2024-01-01 INFO admin logged in"
+        );
+        assert_eq!(sanitize_for_log("\u{1b}[31mred\u{1b}[0m.gpx"), "red.gpx");
+        assert_eq!(
+            sanitize_for_log("\u{1b}]0;title\u{7}ride\u{1b}]8;;x\u{1b}\\.kml"),
+            "ride.kml"
+        );
+        assert_eq!(sanitize_for_log("Überquerung 🏔"), "Überquerung 🏔");
+
+        let long = "a".repeat(MAX_LOG_VALUE_CHARS + 10);
+        assert_eq!(
+            sanitize_for_log(&long).chars().count(),
+            MAX_LOG_VALUE_CHARS + 1
+        );
+    }
+
+    #[test]
+    fn sanitize_input_preserves_unicode_names() {
+        assert_eq!(
+            sanitize_input("Überquerung des Alpenkamms", MAX_NAME_LENGTH),
+            "Überquerung des Alpenkamms"
+        );
+        assert_eq!(
+            sanitize_input("Прогулка по Эльбрусу", MAX_NAME_LENGTH),
+            "Прогулка по Эльбрусу"
+        );
+        assert_eq!(
+            sanitize_input("富士山 登山 🏔", MAX_NAME_LENGTH),
+            "富士山 登山 🏔"
+        );
+        assert_eq!(
+            sanitize_input("Café & Crêpes / Loop #2", MAX_NAME_LENGTH),
+            "Café & Crêpes / Loop #2"
+        );
+    }
+
+    #[test]
+    fn sanitize_input_strips_control_chars_and_truncates() {
+        assert_eq!(sanitize_input("  Trail\u{0}\u{7}\n  ", 100), "Trail");
+        assert_eq!(sanitize_input("Åland\tRide", 100), "ÅlandRide");
+        assert_eq!(sanitize_input("ÄÖÜäöü", 3), "ÄÖÜ");
+    }
 }
@@ -1,10 +1,15 @@
+pub mod category_taxonomy;
+pub mod compression;
+pub mod cors;
 pub mod db;
 pub mod handlers;
 pub mod input_validation;
 pub mod logging;
 pub mod metrics;
 pub mod models;
+pub mod openapi;
 pub mod poi_deduplication;
+pub mod request_tracing;
 pub mod services;
 #[cfg(test)]
 pub mod test_utils;
@@ -3,12 +3,17 @@ use axum::{
     extract::DefaultBodyLimit,
     routing::{get, post},
 };
-use backend::{handlers, logging, metrics, services};
+use backend::models::HealthResponse;
+use backend::{
+    compression, cors, handlers, logging, metrics, openapi::ApiDoc, request_tracing, services,
+};
 use mimalloc::MiMalloc;
 use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing::info;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -18,14 +23,14 @@ async fn main() {
     // Handle health check flag
     let args: Vec<String> = std::env::args().collect();
     if args.len() > 1 && args[1] == "--health-check" {
-        // Simple health check - try to connect to the health endpoint
-        match tokio::net::TcpStream::connect("127.0.0.1:8080").await {
-            Ok(_) => {
+        // Query the health endpoint and require a "healthy" status
+        match check_health("127.0.0.1:8080").await {
+            Ok(()) => {
                 println!("Health check passed");
                 std::process::exit(0);
             }
-            Err(_) => {
-                eprintln!("Health check failed");
+            Err(e) => {
+                eprintln!("Health check failed: {e}");
                 std::process::exit(1);
             }
         }
@@ -57,6 +62,7 @@ async fn main() {
     metrics::initialize_metrics_baseline();
 
     services::enrichment_queue::init_enrichment_queue(Arc::clone(&pool));
+    handlers::spawn_rate_limit_cleanup();
 
     // Run migrations automatically on startup
     info!(
@@ -73,15 +79,30 @@ async fn main() {
         action = "complete",
         "database migrations finished"
     );
+    handlers::spawn_track_share_cleanup(Arc::clone(&pool));
+    services::enrichment_queue::spawn_failed_enrichment_recovery(Arc::clone(&pool));
+
+    let cors_policy = cors::CorsPolicy::from_env();
+    info!(policy = %cors_policy, "configured CORS policy");
 
     let app = Router::new()
         .route("/health", get(handlers::health))
         .route("/metrics", get(metrics::serve_metrics))
         .route("/tracks/upload", post(handlers::upload_track))
         .route("/tracks", get(handlers::list_tracks_geojson))
+        .route("/tracks/my", get(handlers::list_my_tracks))
         .route("/tracks", post(handlers::upload_track))
+        .route("/tracks/batch-upload", post(handlers::batch_upload_tracks))
         .route("/tracks/exist", post(handlers::check_track_exist))
         .route("/tracks/search", get(handlers::search_tracks))
+        .route("/categories", get(handlers::get_categories))
+        .route("/tracks/stats", get(handlers::get_track_stats))
+        .route("/tracks/similar", get(handlers::find_similar_tracks))
+        .route("/tracks/heatmap", get(handlers::get_track_heatmap))
+        .route(
+            "/tracks/bulk",
+            axum::routing::delete(handlers::bulk_delete_tracks),
+        )
         .route("/tracks/{id}", get(handlers::get_track))
         .route(
             "/tracks/{id}/simplified",
@@ -99,11 +120,76 @@ async fn main() {
             "/tracks/{id}/categories",
             axum::routing::patch(handlers::update_track_categories),
         )
+        .route(
+            "/tracks/{id}/visibility",
+            axum::routing::patch(handlers::update_track_visibility),
+        )
+        .route(
+            "/tracks/{id}/classifications",
+            get(handlers::get_track_classifications),
+        )
+        .route("/tracks/{id}/reclassify", post(handlers::reclassify_track))
+        .route(
+            "/tracks/{id}/split-suggestions",
+            get(handlers::get_track_split_suggestions),
+        )
+        .route(
+            "/tracks/{id}/enrichment-status",
+            get(handlers::get_track_enrichment_status),
+        )
+        .route(
+            "/tracks/{id}/comparable-stats",
+            get(handlers::get_track_comparable_stats),
+        )
+        .route("/tracks/{id}/weather", get(handlers::get_track_weather))
+        .route(
+            "/tracks/{id}/thumbnail",
+            get(handlers::get_track_thumbnail).post(handlers::generate_track_thumbnail),
+        )
+        .route("/tracks/{id}/share", post(handlers::create_track_share))
+        .route("/tracks/{id}/clone", post(handlers::clone_track))
+        .route("/tracks/shared/{token}", get(handlers::get_shared_track))
         .route("/tracks/{id}/export", get(handlers::export_track_gpx))
+        .route(
+            "/tracks/export/collection",
+            get(handlers::export_track_collection),
+        )
+        .route(
+            "/tracks/{id}/gpx-roundtrip-diff",
+            get(handlers::get_track_gpx_roundtrip_diff),
+        )
+        .route(
+            "/tracks/{id}/translations",
+            get(handlers::list_track_translations).post(handlers::upsert_track_translation),
+        )
+        .route(
+            "/tracks/{id}/translations/{lang}",
+            axum::routing::delete(handlers::delete_track_translation),
+        )
         .route(
             "/tracks/{id}/enrich-elevation",
             post(handlers::enrich_elevation),
         )
+        .route(
+            "/tracks/{id}/elevation-profile",
+            get(handlers::get_track_elevation_profile),
+        )
+        .route(
+            "/tracks/{id}/pace-chart",
+            get(handlers::get_track_pace_chart),
+        )
+        .route(
+            "/tracks/{id}/elevation-histogram",
+            get(handlers::get_track_elevation_histogram),
+        )
+        .route(
+            "/tracks/{id}/3d-profile",
+            get(handlers::get_track_3d_profile),
+        )
+        .route(
+            "/tracks/{id}/power-zones",
+            get(handlers::get_track_power_zones),
+        )
         .route(
             "/tracks/{id}/slope-profile",
             get(handlers::get_track_slope_profile),
@@ -122,6 +208,10 @@ async fn main() {
         )
         // POI routes
         .route("/pois", get(handlers::get_pois).post(handlers::create_poi))
+        .route(
+            "/pois/bulk",
+            axum::routing::delete(handlers::bulk_delete_pois),
+        )
         .route(
             "/pois/{id}",
             get(handlers::get_poi).delete(handlers::delete_poi),
@@ -135,9 +225,34 @@ async fn main() {
             "/debug/background_task",
             get(handlers::debug_background_task),
         )
+        .route(
+            "/webhooks",
+            get(handlers::list_webhooks).post(handlers::create_webhook),
+        )
+        .route(
+            "/webhooks/{id}",
+            axum::routing::delete(handlers::delete_webhook),
+        )
+        .route("/admin/api-usage", get(handlers::get_api_usage))
+        .route(
+            "/admin/tracks/orphaned-pois",
+            get(handlers::list_orphaned_pois).delete(handlers::delete_orphaned_pois),
+        )
+        .route(
+            "/admin/enrichment-failures",
+            get(handlers::list_enrichment_failures),
+        )
+        .route(
+            "/admin/enrichment-failures/{track_id}/retry",
+            post(handlers::retry_enrichment_failure),
+        )
         .route("/sitemap.xml", get(handlers::sitemap))
+        .merge(SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()))
+        .layer(compression::layer())
         .layer(DefaultBodyLimit::max(max_body_size))
+        .layer(cors::layer(&cors_policy))
         .layer(metrics::HttpMetricsLayer::new())
+        .layer(request_tracing::layer())
         .with_state(pool);
     let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
     info!(address = %addr, "listening");
@@ -154,8 +269,44 @@ async fn main() {
         }
     };
 
-    if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+    if let Err(e) = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    {
         eprintln!("Server error: {e}");
         std::process::exit(1);
     }
 }
+
+/// Minimal HTTP/1.0 client for `--health-check`, so the container image needs no curl
+async fn check_health(addr: &str) -> Result<(), String> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut stream = tokio::net::TcpStream::connect(addr)
+        .await
+        .map_err(|e| format!("connect: {e}"))?;
+    stream
+        .write_all(b"GET /health HTTP/1.0\r\nHost: localhost\r\n\r\n")
+        .await
+        .map_err(|e| format!("write: {e}"))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("read: {e}"))?;
+
+    let response = String::from_utf8_lossy(&response);
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body)
+        .ok_or("malformed response")?;
+    let health: HealthResponse =
+        serde_json::from_str(body).map_err(|e| format!("invalid body: {e}"))?;
+    if health.status == "healthy" {
+        Ok(())
+    } else {
+        Err(format!("status is {}", health.status))
+    }
+}
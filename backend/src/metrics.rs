@@ -229,6 +229,30 @@ static TRACK_ENRICH_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     counter
 });
 
+static TRACK_UPLOAD_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let opts = Opts::new(
+        "track_upload_total",
+        "Successfully uploaded tracks by file format",
+    );
+    let counter = IntCounterVec::new(opts, &["format"]).expect("counter vec");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("register track_upload_total");
+    counter
+});
+
+static ELEVATION_ENRICHMENT_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    let opts = Opts::new(
+        "elevation_enrichment_total",
+        "Elevation enrichment outcomes (success/failure/skipped)",
+    );
+    let counter = IntCounterVec::new(opts, &["status"]).expect("counter vec");
+    REGISTRY
+        .register(Box::new(counter.clone()))
+        .expect("register elevation_enrichment_total");
+    counter
+});
+
 static TRACK_VIEWS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     let opts = Opts::new(
         "track_views_total",
@@ -298,9 +322,9 @@ static SESSION_HEARTBEAT: Lazy<IntCounterVec> = Lazy::new(|| {
 static ELEVATION_API_CALLS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     let opts = Opts::new(
         "elevation_api_calls_total",
-        "Elevation API calls by service",
+        "Elevation API calls by dataset",
     );
-    let counter = IntCounterVec::new(opts, &["service"]).expect("counter vec");
+    let counter = IntCounterVec::new(opts, &["dataset"]).expect("counter vec");
     REGISTRY
         .register(Box::new(counter.clone()))
         .expect("register elevation_api_calls_total");
@@ -481,6 +505,8 @@ impl HttpMetricsLayer {
         let _ = &*TRACK_PARSE_DURATION_SECONDS;
         let _ = &*TRACK_ENRICH_DURATION_SECONDS;
         let _ = &*TRACK_ENRICH_REQUESTS_TOTAL;
+        let _ = &*TRACK_UPLOAD_TOTAL;
+        let _ = &*ELEVATION_ENRICHMENT_TOTAL;
         let _ = &*TRACK_EXPORT_DURATION_SECONDS;
         let _ = &*TRACK_PIPELINE_LATENCY_SECONDS;
         let _ = &*TRACKS_DEDUPLICATED_TOTAL;
@@ -541,6 +567,9 @@ pub fn initialize_metrics_baseline() {
     let _ = TRACKS_DEDUPLICATED_TOTAL.with_label_values(&["gpx_hash_match"]);
     let _ = TRACKS_DELETED_TOTAL.with_label_values(&["success"]);
     let _ = TRACK_PARSE_DURATION_SECONDS.with_label_values(&["gpx"]);
+    let _ = TRACK_PARSE_DURATION_SECONDS.with_label_values(&["kml"]);
+    let _ = TRACK_UPLOAD_TOTAL.with_label_values(&["gpx"]);
+    let _ = TRACK_UPLOAD_TOTAL.with_label_values(&["kml"]);
     let _ = TRACK_PIPELINE_LATENCY_SECONDS.with_label_values(&["success"]);
     let _ = TRACK_LENGTH_KM_BUCKET.with_label_values(&["anonymous"]);
     let _ = TRACK_CATEGORIES_TOTAL.with_label_values(&["unknown"]);
@@ -557,11 +586,16 @@ pub fn initialize_metrics_baseline() {
     let _ = TRACK_ENRICH_REQUESTS_TOTAL.with_label_values(&["failed_remote"]);
     let _ = TRACK_ENRICH_DURATION_SECONDS.with_label_values(&["success"]);
     let _ = TRACK_ENRICH_DURATION_SECONDS.with_label_values(&["failed_remote"]);
+    let _ = ELEVATION_ENRICHMENT_TOTAL.with_label_values(&["success"]);
+    let _ = ELEVATION_ENRICHMENT_TOTAL.with_label_values(&["failure"]);
+    let _ = ELEVATION_ENRICHMENT_TOTAL.with_label_values(&["skipped"]);
     let _ = TRACK_SLOPE_RECALC_DURATION_SECONDS.with_label_values(&["success"]);
     let _ = TRACK_SLOPE_RECALC_DURATION_SECONDS.with_label_values(&["db_error"]);
+    let _ = TRACK_SLOPE_RECALC_DURATION_SECONDS.with_label_values(&["calculation_error"]);
 
     // Elevation API calls counter baseline
-    let _ = ELEVATION_API_CALLS_TOTAL.with_label_values(&["opentopodata"]);
+    let _ = ELEVATION_API_CALLS_TOTAL.with_label_values(&["srtm90m"]);
+    let _ = ELEVATION_API_CALLS_TOTAL.with_label_values(&["srtm30m"]);
     let _ = ELEVATION_API_CALLS_TOTAL.with_label_values(&["open-elevation"]);
 
     // Export/simplify/POI
@@ -571,6 +605,7 @@ pub fn initialize_metrics_baseline() {
     let _ = POIS_CREATED_TOTAL.with_label_values(&["manual"]);
     let _ = POIS_DELETED_TOTAL.with_label_values(&["unlink_track"]);
     let _ = POIS_DELETED_TOTAL.with_label_values(&["delete_poi"]);
+    let _ = POIS_DELETED_TOTAL.with_label_values(&["bulk_delete_pois"]);
 
     // Product observability
     let _ = TRACK_VIEWS_TOTAL.with_label_values(&["unknown", "direct"]);
@@ -591,6 +626,9 @@ pub fn initialize_metrics_baseline() {
     let _ = TRACK_EDITS_TOTAL.with_label_values(&["name"]);
     let _ = TRACK_EDITS_TOTAL.with_label_values(&["description"]);
     let _ = TRACK_EDITS_TOTAL.with_label_values(&["categories"]);
+    let _ = TRACK_EDITS_TOTAL.with_label_values(&["visibility"]);
+    let _ = TRACK_EDITS_TOTAL.with_label_values(&["classifications"]);
+    let _ = TRACK_EDITS_TOTAL.with_label_values(&["translation"]);
     let _ = TRACK_EXPORTS_TOTAL.with_label_values(&["gpx"]);
     let _ = TRACK_EXPORTS_TOTAL.with_label_values(&["kml"]);
     let _ = TRACK_EXPORTS_TOTAL.with_label_values(&["fit"]);
@@ -838,6 +876,16 @@ pub fn record_track_uploaded(source: &str) {
     TRACKS_UPLOADED_TOTAL.with_label_values(&[source]).inc();
 }
 
+/// Record a successfully stored upload by file format (gpx/kml)
+pub fn record_track_upload_format(format: &str) {
+    let fmt_label = match format {
+        "gpx" => "gpx",
+        "kml" => "kml",
+        _ => "other",
+    };
+    TRACK_UPLOAD_TOTAL.with_label_values(&[fmt_label]).inc();
+}
+
 pub fn record_track_deduplicated(reason: &str) {
     TRACKS_DEDUPLICATED_TOTAL.with_label_values(&[reason]).inc();
 }
@@ -846,6 +894,12 @@ pub fn record_track_deleted(result: &str) {
     TRACKS_DELETED_TOTAL.with_label_values(&[result]).inc();
 }
 
+pub fn record_tracks_deleted(result: &str, count: u64) {
+    TRACKS_DELETED_TOTAL
+        .with_label_values(&[result])
+        .inc_by(count);
+}
+
 pub fn record_track_category(category: &str) {
     TRACK_CATEGORIES_TOTAL.with_label_values(&[category]).inc();
 }
@@ -903,6 +957,9 @@ pub fn record_track_edit(field: &str) {
         "name" => "name",
         "description" => "description",
         "categories" => "categories",
+        "visibility" => "visibility",
+        "classifications" => "classifications",
+        "translation" => "translation",
         _ => "other",
     };
     TRACK_EDITS_TOTAL.with_label_values(&[field_label]).inc();
@@ -978,6 +1035,12 @@ pub fn record_poi_deleted(source: &str) {
     POIS_DELETED_TOTAL.with_label_values(&[source]).inc();
 }
 
+pub fn record_pois_deleted(source: &str, count: u64) {
+    POIS_DELETED_TOTAL
+        .with_label_values(&[source])
+        .inc_by(count);
+}
+
 pub fn record_bulk_operation(operation: &str, count: usize) {
     BULK_OPERATIONS_TOTAL.with_label_values(&[operation]).inc();
     BULK_OPERATIONS_ITEMS
@@ -1053,12 +1116,28 @@ pub fn record_track_enrich_status(status: &str) {
     TRACK_ENRICH_REQUESTS_TOTAL
         .with_label_values(&[status])
         .inc();
+    if let Some(outcome) = enrichment_outcome(status) {
+        ELEVATION_ENRICHMENT_TOTAL
+            .with_label_values(&[outcome])
+            .inc();
+    }
+}
+
+/// Collapse detailed enrichment statuses into success/failure/skipped.
+/// Intermediate states such as "queued" are not final outcomes and are not counted.
+fn enrichment_outcome(status: &str) -> Option<&'static str> {
+    match status {
+        "success" => Some("success"),
+        s if s.starts_with("skipped") => Some("skipped"),
+        s if s.starts_with("failed") || s == "queue_full" => Some("failure"),
+        _ => None,
+    }
 }
 
-/// Record elevation API calls (for Prometheus). Service name like "opentopodata" or "open-elevation".
-pub fn record_elevation_api_calls(service: &str, count: u32) {
+/// Record elevation API calls (for Prometheus). Dataset name like "srtm90m" or "open-elevation".
+pub fn record_elevation_api_calls(dataset: &str, count: u32) {
     ELEVATION_API_CALLS_TOTAL
-        .with_label_values(&[service])
+        .with_label_values(&[dataset])
         .inc_by(count as u64);
 }
 
@@ -1173,5 +1252,16 @@ mod tests {
         assert!(body_str.contains("track_edits_total"));
         assert!(body_str.contains("track_exports_total"));
         assert!(body_str.contains("trackly_session_heartbeat"));
+        assert!(body_str.contains("track_upload_total"));
+        assert!(body_str.contains("elevation_enrichment_total"));
+    }
+
+    #[test]
+    fn enrichment_outcome_collapses_statuses() {
+        assert_eq!(enrichment_outcome("success"), Some("success"));
+        assert_eq!(enrichment_outcome("skipped_not_needed"), Some("skipped"));
+        assert_eq!(enrichment_outcome("failed_remote"), Some("failure"));
+        assert_eq!(enrichment_outcome("queue_full"), Some("failure"));
+        assert_eq!(enrichment_outcome("queued"), None);
     }
 }
@@ -1,16 +1,39 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Serialize, serde::Deserialize)]
+#[derive(Serialize, serde::Deserialize, ToSchema)]
 pub struct TrackUploadResponse {
     pub id: Uuid,
     pub url: String,
 }
 
-#[derive(Serialize, serde::Deserialize)]
+#[derive(Serialize, ToSchema)]
+pub struct BatchUploadError {
+    pub filename: String,
+    pub error: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BatchUploadResponse {
+    pub uploaded: Vec<TrackUploadResponse>,
+    pub duplicates: Vec<Uuid>,
+    pub errors: Vec<BatchUploadError>,
+}
+
+/// `name`, `length_km` and `created_at` describe the existing track so the client can
+/// show what the upload would duplicate without a second request. They are left out
+/// unless the track is public or owned by the caller.
+#[derive(Default, Serialize, serde::Deserialize, ToSchema)]
 pub struct TrackExistResponse {
     pub is_exist: bool,
     pub id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_km: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,11 +45,13 @@ pub struct TrackListQuery {
     pub elevation_gain_max: Option<f32>,
     pub slope_min: Option<f32>,
     pub slope_max: Option<f32>,
+    /// `true` for loops only, `false` for point-to-point tracks only
+    pub is_loop: Option<bool>,
     /// When set, restrict results to tracks owned by this session (show private and public tracks)
     pub owner_session_id: Option<Uuid>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TrackListItem {
     pub id: Uuid,
     pub name: String,
@@ -38,10 +63,25 @@ pub struct TrackListItem {
     pub slope_min: Option<f32>,
     pub slope_max: Option<f32>,
     pub slope_avg: Option<f32>,
+    pub cadence_min: Option<i32>,
+    pub cadence_max: Option<i32>,
+    pub is_loop: bool,
+    pub is_public: bool,
     pub url: String,
 }
 
-#[derive(Debug, Serialize)]
+/// Distance, climb and duration of one segment of a multi-segment track. Elevation and
+/// duration are `None` when the stored per-point data does not cover the segment.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrackSegmentStats {
+    pub segment_index: usize,
+    pub length_km: f64,
+    pub elevation_gain: Option<f32>,
+    pub elevation_loss: Option<f32>,
+    pub duration_seconds: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GapEndpoint {
     pub lat: f64,
     pub lon: f64,
@@ -49,7 +89,7 @@ pub struct GapEndpoint {
     pub point_index: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GapInfo {
     pub kind: String, // "segment" or "pause"
     pub from: GapEndpoint,
@@ -58,7 +98,64 @@ pub struct GapInfo {
     pub duration_seconds: Option<i64>,
 }
 
-#[derive(Serialize)]
+/// A gap the track could be split at, from `GET /tracks/{id}/split-suggestions`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SplitSuggestion {
+    #[serde(rename = "type")]
+    pub kind: String, // "segment" or "pause"
+    pub distance_m: f64,
+    pub duration_seconds: Option<i64>,
+    pub from: GapEndpoint,
+    pub to: GapEndpoint,
+}
+
+/// How a track compares with public tracks of the same activity and similar length,
+/// from `GET /tracks/{id}/comparable-stats`. Percentiles are the share of peers (0-100)
+/// with a lower value, `None` when the track or its peers lack the metric.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComparableStats {
+    pub peer_count: u64,
+    pub percentile_speed: Option<f64>,
+    pub percentile_hr: Option<f64>,
+    pub peer_avg_speed: Option<f64>,
+    pub peer_median_speed: Option<f64>,
+    pub peer_stddev_speed: Option<f64>,
+    pub peer_avg_hr: Option<f64>,
+    pub peer_median_hr: Option<f64>,
+    pub peer_stddev_hr: Option<f64>,
+}
+
+/// Weather during a track, from `GET /tracks/{id}/weather`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackWeather {
+    pub temperature_c: f64,
+    pub wind_speed_kmh: f64,
+    pub precipitation_mm: f64,
+    pub description: String,
+}
+
+/// Hourly historical observation as fetched and cached; `weather_code` is a WMO code
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherObservation {
+    pub temperature_c: f64,
+    pub wind_speed_kmh: f64,
+    pub precipitation_mm: f64,
+    pub weather_code: i32,
+}
+
+impl From<GapInfo> for SplitSuggestion {
+    fn from(gap: GapInfo) -> Self {
+        SplitSuggestion {
+            kind: gap.kind,
+            distance_m: gap.distance_m,
+            duration_seconds: gap.duration_seconds,
+            from: gap.from,
+            to: gap.to,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct TrackDetail {
     pub id: Uuid,
     pub name: String,
@@ -67,10 +164,15 @@ pub struct TrackDetail {
     pub geom_geojson: serde_json::Value, // Store geometry as GeoJSON for API
     pub segment_gaps: Option<Vec<GapInfo>>, // Teleport gaps between segments
     pub pause_gaps: Option<Vec<GapInfo>>, // Time-based gaps on continuous tracks
+    pub segments: Option<Vec<TrackSegmentStats>>, // Only for tracks with several segments
     pub length_km: f64,
     pub elevation_profile: Option<serde_json::Value>, // Keep as JSON for API flexibility
     pub hr_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
     pub temp_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
+    pub cadence_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
+    // Only used for GPX export; clients fetch the series from /tracks/{id}/power
+    #[serde(skip)]
+    pub power_data: Option<serde_json::Value>,
     pub time_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
     // Unified elevation fields
     pub elevation_gain: Option<f32>,
@@ -90,21 +192,38 @@ pub struct TrackDetail {
     pub avg_hr: Option<i32>,
     pub hr_min: Option<i32>,
     pub hr_max: Option<i32>,
+    pub cadence_avg: Option<i32>,
+    pub cadence_min: Option<i32>,
+    pub cadence_max: Option<i32>,
+    pub power_avg: Option<i32>,
+    pub power_max: Option<i32>,
+    /// Normalized power (30 s rolling average, 4th-power mean) in watts
+    pub power_normalized: Option<i32>,
     pub moving_time: Option<i32>,
     pub pause_time: Option<i32>,
     pub moving_avg_speed: Option<f64>,
     pub moving_avg_pace: Option<f64>,
+    pub moving_distance_km: Option<f64>,
+    /// Start and end points lie within `TRACK_LOOP_THRESHOLD_M` of each other
+    pub is_loop: bool,
+    pub elevation_source: Option<String>,
+    pub classification_confidence: Option<serde_json::Value>,
     pub duration_seconds: Option<i32>,
     pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     pub session_id: Option<Uuid>, // Add session_id for owner check
+    // Provenance from the GPX <metadata> element
+    pub metadata_author: Option<String>,
+    pub metadata_link: Option<String>,
+    pub metadata_copyright: Option<String>,
+    pub is_public: bool,
     pub auto_classifications: Vec<String>, // Automatically determined track classifications
     pub speed_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
     pub pace_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TrackSimplified {
     pub id: Uuid,
     pub name: String,
@@ -118,6 +237,7 @@ pub struct TrackSimplified {
     pub elevation_profile: Option<serde_json::Value>,
     pub hr_data: Option<serde_json::Value>,
     pub temp_data: Option<serde_json::Value>,
+    pub cadence_data: Option<serde_json::Value>,
     pub time_data: Option<serde_json::Value>,
     // Unified elevation fields
     pub elevation_gain: Option<f32>,
@@ -137,21 +257,37 @@ pub struct TrackSimplified {
     pub avg_hr: Option<i32>,
     pub hr_min: Option<i32>,
     pub hr_max: Option<i32>,
+    pub cadence_avg: Option<i32>,
+    pub cadence_min: Option<i32>,
+    pub cadence_max: Option<i32>,
+    pub power_avg: Option<i32>,
+    pub power_max: Option<i32>,
+    /// Normalized power (30 s rolling average, 4th-power mean) in watts
+    pub power_normalized: Option<i32>,
     pub moving_time: Option<i32>,
     pub pause_time: Option<i32>,
     pub moving_avg_speed: Option<f64>,
     pub moving_avg_pace: Option<f64>,
+    pub moving_distance_km: Option<f64>,
+    /// Start and end points lie within `TRACK_LOOP_THRESHOLD_M` of each other
+    pub is_loop: bool,
+    pub elevation_source: Option<String>,
+    pub classification_confidence: Option<serde_json::Value>,
     pub duration_seconds: Option<i32>,
     pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     pub session_id: Option<Uuid>,
+    // Provenance from the GPX <metadata> element
+    pub metadata_author: Option<String>,
+    pub metadata_link: Option<String>,
+    pub metadata_copyright: Option<String>,
     pub auto_classifications: Vec<String>,
     pub speed_data: Option<serde_json::Value>, // Store as JSON for compatibility with DB jsonb
     pub pace_data: Option<serde_json::Value>,  // Store as JSON for compatibility with DB jsonb
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 pub struct TrackGeoJsonFeature {
     #[serde(rename = "type")]
     pub type_field: String,
@@ -159,14 +295,20 @@ pub struct TrackGeoJsonFeature {
     pub properties: serde_json::Value,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 pub struct TrackGeoJsonCollection {
     #[serde(rename = "type")]
     pub type_field: String,
     pub features: Vec<TrackGeoJsonFeature>,
+    /// Number of tracks matching the filters, including ones too small to draw at this zoom
+    pub total: i64,
+    /// Set when `features` was cut off at the `max_tracks` limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TrackGeoJsonQuery {
     pub bbox: Option<String>,
     pub zoom: Option<f64>,
@@ -182,6 +324,110 @@ pub struct TrackGeoJsonQuery {
     pub slope_max: Option<f32>,
     /// When set, restrict results to tracks owned by this session (show private and public tracks)
     pub owner_session_id: Option<Uuid>,
+    /// ISO-8601 bounds on when the activity was recorded (inclusive)
+    pub recorded_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub recorded_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub sort_by: Option<SortField>,
+    pub sort_dir: Option<SortDirection>,
+    /// `true` for loops only, `false` for point-to-point tracks only
+    pub is_loop: Option<bool>,
+    /// Lowest recorded cadence must be at least this
+    pub cadence_min: Option<i32>,
+    /// Highest recorded cadence must be at most this
+    pub cadence_max: Option<i32>,
+    /// At most this many tracks are returned (default `MAX_TRACKS_PER_REQUEST` or 500,
+    /// never more than 5000); narrow large result sets with `bbox` or filters
+    pub max_tracks: Option<u32>,
+}
+
+/// Fields the track list can be ordered by; unknown values are rejected during deserialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    CreatedAt,
+    Length,
+    ElevationGain,
+    RecordedAt,
+    Name,
+}
+
+impl SortField {
+    /// Column to order by; fixed per variant so user input never reaches the SQL text
+    pub fn column(self) -> &'static str {
+        match self {
+            SortField::CreatedAt => "created_at",
+            SortField::Length => "length_km",
+            SortField::ElevationGain => "elevation_gain",
+            SortField::RecordedAt => "recorded_at",
+            SortField::Name => "name",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
+/// Numeric filters are bound as REAL/DOUBLE parameters; NaN and infinities would compare
+/// unpredictably there, so they are rejected before any query is built
+fn all_finite(values: &[Option<f64>]) -> bool {
+    values.iter().flatten().all(|v| v.is_finite())
+}
+
+impl TrackGeoJsonQuery {
+    pub fn has_finite_numeric_filters(&self) -> bool {
+        all_finite(&[
+            self.zoom,
+            self.min_length,
+            self.max_length,
+            self.elevation_gain_min.map(f64::from),
+            self.elevation_gain_max.map(f64::from),
+            self.slope_min.map(f64::from),
+            self.slope_max.map(f64::from),
+        ])
+    }
+}
+
+impl TrackSimplificationQuery {
+    pub fn has_finite_zoom(&self) -> bool {
+        all_finite(&[self.zoom])
+    }
+}
+
+impl TrackListQuery {
+    pub fn has_finite_numeric_filters(&self) -> bool {
+        all_finite(&[
+            self.min_length,
+            self.max_length,
+            self.elevation_gain_min.map(f64::from),
+            self.elevation_gain_max.map(f64::from),
+            self.slope_min.map(f64::from),
+            self.slope_max.map(f64::from),
+        ])
+    }
+}
+
+/// Both bounds are optional; when both are given the range must not be inverted
+pub fn is_valid_recorded_range(
+    after: Option<chrono::DateTime<chrono::Utc>>,
+    before: Option<chrono::DateTime<chrono::Utc>>,
+) -> bool {
+    match (after, before) {
+        (Some(after), Some(before)) => after <= before,
+        _ => true,
+    }
 }
 
 // Custom deserializer to handle both comma-separated string and array formats
@@ -240,6 +486,8 @@ pub struct ParsedTrackData {
     pub elevation_profile: Option<Vec<Option<f64>>>,
     pub hr_data: Option<Vec<Option<i32>>>,
     pub temp_data: Option<Vec<Option<f64>>>,
+    pub cadence_data: Option<Vec<Option<i32>>>,
+    pub power_data: Option<Vec<Option<i32>>>, // Watts from a power meter
     pub time_data: Option<Vec<Option<chrono::DateTime<chrono::Utc>>>>,
     // Unified elevation fields
     pub elevation_gain: Option<f32>,
@@ -256,73 +504,417 @@ pub struct ParsedTrackData {
     pub avg_hr: Option<i32>,
     pub hr_min: Option<i32>,
     pub hr_max: Option<i32>,
+    pub cadence_avg: Option<i32>,
+    pub cadence_min: Option<i32>,
+    pub cadence_max: Option<i32>,
+    pub power_avg: Option<i32>,
+    pub power_max: Option<i32>,
+    /// Normalized power (30 s rolling average, 4th-power mean) in watts
+    pub power_normalized: Option<i32>,
     pub moving_time: Option<i32>,
     pub pause_time: Option<i32>,
     pub moving_avg_speed: Option<f64>,
     pub moving_avg_pace: Option<f64>,
+    pub moving_distance_km: Option<f64>,
+    /// Start and end points lie within `TRACK_LOOP_THRESHOLD_M` of each other
+    pub is_loop: bool,
+    pub elevation_source: Option<crate::track_utils::ElevationSource>,
+    /// Label -> confidence map for `auto_classifications`
+    pub classification_confidence: serde_json::Value,
     pub duration_seconds: Option<i32>,
     pub hash: String,
     pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// `<metadata><author><name>`
+    pub metadata_author: Option<String>,
+    /// `<metadata><link href>`, only kept for http(s) URLs
+    pub metadata_link: Option<String>,
+    /// Copyright holder from `<metadata><copyright author>`
+    pub metadata_copyright: Option<String>,
     pub auto_classifications: Vec<String>, // Result of automatic track classification
     pub speed_data: Option<Vec<Option<f64>>>, // Point-by-point speed data (km/h)
     pub pace_data: Option<Vec<Option<f64>>>, // Point-by-point pace data (min/km)
     pub waypoints: Vec<ParsedWaypoint>,    // Waypoints/POIs from GPX file
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTrackDescriptionRequest {
     pub description: String,
     pub session_id: Uuid,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTrackNameRequest {
     pub name: String,
     pub session_id: Uuid,
 }
 
-#[derive(Debug, Deserialize)]
+/// Name and owner of the copy created by `POST /tracks/{id}/clone`
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CloneTrackRequest {
+    pub name: String,
+    pub session_id: Uuid,
+}
+
+/// Track name and description in one language
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrackTranslation {
+    pub language_code: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertTrackTranslationRequest {
+    pub language_code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteTrackTranslationRequest {
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TrackCollectionExportQuery {
+    /// Comma separated track ids
+    pub ids: String,
+    /// Session of the caller; its private tracks are included
+    pub session_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkDeleteTracksRequest {
+    pub track_ids: Vec<Uuid>,
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeleteTracksResponse {
+    pub deleted_count: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTrackVisibilityRequest {
+    pub is_public: bool,
+    pub session_id: Uuid,
+}
+
+/// Auto classifications of a track with per-label confidence and reasoning
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackClassificationsResponse {
+    pub classifications: Vec<String>,
+    pub confidence: std::collections::BTreeMap<String, f64>,
+    pub reasoning: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReclassifyTrackRequest {
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub session_id: Uuid,
+    pub events: Vec<String>,
+    /// Optional shared secret; generated when omitted
+    pub secret: Option<String>,
+}
+
+/// Session for `GET /tracks/my`; the `X-Session-Id` header works as well
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MyTracksQuery {
+    pub session_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebhookSessionQuery {
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteWebhookRequest {
+    pub session_id: Uuid,
+}
+
+/// Registered webhook as shown to its owner (without the secret)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookInfo {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Returned once on creation so the owner can verify signatures
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: String,
+}
+
+/// Delivery target loaded when an event fires
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Entry of the category taxonomy as stored in the `categories` table
+#[derive(Debug, Clone, Serialize)]
+pub struct Category {
+    pub slug: String,
+    pub display_name: String,
+    pub parent_slug: Option<String>,
+}
+
+/// Category with its subcategories, as returned by `GET /categories`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CategoryNode {
+    pub slug: String,
+    pub display_name: String,
+    #[schema(no_recursion)]
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTrackCategoriesRequest {
     pub categories: Vec<String>,
     pub session_id: Uuid,
+    /// Replaces the automatically detected activity types when the classifier got them wrong
+    pub auto_classifications: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TrackSearchQuery {
     pub query: String,
+    pub recorded_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub recorded_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Minimum trigram similarity of the name for fuzzy matches; defaults to 0.2, range 0.0-1.0
+    pub similarity_threshold: Option<f64>,
+    /// Page size; defaults to 50, at most 500
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct MapInteractionEvent {
     pub action: String,
     pub zoom: Option<f64>,
     pub session_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Lightweight elevation chart payload: elevation values paired with cumulative distance
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ElevationProfileResponse {
+    pub distance_m: Vec<f64>,
+    pub elevation_m: Vec<f64>,
+    pub length_km: f64,
+}
+
+/// Pace chart payload: filtered pace in min/km paired with cumulative distance
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaceChartResponse {
+    pub distance_m: Vec<f64>,
+    pub pace_min_per_km: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ElevationHistogramQuery {
+    /// Number of equal-width elevation bins; defaults to 20, at most 100
+    pub buckets: Option<usize>,
+}
+
+/// Points and distance recorded within one elevation band
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ElevationHistogramBucket {
+    pub from_m: f64,
+    pub to_m: f64,
+    pub count: usize,
+    pub distance_m: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ElevationHistogramResponse {
+    pub buckets: Vec<ElevationHistogramBucket>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PowerZonesQuery {
+    /// Functional threshold power of the rider in watts
+    pub ftp: u32,
+}
+
+/// Time spent in one Coggan power zone; `max_watts` is `None` for the open-ended zone 7
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PowerZone {
+    pub zone: u8,
+    pub name: String,
+    pub min_watts: i32,
+    pub max_watts: Option<i32>,
+    pub seconds: u32,
+    pub percent: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PowerZonesResponse {
+    pub ftp: u32,
+    pub total_seconds: u32,
+    pub zones: Vec<PowerZone>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TrackHeatmapQuery {
+    pub bbox: Option<String>, // "minLon,minLat,maxLon,maxLat"
+    /// Coarsens the grid to one tile at this zoom split into `resolution` cells
+    pub zoom: Option<f64>,
+    /// Number of grid cells across the bbox (or the tile at `zoom`); defaults to 100
+    pub resolution: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SimilarTracksQuery {
+    /// Minimum similarity in (0, 1]; defaults to 0.95
+    pub threshold: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TrackSimplificationQuery {
     pub zoom: Option<f64>,
     pub mode: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+/// Short reference to a track used in aggregate statistics
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackStatsEntry {
+    pub id: Uuid,
+    pub name: String,
+    pub length_km: f64,
+}
+
+/// Numeric field whose value changed after a GPX export/import round trip
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoundtripDelta {
+    pub original: f64,
+    pub roundtrip: f64,
+    pub delta_pct: f64,
+}
+
+/// Report of which track fields survive a GPX export followed by re-import
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct GpxRoundtripDiff {
+    pub preserved: Vec<String>,
+    pub lost: Vec<String>,
+    pub degraded: std::collections::BTreeMap<String, RoundtripDelta>,
+}
+
+/// Aggregate statistics across all public tracks
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackStats {
+    pub total_tracks: i64,
+    pub total_length_km: f64,
+    pub total_elevation_gain: f64,
+    pub avg_length_km: Option<f64>,
+    /// Number of tracks per auto-classification (e.g. running, cycling, hiking)
+    pub classifications: std::collections::BTreeMap<String, i64>,
+    pub longest_track: Option<TrackStatsEntry>,
+    pub shortest_track: Option<TrackStatsEntry>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TrackSearchResult {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub categories: Vec<String>,
     pub length_km: f64,
+    pub recorded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub elevation_gain: Option<f32>,
+    pub avg_speed: Option<f64>,
+    pub is_public: bool,
     pub url: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// One page of search results plus the number of matches across all pages
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackSearchResponse {
+    pub tracks: Vec<TrackSearchResult>,
+    pub total_count: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GenerateThumbnailRequest {
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTrackShareRequest {
+    pub session_id: Uuid,
+    /// How long the link stays valid, 1 to 720 hours
+    pub expires_in_hours: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrackShareResponse {
+    /// Path of the shared view, `/tracks/shared/{token}`
+    pub url: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct EnrichElevationRequest {
     pub force: Option<bool>,
     pub dataset: Option<String>,
     pub session_id: Uuid,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecalculateSlopesRequest {
+    pub session_id: Uuid,
+    /// Leave a track that already has slope segments untouched; slopes are always
+    /// recalculated unless this is set
+    pub skip_if_calculated: Option<bool>,
+    /// Elevation smoothing half-window in meters; falls back to the env-configured default
+    pub smoothing_window_m: Option<f64>,
+}
+
+impl RecalculateSlopesRequest {
+    /// Upper bound for the smoothing half-window to keep recalculation cost reasonable
+    pub const MAX_SMOOTHING_WINDOW_M: f64 = 1000.0;
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.session_id.is_nil() {
+            return Err("session_id must not be nil");
+        }
+        if let Some(window) = self.smoothing_window_m
+            && !(window > 0.0 && window <= Self::MAX_SMOOTHING_WINDOW_M)
+        {
+            return Err("smoothing_window_m must be within (0, 1000] meters");
+        }
+        Ok(())
+    }
+
+    /// Whether a track with existing slope segments should be left as it is; a requested
+    /// smoothing window always recalculates, since the stored slopes may use another one
+    pub fn skips_calculated_track(&self) -> bool {
+        self.skip_if_calculated.unwrap_or(false) && self.smoothing_window_m.is_none()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct EnrichElevationResponse {
     pub id: Uuid,
     pub message: String,
@@ -334,6 +926,51 @@ pub struct EnrichElevationResponse {
     pub enriched_at: Option<chrono::NaiveDateTime>,
 }
 
+/// External API calls made on one day, from `GET /admin/api-usage`
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ApiUsageStat {
+    pub date: chrono::NaiveDate,
+    pub api_calls_count: i32,
+}
+
+/// Background elevation enrichment that failed, from `GET /admin/enrichment-failures`
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct EnrichmentFailure {
+    pub track_id: Uuid,
+    pub attempted_at: chrono::DateTime<chrono::Utc>,
+    pub error_message: String,
+    pub retry_count: i32,
+}
+
+/// Identifies the caller of admin endpoints; must match `ADMIN_SESSION_ID`
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AdminQuery {
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ApiUsageQuery {
+    /// Service name usage was recorded under, e.g. the elevation dataset
+    pub service: String,
+    /// Number of days to look back (1-365, default 30)
+    pub days: Option<i32>,
+}
+
+/// Body of `GET /health`
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    pub status: String, // "healthy", "degraded" or "unhealthy"
+    pub db: DbHealth,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DbHealth {
+    pub connected: bool,
+    pub pool_size: u32,
+    pub idle_connections: u32,
+}
+
 /// Track data needed for elevation enrichment
 #[derive(Debug)]
 pub struct TrackForElevationEnrichment {
@@ -353,6 +990,55 @@ pub struct TrackForElevationEnrichment {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sort_params_deserialize() {
+        let query: TrackGeoJsonQuery =
+            serde_json::from_str(r#"{"sort_by":"elevation_gain","sort_dir":"asc"}"#).unwrap();
+        assert_eq!(query.sort_by, Some(SortField::ElevationGain));
+        assert_eq!(query.sort_dir, Some(SortDirection::Asc));
+
+        let invalid = serde_json::from_str::<TrackGeoJsonQuery>(r#"{"sort_by":"id; DROP"}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_recorded_range_validation() {
+        let summer_start = "2024-06-01T00:00:00Z".parse().ok();
+        let summer_end = "2024-08-31T23:59:59Z".parse().ok();
+
+        assert!(is_valid_recorded_range(summer_start, summer_end));
+        assert!(is_valid_recorded_range(summer_start, None));
+        assert!(is_valid_recorded_range(None, summer_end));
+        assert!(!is_valid_recorded_range(summer_end, summer_start));
+    }
+
+    #[test]
+    fn test_numeric_filters_must_be_finite() {
+        let mut query: TrackGeoJsonQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.has_finite_numeric_filters());
+        query.slope_min = Some(f32::MIN);
+        query.slope_max = Some(f32::MAX);
+        query.elevation_gain_min = Some(0.0);
+        assert!(query.has_finite_numeric_filters());
+
+        for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            query.slope_max = Some(bad);
+            assert!(!query.has_finite_numeric_filters());
+        }
+        query.slope_max = None;
+        query.zoom = Some(f64::NAN);
+        assert!(!query.has_finite_numeric_filters());
+
+        let mut list_query: TrackListQuery = serde_json::from_str("{}").unwrap();
+        list_query.elevation_gain_max = Some(f32::INFINITY);
+        assert!(!list_query.has_finite_numeric_filters());
+
+        let mut simplification: TrackSimplificationQuery = serde_json::from_str("{}").unwrap();
+        assert!(simplification.has_finite_zoom());
+        simplification.zoom = Some(f64::NAN);
+        assert!(!simplification.has_finite_zoom());
+    }
+
     #[test]
     fn test_track_upload_response_serde() {
         let resp = TrackUploadResponse {
@@ -369,10 +1055,50 @@ mod tests {
         let resp = TrackExistResponse {
             is_exist: true,
             id: Some(Uuid::new_v4()),
+            name: Some("Morning Run".to_string()),
+            length_km: Some(42.2),
+            created_at: None,
         };
         let json = serde_json::to_string(&resp).unwrap();
         let de: TrackExistResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(resp.is_exist, de.is_exist);
+        assert_eq!(de.name.as_deref(), Some("Morning Run"));
+        assert_eq!(de.length_km, Some(42.2));
+    }
+
+    #[test]
+    fn test_track_geojson_collection_serializes_total() {
+        let collection = TrackGeoJsonCollection {
+            type_field: "FeatureCollection".to_string(),
+            features: vec![],
+            total: 42,
+            warning: None,
+        };
+        let json = serde_json::to_value(&collection).unwrap();
+        assert_eq!(json["type"], "FeatureCollection");
+        assert_eq!(json["total"], 42);
+        assert!(json.get("warning").is_none());
+    }
+
+    #[test]
+    fn test_track_search_result_serializes_summary_fields() {
+        let result = TrackSearchResult {
+            id: Uuid::new_v4(),
+            name: "Morning run".to_string(),
+            description: None,
+            categories: vec!["running".to_string()],
+            length_km: 10.5,
+            recorded_at: Some("2025-06-01T07:30:00Z".parse().unwrap()),
+            elevation_gain: Some(120.0),
+            avg_speed: None,
+            is_public: true,
+            url: "/tracks/1".to_string(),
+        };
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["recorded_at"], "2025-06-01T07:30:00Z");
+        assert_eq!(json["elevation_gain"], 120.0);
+        assert!(json["avg_speed"].is_null());
+        assert_eq!(json["is_public"], true);
     }
 
     // Track optimization related tests
@@ -391,6 +1117,14 @@ mod tests {
             slope_min: None,
             slope_max: None,
             owner_session_id: None,
+            recorded_after: None,
+            recorded_before: None,
+            sort_by: None,
+            sort_dir: None,
+            is_loop: None,
+            cadence_min: None,
+            cadence_max: None,
+            max_tracks: None,
         };
 
         assert_eq!(query_overview.zoom, Some(10.0));
@@ -408,6 +1142,14 @@ mod tests {
             slope_min: None,
             slope_max: None,
             owner_session_id: None,
+            recorded_after: None,
+            recorded_before: None,
+            sort_by: None,
+            sort_dir: None,
+            is_loop: None,
+            cadence_min: None,
+            cadence_max: None,
+            max_tracks: None,
         };
 
         assert_eq!(query_detail.zoom, Some(15.0));
@@ -503,7 +1245,7 @@ mod tests {
 // ============================================================================
 
 /// POI structure from database
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Poi {
     pub id: i32,
     pub name: String,
@@ -519,7 +1261,7 @@ pub struct Poi {
 }
 
 /// POI with distance and sequence information from track association
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PoiWithDistance {
     #[serde(flatten)]
     pub poi: Poi,
@@ -528,21 +1270,27 @@ pub struct PoiWithDistance {
 }
 
 /// Request to create a new POI
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreatePoiRequest {
     pub name: String,
     pub description: Option<String>,
     pub category: Option<String>,
     pub elevation: Option<f32>,
-    pub lat: f64,
-    pub lon: f64,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    /// GeoJSON Point geometry (`[lon, lat]`); takes precedence over `lat`/`lon`
+    #[schema(value_type = Option<Object>)]
+    pub geometry: Option<serde_json::Value>,
     pub session_id: Option<Uuid>,
 }
 
 /// Query parameters for listing POIs
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PoiQuery {
     pub bbox: Option<String>, // "minLon,minLat,maxLon,maxLat"
+    /// Comma-separated list or repeated parameter; matches any of the given categories
+    #[serde(default, deserialize_with = "deserialize_categories")]
     pub categories: Option<Vec<String>>,
     pub track_id: Option<Uuid>,
     pub search: Option<String>,
@@ -551,7 +1299,7 @@ pub struct PoiQuery {
 }
 
 /// Response for POI list endpoint
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PoiListResponse {
     pub pois: Vec<Poi>,
     pub total: i64,
@@ -568,8 +1316,20 @@ pub struct ParsedWaypoint {
     pub elevation: Option<f32>,
 }
 
+/// Request to delete several POIs at once
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkDeletePoisRequest {
+    pub poi_ids: Vec<i32>,
+    pub session_id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkDeletePoisResponse {
+    pub deleted_count: u64,
+}
+
 /// Request to delete a POI
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct DeletePoiRequest {
     pub session_id: Option<Uuid>,
 }
@@ -0,0 +1,140 @@
+//! OpenAPI description of the HTTP API, served at `/openapi.json` with Swagger UI at `/docs`.
+
+use crate::handlers;
+use utoipa::{OpenApi, ToSchema};
+
+/// Multipart body of `POST /tracks/exist` (documentation only)
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct TrackFileForm {
+    /// GPX or KML file
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+/// Multipart body of `POST /tracks/upload` (documentation only)
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct TrackUploadForm {
+    /// GPX or KML file
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Comma-separated category slugs
+    pub categories: String,
+    pub session_id: Option<String>,
+}
+
+/// Multipart body of `POST /tracks/batch-upload` (documentation only)
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct BatchUploadForm {
+    /// One `file` part per GPX or KML file
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub file: Vec<Vec<u8>>,
+    /// Comma-separated category slugs applied to every file
+    pub categories: Option<String>,
+    pub session_id: String,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Trackly API", description = "Upload, browse and analyse GPS tracks"),
+    paths(
+        handlers::health,
+        handlers::sitemap,
+        handlers::get_api_usage,
+        handlers::list_orphaned_pois,
+        handlers::delete_orphaned_pois,
+        handlers::list_enrichment_failures,
+        handlers::retry_enrichment_failure,
+        handlers::debug_background_task,
+        handlers::upload_track,
+        handlers::batch_upload_tracks,
+        handlers::check_track_exist,
+        handlers::list_tracks_geojson,
+        handlers::list_my_tracks,
+        handlers::search_tracks,
+        handlers::get_track_stats,
+        handlers::find_similar_tracks,
+        handlers::get_track_heatmap,
+        handlers::get_categories,
+        handlers::get_track,
+        handlers::delete_track,
+        handlers::bulk_delete_tracks,
+        handlers::get_track_simplified,
+        handlers::get_track_split_suggestions,
+        handlers::get_track_enrichment_status,
+        handlers::get_track_comparable_stats,
+        handlers::get_track_weather,
+        handlers::generate_track_thumbnail,
+        handlers::get_track_thumbnail,
+        handlers::create_track_share,
+        handlers::clone_track,
+        handlers::get_shared_track,
+        handlers::update_track_description,
+        handlers::update_track_name,
+        handlers::update_track_categories,
+        handlers::update_track_visibility,
+        handlers::upsert_track_translation,
+        handlers::list_track_translations,
+        handlers::delete_track_translation,
+        handlers::get_track_classifications,
+        handlers::reclassify_track,
+        handlers::export_track_gpx,
+        handlers::export_track_collection,
+        handlers::get_track_gpx_roundtrip_diff,
+        handlers::enrich_elevation,
+        handlers::get_track_elevation_profile,
+        handlers::get_track_elevation_histogram,
+        handlers::get_track_3d_profile,
+        handlers::get_track_pace_chart,
+        handlers::get_track_power_zones,
+        handlers::get_track_slope_profile,
+        handlers::recalculate_track_slopes,
+        handlers::record_map_interaction,
+        handlers::get_pois,
+        handlers::create_poi,
+        handlers::get_poi,
+        handlers::delete_poi,
+        handlers::bulk_delete_pois,
+        handlers::get_track_pois,
+        handlers::unlink_track_poi,
+        handlers::create_webhook,
+        handlers::list_webhooks,
+        handlers::delete_webhook,
+    ),
+    tags(
+        (name = "tracks", description = "Track upload, listing and editing"),
+        (name = "elevation", description = "Elevation and slope data"),
+        (name = "pois", description = "Points of interest"),
+        (name = "categories", description = "Category taxonomy"),
+        (name = "webhooks", description = "Event notifications"),
+        (name = "observability", description = "Client telemetry"),
+        (name = "system", description = "Health and housekeeping"),
+    )
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_documents_every_route() {
+        let spec = ApiDoc::openapi();
+        for path in [
+            "/tracks",
+            "/tracks/{id}",
+            "/tracks/upload",
+            "/pois/{id}",
+            "/webhooks/{id}",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing {path}");
+        }
+        let schemas = spec.components.expect("components").schemas;
+        assert!(schemas.contains_key("TrackDetail"));
+        assert!(schemas.contains_key("PoiListResponse"));
+    }
+}
@@ -3,12 +3,15 @@
 // Optimized with bulk operations for better performance
 
 use crate::models::ParsedWaypoint;
-use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder, Row};
 use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// POIs farther than this from an edited track line are unlinked from it
+const RELINK_MAX_OFFSET_M: f64 = 200.0;
+
 pub struct PoiDeduplicationService;
 
 impl PoiDeduplicationService {
@@ -50,7 +53,7 @@ impl PoiDeduplicationService {
     /// Bulk insert or update POIs using QueryBuilder
     /// Returns vector of (index, poi_id) for successfully inserted/updated POIs
     pub async fn bulk_find_or_create_pois(
-        pool: &PgPool,
+        conn: &mut PgConnection,
         waypoints: &[ParsedWaypoint],
     ) -> Result<Vec<(usize, i32)>, sqlx::Error> {
         if waypoints.is_empty() {
@@ -120,7 +123,7 @@ impl PoiDeduplicationService {
         );
 
         let query = query_builder.build();
-        let rows = query.fetch_all(pool).await?;
+        let rows = query.fetch_all(&mut *conn).await?;
 
         let elapsed = start.elapsed().as_secs_f64();
         crate::metrics::observe_db_query("bulk_insert_pois", elapsed);
@@ -150,7 +153,7 @@ impl PoiDeduplicationService {
     /// Bulk link POIs to track with distance calculation
     /// Uses a single query with UNNEST for all links
     pub async fn bulk_link_pois_to_track(
-        pool: &PgPool,
+        conn: &mut PgConnection,
         track_id: Uuid,
         poi_ids_with_order: &[(i32, i32)], // (poi_id, sequence_order)
     ) -> Result<usize, sqlx::Error> {
@@ -183,7 +186,7 @@ impl PoiDeduplicationService {
         .bind(track_id)
         .bind(&poi_ids)
         .bind(&sequence_orders)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
         let elapsed = start.elapsed().as_secs_f64();
@@ -200,9 +203,9 @@ impl PoiDeduplicationService {
     }
 
     /// Optimized batch link POIs to track using bulk operations
-    /// Single DB roundtrip for inserts, single for links
+    /// Single DB roundtrip for inserts, single for links; runs on the caller's transaction
     pub async fn link_pois_to_track(
-        pool: &PgPool,
+        conn: &mut PgConnection,
         track_id: Uuid,
         waypoints: Vec<ParsedWaypoint>,
     ) -> Result<usize, sqlx::Error> {
@@ -220,7 +223,7 @@ impl PoiDeduplicationService {
         let pipeline_start = Instant::now();
 
         // Step 1: Bulk insert/update all POIs
-        let poi_results = Self::bulk_find_or_create_pois(pool, &waypoints).await?;
+        let poi_results = Self::bulk_find_or_create_pois(&mut *conn, &waypoints).await?;
 
         if poi_results.is_empty() {
             warn!("No POIs were created for track {}", track_id);
@@ -235,7 +238,7 @@ impl PoiDeduplicationService {
 
         // Step 3: Bulk link all POIs to track
         let linked_count =
-            Self::bulk_link_pois_to_track(pool, track_id, &poi_ids_with_order).await?;
+            Self::bulk_link_pois_to_track(&mut *conn, track_id, &poi_ids_with_order).await?;
 
         let pipeline_elapsed = pipeline_start.elapsed().as_secs_f64();
         crate::metrics::observe_poi_link_duration("link_pois_to_track", pipeline_elapsed);
@@ -255,6 +258,86 @@ impl PoiDeduplicationService {
         Ok(linked_count)
     }
 
+    /// Re-project a track's linked POIs onto its new geometry after the line was edited.
+    /// Recomputes `distance_from_start_m` and `sequence_order` and unlinks POIs that no longer
+    /// lie within `RELINK_MAX_OFFSET_M` of the new line. Coordinates are (lat, lon).
+    pub async fn relink_pois_after_geometry_change(
+        pool: &PgPool,
+        track_id: Uuid,
+        new_coordinates: &[(f64, f64)],
+    ) -> Result<(), sqlx::Error> {
+        if new_coordinates.len() < 2 {
+            debug!(
+                "Skipping POI relink for track {}: geometry has fewer than 2 points",
+                track_id
+            );
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let lats: Vec<f64> = new_coordinates.iter().map(|(lat, _)| *lat).collect();
+        let lons: Vec<f64> = new_coordinates.iter().map(|(_, lon)| *lon).collect();
+
+        let result = sqlx::query(
+            r#"
+            WITH line AS (
+                SELECT ST_SetSRID(ST_MakeLine(ARRAY(
+                    SELECT ST_MakePoint(c.lon, c.lat)
+                    FROM UNNEST($2::float8[], $3::float8[]) WITH ORDINALITY AS c(lat, lon, ord)
+                    ORDER BY c.ord
+                )), 4326) AS geom
+            ),
+            removed AS (
+                DELETE FROM track_pois tp
+                USING pois p, line l
+                WHERE tp.track_id = $1
+                  AND p.id = tp.poi_id
+                  AND NOT ST_DWithin(p.geom, l.geom::geography, $4)
+                RETURNING tp.poi_id
+            ),
+            located AS (
+                SELECT
+                    tp.poi_id,
+                    ST_Length(
+                        ST_LineSubstring(l.geom, 0, ST_LineLocatePoint(l.geom, p.geom::geometry))::geography
+                    ) AS distance_m
+                FROM track_pois tp
+                JOIN pois p ON p.id = tp.poi_id
+                CROSS JOIN line l
+                WHERE tp.track_id = $1
+                  AND tp.poi_id NOT IN (SELECT poi_id FROM removed)
+            ),
+            ordered AS (
+                SELECT poi_id, distance_m,
+                       (ROW_NUMBER() OVER (ORDER BY distance_m, poi_id) - 1)::int AS seq
+                FROM located
+            )
+            UPDATE track_pois tp
+            SET distance_from_start_m = ordered.distance_m,
+                sequence_order = ordered.seq
+            FROM ordered
+            WHERE tp.track_id = $1 AND tp.poi_id = ordered.poi_id
+            "#,
+        )
+        .bind(track_id)
+        .bind(&lats)
+        .bind(&lons)
+        .bind(RELINK_MAX_OFFSET_M)
+        .execute(pool)
+        .await?;
+
+        let elapsed = start.elapsed().as_secs_f64();
+        crate::metrics::observe_db_query("relink_pois", elapsed);
+        debug!(
+            "Relinked {} POIs to new geometry of track {} in {:.3}s",
+            result.rows_affected(),
+            track_id,
+            elapsed
+        );
+
+        Ok(())
+    }
+
     /// Find potential duplicates using fuzzy matching
     /// Used for suggesting merges to users
     #[allow(dead_code)]
@@ -370,4 +453,104 @@ mod tests {
         let k2 = PoiDeduplicationService::generate_dedup_key(&wp2);
         assert_eq!(k1, k2);
     }
+
+    #[tokio::test]
+    #[ignore] // Requires database setup
+    async fn relink_reorders_pois_and_unlinks_those_off_the_new_line() {
+        use sqlx::postgres::PgPoolOptions;
+
+        let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for tests");
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&db_url)
+            .await
+            .unwrap();
+
+        let track_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO tracks (id, name, categories, geom, length_km, hash)
+            VALUES ($1, 'Relinked track', '{}',
+                ST_Multi(ST_GeomFromText('LINESTRING(37.0 55.0, 37.0 55.01)', 4326)), 1.1, $2)
+            "#,
+        )
+        .bind(track_id)
+        .bind(format!("relink-test-{track_id}"))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Near the original start, near the original end, and next to the original line only
+        let suffix = track_id.simple().to_string();
+        let mut poi_ids = Vec::new();
+        for (name, lat, lon) in [
+            ("Relink start", 55.0005, 37.0),
+            ("Relink end", 55.0095, 37.0),
+            ("Relink far", 55.005, 37.0001),
+        ] {
+            let poi_id: i32 = sqlx::query_scalar(
+                r#"
+                INSERT INTO pois (name, geom)
+                VALUES ($1, ST_SetSRID(ST_MakePoint($3, $2), 4326)::geography)
+                RETURNING id
+                "#,
+            )
+            .bind(format!("{name} {suffix}"))
+            .bind(lat)
+            .bind(lon)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            poi_ids.push(poi_id);
+        }
+        for (order, poi_id) in poi_ids.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO track_pois (track_id, poi_id, distance_from_start_m, sequence_order) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(track_id)
+            .bind(poi_id)
+            .bind(order as f32 * 500.0)
+            .bind(order as i32)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Reverse the direction and move the middle ~300 m east, so the far POI drops off
+        // while the start and end POIs stay on the line's endpoints.
+        let new_coordinates = [
+            (55.0095, 37.0),
+            (55.0095, 37.005),
+            (55.0005, 37.005),
+            (55.0005, 37.0),
+        ];
+        PoiDeduplicationService::relink_pois_after_geometry_change(
+            &pool,
+            track_id,
+            &new_coordinates,
+        )
+        .await
+        .unwrap();
+
+        let linked: Vec<(i32, i32)> = sqlx::query_as(
+            "SELECT poi_id, sequence_order FROM track_pois WHERE track_id = $1 ORDER BY sequence_order",
+        )
+        .bind(track_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("DELETE FROM tracks WHERE id = $1")
+            .bind(track_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM pois WHERE id = ANY($1)")
+            .bind(&poi_ids)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(linked, vec![(poi_ids[1], 0), (poi_ids[0], 1)]);
+    }
 }
@@ -0,0 +1,53 @@
+//! Per-request trace IDs so log lines from one request can be correlated.
+
+use axum::http::{HeaderName, Request};
+use tower::ServiceBuilder;
+use tower::layer::util::{Identity, Stack};
+use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{MakeSpan, TraceLayer};
+use tracing::Span;
+
+/// Carries the trace ID on requests and responses. A value sent by the client (e.g. from
+/// an upstream proxy) is kept, otherwise a UUID is generated.
+pub const TRACE_ID_HEADER: HeaderName = HeaderName::from_static("x-trace-id");
+
+/// Opens a `request` span carrying the trace ID, which every log line emitted while
+/// handling the request inherits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MakeRequestSpan;
+
+impl<B> MakeSpan<B> for MakeRequestSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        let trace_id = request
+            .headers()
+            .get(&TRACE_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        tracing::info_span!(
+            "request",
+            trace_id,
+            method = %request.method(),
+            path = %request.uri().path(),
+        )
+    }
+}
+
+pub type RequestTracingLayer = ServiceBuilder<
+    Stack<
+        PropagateRequestIdLayer,
+        Stack<
+            TraceLayer<SharedClassifier<ServerErrorsAsFailures>, MakeRequestSpan>,
+            Stack<SetRequestIdLayer<MakeRequestUuid>, Identity>,
+        >,
+    >,
+>;
+
+/// Assigns the trace ID, wraps the request in its span and echoes the ID back in the
+/// `X-Trace-ID` response header
+pub fn layer() -> RequestTracingLayer {
+    ServiceBuilder::new()
+        .layer(SetRequestIdLayer::new(TRACE_ID_HEADER, MakeRequestUuid))
+        .layer(TraceLayer::new_for_http().make_span_with(MakeRequestSpan))
+        .layer(PropagateRequestIdLayer::new(TRACE_ID_HEADER))
+}
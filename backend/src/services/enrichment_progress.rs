@@ -0,0 +1,167 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::watch;
+use tracing::error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// State of an elevation enrichment as streamed by `GET /tracks/{id}/enrichment-status`
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum EnrichmentProgress {
+    Running {
+        progress: f64,
+        points_processed: u32,
+        total_points: u32,
+    },
+    Complete {
+        elevation_gain: Option<f64>,
+        elevation_loss: Option<f64>,
+    },
+    Failed,
+    /// No enrichment is running for the track
+    Idle,
+}
+
+impl EnrichmentProgress {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, EnrichmentProgress::Running { .. })
+    }
+
+    /// SSE event name for this state
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            EnrichmentProgress::Running { .. } => "progress",
+            EnrichmentProgress::Complete { .. } => "complete",
+            EnrichmentProgress::Failed => "failed",
+            EnrichmentProgress::Idle => "idle",
+        }
+    }
+}
+
+// One channel per track with an enrichment in flight
+static CHANNELS: Lazy<Mutex<HashMap<Uuid, watch::Sender<EnrichmentProgress>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Follow the enrichment currently running for `track_id`, if any
+pub fn subscribe(track_id: Uuid) -> Option<watch::Receiver<EnrichmentProgress>> {
+    match CHANNELS.lock() {
+        Ok(channels) => channels.get(&track_id).map(|tx| tx.subscribe()),
+        Err(e) => {
+            error!(error = ?e, "enrichment progress mutex poisoned");
+            None
+        }
+    }
+}
+
+/// Publishes progress for one enrichment run. Dropping it before [`complete`] reports
+/// the run as failed, so every early return in the caller closes open streams.
+///
+/// [`complete`]: ProgressReporter::complete
+pub struct ProgressReporter {
+    track_id: Uuid,
+    sender: watch::Sender<EnrichmentProgress>,
+}
+
+impl ProgressReporter {
+    pub fn start(track_id: Uuid, total_points: usize) -> Self {
+        let (sender, _) = watch::channel(EnrichmentProgress::Running {
+            progress: 0.0,
+            points_processed: 0,
+            total_points: total_points as u32,
+        });
+        match CHANNELS.lock() {
+            Ok(mut channels) => {
+                channels.insert(track_id, sender.clone());
+            }
+            Err(e) => error!(error = ?e, "enrichment progress mutex poisoned"),
+        }
+        Self { track_id, sender }
+    }
+
+    pub fn update(&self, points_processed: usize, total_points: usize) {
+        let progress = if total_points == 0 {
+            0.0
+        } else {
+            (points_processed as f64 / total_points as f64).min(1.0)
+        };
+        self.sender.send_replace(EnrichmentProgress::Running {
+            progress,
+            points_processed: points_processed as u32,
+            total_points: total_points as u32,
+        });
+    }
+
+    pub fn complete(self, elevation_gain: Option<f64>, elevation_loss: Option<f64>) {
+        self.sender.send_replace(EnrichmentProgress::Complete {
+            elevation_gain,
+            elevation_loss,
+        });
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        if !self.sender.borrow().is_finished() {
+            self.sender.send_replace(EnrichmentProgress::Failed);
+        }
+        // A newer run for the same track may have replaced this channel already
+        if let Ok(mut channels) = CHANNELS.lock()
+            && channels
+                .get(&self.track_id)
+                .is_some_and(|tx| tx.same_channel(&self.sender))
+        {
+            channels.remove(&self.track_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reporter_publishes_progress_and_completion() {
+        let track_id = Uuid::new_v4();
+        assert!(subscribe(track_id).is_none());
+
+        let reporter = ProgressReporter::start(track_id, 200);
+        let rx = subscribe(track_id).expect("channel registered");
+        reporter.update(50, 200);
+        assert_eq!(
+            *rx.borrow(),
+            EnrichmentProgress::Running {
+                progress: 0.25,
+                points_processed: 50,
+                total_points: 200,
+            }
+        );
+
+        reporter.complete(Some(120.0), Some(80.0));
+        assert!(rx.borrow().is_finished());
+        assert_eq!(rx.borrow().event_name(), "complete");
+        assert!(subscribe(track_id).is_none());
+    }
+
+    #[test]
+    fn dropped_reporter_reports_failure() {
+        let track_id = Uuid::new_v4();
+        let reporter = ProgressReporter::start(track_id, 10);
+        let rx = subscribe(track_id).unwrap();
+        drop(reporter);
+        assert_eq!(*rx.borrow(), EnrichmentProgress::Failed);
+    }
+
+    #[test]
+    fn progress_serializes_with_status_tag() {
+        let json = serde_json::to_value(EnrichmentProgress::Complete {
+            elevation_gain: Some(10.0),
+            elevation_loss: None,
+        })
+        .unwrap();
+        assert_eq!(json["status"], "complete");
+        assert_eq!(json["elevation_gain"], 10.0);
+    }
+}
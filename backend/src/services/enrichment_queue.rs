@@ -1,8 +1,10 @@
 use crate::{
     db, metrics,
+    models::EnrichmentFailure,
+    services::{enrichment_progress::ProgressReporter, webhooks},
     track_utils::{
         ElevationEnrichmentService, elevation_enrichment::EnrichmentResult,
-        slope::recalculate_slope_metrics,
+        extract_coordinates_from_geojson, slope::recalculate_slope_metrics,
     },
 };
 use once_cell::sync::OnceCell;
@@ -14,10 +16,12 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::time::{Duration, timeout};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 const ENQUEUE_GRACE_MS: u64 = 20;
+/// Failures retried this many times are no longer picked up at startup
+pub const MAX_ENRICHMENT_RETRIES: i32 = 5;
 
 static ENRICHMENT_QUEUE: OnceCell<EnrichmentQueue> = OnceCell::new();
 
@@ -104,6 +108,24 @@ impl EnrichmentQueue {
     pub async fn try_enqueue(&self, job: EnrichmentJob) -> Result<(), EnqueueError> {
         self.enqueue_with_grace(job, Duration::from_millis(0)).await
     }
+
+    /// Wait for room in the queue however long it takes; for background producers
+    /// that must not bypass the queue's concurrency limit
+    pub async fn enqueue_waiting(&self, job: EnrichmentJob) -> Result<(), EnqueueError> {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| EnqueueError::NotInitialized)?;
+        self.sender
+            .send(QueuedJob {
+                job,
+                _permit: permit,
+            })
+            .await
+            .map_err(|_| EnqueueError::NotInitialized)
+    }
 }
 
 pub fn init_enrichment_queue(pool: Arc<PgPool>) {
@@ -145,6 +167,15 @@ pub async fn try_enqueue(job: EnrichmentJob) -> Result<(), EnqueueError> {
         .await
 }
 
+pub async fn enqueue_waiting(job: EnrichmentJob) -> Result<(), EnqueueError> {
+    ENRICHMENT_QUEUE
+        .get()
+        .cloned()
+        .ok_or(EnqueueError::NotInitialized)?
+        .enqueue_waiting(job)
+        .await
+}
+
 pub fn spawn_immediate_enrichment(pool: Arc<PgPool>, job: EnrichmentJob) {
     tokio::spawn(async move {
         run_enrichment_job(pool, job).await;
@@ -177,14 +208,50 @@ async fn run_enrichment_job(pool: Arc<PgPool>, job: EnrichmentJob) {
 
     debug!(track_id = %job.track_id, endpoint = "enrichment_queue", "starting enrichment job");
 
+    // The track may have been deleted while the job waited in the queue
+    match db::track_id_exists(&pool, job.track_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            info!(track_id = %job.track_id, endpoint = "enrichment_queue", "track no longer exists; skipping enrichment");
+            metrics::record_track_enrich_status("skipped_missing_track");
+            return;
+        }
+        Err(e) => {
+            error!(?job.track_id, "Failed to check track existence: {e}");
+            metrics::record_track_enrich_status("failed_update_db");
+            record_failure(&pool, job.track_id, &format!("track lookup failed: {e}")).await;
+            return;
+        }
+    }
+
+    let progress = ProgressReporter::start(job.track_id, coordinates.len());
     match enrichment_service
-        .enrich_track_elevation(coordinates.clone())
+        .enrich_track_elevation_with_progress(coordinates.clone(), &|done, total| {
+            progress.update(done, total)
+        })
         .await
     {
         Ok(result) => {
             match persist_enrichment_result(&pool, job.track_id, &coordinates, &result).await {
                 Ok(()) => {
+                    if let Err(e) = db::clear_enrichment_failure(&pool, job.track_id).await {
+                        warn!(track_id = %job.track_id, error = ?e, "failed to clear enrichment failure");
+                    }
+                    progress.complete(
+                        result.metrics.elevation_gain.map(f64::from),
+                        result.metrics.elevation_loss.map(f64::from),
+                    );
                     metrics::record_track_enrich_status("success");
+                    webhooks::dispatch_track_event(
+                        Arc::clone(&pool),
+                        webhooks::EVENT_ELEVATION_ENRICHED,
+                        job.track_id,
+                        serde_json::json!({
+                            "elevation_gain": result.metrics.elevation_gain,
+                            "elevation_loss": result.metrics.elevation_loss,
+                            "elevation_dataset": result.dataset,
+                        }),
+                    );
                     metrics::observe_track_enrich_duration(
                         "success",
                         enrich_start.elapsed().as_secs_f64(),
@@ -199,6 +266,7 @@ async fn run_enrichment_job(pool: Arc<PgPool>, job: EnrichmentJob) {
                 Err(PersistError::Elevation(e)) => {
                     error!(?job.track_id, "Failed to persist enrichment result: {e}");
                     metrics::record_track_enrich_status("failed_update_db");
+                    record_failure(&pool, job.track_id, &format!("persist elevation: {e}")).await;
                     metrics::observe_track_enrich_duration(
                         "failed_update_db",
                         enrich_start.elapsed().as_secs_f64(),
@@ -207,6 +275,7 @@ async fn run_enrichment_job(pool: Arc<PgPool>, job: EnrichmentJob) {
                 Err(PersistError::Slope(e)) => {
                     error!(?job.track_id, "Failed to update slope data: {e}");
                     metrics::record_track_enrich_status("failed_update_slope");
+                    record_failure(&pool, job.track_id, &format!("persist slope: {e}")).await;
                     metrics::observe_track_enrich_duration(
                         "failed_update_slope",
                         enrich_start.elapsed().as_secs_f64(),
@@ -217,6 +286,7 @@ async fn run_enrichment_job(pool: Arc<PgPool>, job: EnrichmentJob) {
         Err(e) => {
             error!(?job.track_id, "Failed to auto-enrich track elevation: {e}");
             metrics::record_track_enrich_status("failed_remote");
+            record_failure(&pool, job.track_id, &format!("elevation API: {e}")).await;
             metrics::observe_track_enrich_duration(
                 "failed_remote",
                 enrich_start.elapsed().as_secs_f64(),
@@ -225,6 +295,87 @@ async fn run_enrichment_job(pool: Arc<PgPool>, job: EnrichmentJob) {
     }
 }
 
+async fn record_failure(pool: &Arc<PgPool>, track_id: Uuid, message: &str) {
+    if let Err(e) = db::record_enrichment_failure(pool, track_id, message).await {
+        warn!(%track_id, error = ?e, "failed to record enrichment failure");
+    }
+}
+
+/// Whether startup recovery should try a failed enrichment again
+fn should_retry(failure: &EnrichmentFailure) -> bool {
+    failure.retry_count < MAX_ENRICHMENT_RETRIES
+}
+
+/// Enrichment job for a track whose last enrichment failed; `None` when the track no
+/// longer exists or has no coordinates
+async fn retry_job(
+    pool: &Arc<PgPool>,
+    track_id: Uuid,
+) -> Result<Option<EnrichmentJob>, sqlx::Error> {
+    let Some(track) = db::get_track_by_id(pool, track_id).await? else {
+        return Ok(None);
+    };
+    Ok(
+        match extract_coordinates_from_geojson(&track.geom_geojson) {
+            Ok(coordinates) if !coordinates.is_empty() => Some(EnrichmentJob {
+                track_id,
+                coordinates,
+            }),
+            _ => None,
+        },
+    )
+}
+
+/// Queue a new enrichment for a track whose last one failed. Returns `false` when the
+/// track no longer exists or has no coordinates.
+pub async fn retry_enrichment(pool: &Arc<PgPool>, track_id: Uuid) -> Result<bool, sqlx::Error> {
+    let Some(job) = retry_job(pool, track_id).await? else {
+        return Ok(false);
+    };
+    if enqueue(job.clone()).await.is_err() {
+        spawn_immediate_enrichment(Arc::clone(pool), job);
+    }
+    Ok(true)
+}
+
+/// Re-attempt enrichments recorded as failed, e.g. by an earlier process. Jobs go
+/// through the queue one at a time as room frees up, so a large backlog does not
+/// flood the elevation API. Call once at startup, after migrations and
+/// `init_enrichment_queue`.
+pub fn spawn_failed_enrichment_recovery(pool: Arc<PgPool>) {
+    tokio::spawn(async move {
+        let failures = match db::list_enrichment_failures(&pool).await {
+            Ok(failures) => failures,
+            Err(e) => {
+                warn!(error = ?e, "failed to load enrichment failures for recovery");
+                return;
+            }
+        };
+        let failures: Vec<EnrichmentFailure> = failures.into_iter().filter(should_retry).collect();
+        if failures.is_empty() {
+            return;
+        }
+        info!(count = failures.len(), "retrying failed enrichments");
+        for failure in failures {
+            let job = match retry_job(&pool, failure.track_id).await {
+                Ok(Some(job)) => job,
+                Ok(None) => {
+                    debug!(track_id = %failure.track_id, "failed enrichment no longer retryable");
+                    continue;
+                }
+                Err(e) => {
+                    warn!(track_id = %failure.track_id, error = ?e, "failed to retry enrichment");
+                    continue;
+                }
+            };
+            if let Err(e) = enqueue_waiting(job).await {
+                warn!(error = ?e, "enrichment queue unavailable, stopping recovery");
+                return;
+            }
+        }
+    });
+}
+
 async fn persist_enrichment_result(
     pool: &Arc<PgPool>,
     track_id: Uuid,
@@ -251,8 +402,22 @@ async fn persist_enrichment_result(
 
     if let Some(profile) = &result.elevation_profile {
         let slope_start = Instant::now();
-        let slope_result =
-            recalculate_slope_metrics(coordinates, profile, &format!("Track {track_id}"));
+        let track_name = format!("Track {track_id}");
+        let slope_result = match recalculate_slope_metrics(coordinates, profile, &track_name) {
+            Ok(slope_result) => slope_result,
+            Err(e) => {
+                // Elevation is already stored; the track stays without slope data, flagged so
+                // the slope profile reports a failure instead of a calculation in progress
+                e.log(&track_name);
+                metrics::observe_slope_recalc(
+                    "calculation_error",
+                    slope_start.elapsed().as_secs_f64(),
+                );
+                return db::mark_slope_calculation_failed(pool, track_id)
+                    .await
+                    .map_err(PersistError::Slope);
+            }
+        };
         let slope_duration = slope_start.elapsed().as_secs_f64();
 
         if let Err(e) = db::update_track_slope(
@@ -331,6 +496,45 @@ mod tests {
         assert_eq!(items, vec![first, second]);
     }
 
+    #[test]
+    fn recovery_skips_failures_out_of_retries() {
+        let failure = |retry_count| EnrichmentFailure {
+            track_id: Uuid::new_v4(),
+            attempted_at: chrono::Utc::now(),
+            error_message: "elevation API: timeout".to_string(),
+            retry_count,
+        };
+        assert!(should_retry(&failure(0)));
+        assert!(should_retry(&failure(MAX_ENRICHMENT_RETRIES - 1)));
+        assert!(!should_retry(&failure(MAX_ENRICHMENT_RETRIES)));
+    }
+
+    #[tokio::test]
+    async fn enqueue_waiting_blocks_until_room() {
+        let (release, gate) = tokio::sync::watch::channel(false);
+        let queue = start_queue_for_tests(1, move |_job| {
+            let mut gate = gate.clone();
+            async move {
+                let _ = gate.wait_for(|open| *open).await;
+            }
+        });
+        let job = || EnrichmentJob {
+            track_id: Uuid::new_v4(),
+            coordinates: vec![(0.0, 0.0)],
+        };
+        queue.enqueue(job()).await.unwrap();
+
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.enqueue_waiting(job()).await }
+        });
+        sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        release.send(true).unwrap();
+        assert_eq!(waiting.await.unwrap(), Ok(()));
+    }
+
     #[tokio::test]
     async fn queue_respects_capacity() {
         let queue = start_queue_for_tests(1, |_job| async move {});
@@ -1,7 +1,10 @@
-use crate::models::TrackDetail;
-use crate::track_utils::extract_segments_from_geojson;
+use crate::models::{GpxRoundtripDiff, RoundtripDelta, TrackDetail};
+use crate::track_utils::{TrackParseError, extract_segments_from_geojson, parse_gpx_full};
 use chrono::Utc;
 
+/// Relative change (percent) below which a numeric field counts as preserved
+const ROUNDTRIP_TOLERANCE_PCT: f64 = 0.1;
+
 /// Service for exporting tracks to GPX format
 #[derive(Default)]
 pub struct GpxExportService;
@@ -30,6 +33,7 @@ impl GpxExportService {
             .as_ref()
             .map(|d| xml_escape(d))
             .unwrap_or_default();
+        let provenance = self.get_provenance_xml(track);
 
         format!(
             r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -41,7 +45,7 @@ impl GpxExportService {
   <metadata>
     <name>{track_name}</name>
     <desc>{track_description}</desc>
-    <time>{created_at}</time>
+{provenance}    <time>{created_at}</time>
   </metadata>
   <trk>
     <name>{track_name}</name>
@@ -53,6 +57,237 @@ impl GpxExportService {
         )
     }
 
+    /// Export the track to GPX, parse the result back and report which fields
+    /// were preserved, lost or changed along the way. Fields the stored track
+    /// does not have are not reported.
+    pub fn roundtrip_diff(&self, track: &TrackDetail) -> Result<GpxRoundtripDiff, TrackParseError> {
+        let gpx = self.generate_gpx(track);
+        let parsed = parse_gpx_full(gpx.as_bytes())?;
+        let mut diff = GpxRoundtripDiff::default();
+
+        // Data GPX has no place for
+        if !track.categories.is_empty() {
+            diff.lost.push("categories".to_string());
+        }
+        if !track.auto_classifications.is_empty() {
+            let mut original = track.auto_classifications.clone();
+            let mut roundtrip = parsed.auto_classifications.clone();
+            original.sort();
+            roundtrip.sort();
+            let bucket = if original == roundtrip {
+                &mut diff.preserved
+            } else {
+                &mut diff.lost
+            };
+            bucket.push("auto_classifications".to_string());
+        }
+
+        // Point series and derived JSON
+        let series = [
+            (
+                "elevation_profile",
+                track.elevation_profile.is_some(),
+                parsed.elevation_profile.is_some(),
+            ),
+            ("hr_data", track.hr_data.is_some(), parsed.hr_data.is_some()),
+            (
+                "temp_data",
+                track.temp_data.is_some(),
+                parsed.temp_data.is_some(),
+            ),
+            (
+                "cadence_data",
+                track.cadence_data.is_some(),
+                parsed.cadence_data.is_some(),
+            ),
+            (
+                "power_data",
+                track.power_data.is_some(),
+                parsed.power_data.is_some(),
+            ),
+            (
+                "time_data",
+                track.time_data.is_some(),
+                parsed.time_data.is_some(),
+            ),
+            (
+                "speed_data",
+                track.speed_data.is_some(),
+                parsed.speed_data.is_some(),
+            ),
+            (
+                "pace_data",
+                track.pace_data.is_some(),
+                parsed.pace_data.is_some(),
+            ),
+            (
+                "slope_histogram",
+                track.slope_histogram.is_some(),
+                parsed.slope_histogram.is_some(),
+            ),
+            (
+                "slope_segments",
+                track.slope_segments.is_some(),
+                parsed.slope_segments.is_some(),
+            ),
+            (
+                "recorded_at",
+                track.recorded_at.is_some(),
+                parsed.recorded_at.is_some(),
+            ),
+            (
+                "metadata_author",
+                track.metadata_author.is_some(),
+                parsed.metadata_author.is_some(),
+            ),
+            (
+                "metadata_link",
+                track.metadata_link.is_some(),
+                parsed.metadata_link.is_some(),
+            ),
+            (
+                "metadata_copyright",
+                track.metadata_copyright.is_some(),
+                parsed.metadata_copyright.is_some(),
+            ),
+        ];
+        for (field, original, roundtrip) in series {
+            if original {
+                let bucket = if roundtrip {
+                    &mut diff.preserved
+                } else {
+                    &mut diff.lost
+                };
+                bucket.push(field.to_string());
+            }
+        }
+
+        // Scalar metrics
+        let as_f64 = |v: Option<f32>| v.map(f64::from);
+        let as_f64_i = |v: Option<i32>| v.map(f64::from);
+        let metrics = [
+            ("length_km", Some(track.length_km), Some(parsed.length_km)),
+            (
+                "elevation_gain",
+                as_f64(track.elevation_gain),
+                as_f64(parsed.elevation_gain),
+            ),
+            (
+                "elevation_loss",
+                as_f64(track.elevation_loss),
+                as_f64(parsed.elevation_loss),
+            ),
+            (
+                "elevation_min",
+                as_f64(track.elevation_min),
+                as_f64(parsed.elevation_min),
+            ),
+            (
+                "elevation_max",
+                as_f64(track.elevation_max),
+                as_f64(parsed.elevation_max),
+            ),
+            (
+                "slope_min",
+                as_f64(track.slope_min),
+                as_f64(parsed.slope_min),
+            ),
+            (
+                "slope_max",
+                as_f64(track.slope_max),
+                as_f64(parsed.slope_max),
+            ),
+            (
+                "slope_avg",
+                as_f64(track.slope_avg),
+                as_f64(parsed.slope_avg),
+            ),
+            ("avg_speed", track.avg_speed, parsed.avg_speed),
+            ("avg_hr", as_f64_i(track.avg_hr), as_f64_i(parsed.avg_hr)),
+            ("hr_min", as_f64_i(track.hr_min), as_f64_i(parsed.hr_min)),
+            ("hr_max", as_f64_i(track.hr_max), as_f64_i(parsed.hr_max)),
+            (
+                "cadence_avg",
+                as_f64_i(track.cadence_avg),
+                as_f64_i(parsed.cadence_avg),
+            ),
+            (
+                "cadence_min",
+                as_f64_i(track.cadence_min),
+                as_f64_i(parsed.cadence_min),
+            ),
+            (
+                "cadence_max",
+                as_f64_i(track.cadence_max),
+                as_f64_i(parsed.cadence_max),
+            ),
+            (
+                "power_avg",
+                as_f64_i(track.power_avg),
+                as_f64_i(parsed.power_avg),
+            ),
+            (
+                "moving_time",
+                as_f64_i(track.moving_time),
+                as_f64_i(parsed.moving_time),
+            ),
+            (
+                "pause_time",
+                as_f64_i(track.pause_time),
+                as_f64_i(parsed.pause_time),
+            ),
+            (
+                "moving_avg_speed",
+                track.moving_avg_speed,
+                parsed.moving_avg_speed,
+            ),
+            (
+                "moving_avg_pace",
+                track.moving_avg_pace,
+                parsed.moving_avg_pace,
+            ),
+            (
+                "moving_distance_km",
+                track.moving_distance_km,
+                parsed.moving_distance_km,
+            ),
+            (
+                "duration_seconds",
+                as_f64_i(track.duration_seconds),
+                as_f64_i(parsed.duration_seconds),
+            ),
+        ];
+        for (field, original, roundtrip) in metrics {
+            match (original, roundtrip) {
+                (None, _) => {}
+                (Some(_), None) => diff.lost.push(field.to_string()),
+                (Some(original), Some(roundtrip)) => {
+                    let delta_pct = if original != 0.0 {
+                        (roundtrip - original) / original.abs() * 100.0
+                    } else if roundtrip == 0.0 {
+                        0.0
+                    } else {
+                        100.0
+                    };
+                    if delta_pct.abs() <= ROUNDTRIP_TOLERANCE_PCT {
+                        diff.preserved.push(field.to_string());
+                    } else {
+                        diff.degraded.insert(
+                            field.to_string(),
+                            RoundtripDelta {
+                                original,
+                                roundtrip,
+                                delta_pct,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(diff)
+    }
+
     /// Sanitize filename for safe file system usage
     pub fn sanitize_filename(&self, name: &str) -> String {
         name.chars()
@@ -80,11 +315,11 @@ impl GpxExportService {
         let mut track_points = String::new();
         for (i, (lat, lon)) in coordinates.iter().enumerate() {
             let elevation = self.get_elevation_xml(track, i);
-            let hr_data = self.get_hr_xml(track, i);
+            let extensions = self.get_extensions_xml(track, i);
             let time_data = self.get_time_xml(track, i);
 
             track_points.push_str(&format!(
-                "      <trkpt lat=\"{lat:.7}\" lon=\"{lon:.7}\">{elevation}{time_data}{hr_data}</trkpt>\n"
+                "      <trkpt lat=\"{lat:.7}\" lon=\"{lon:.7}\">{elevation}{time_data}{extensions}</trkpt>\n"
             ));
         }
         track_points
@@ -101,17 +336,48 @@ impl GpxExportService {
         String::new()
     }
 
-    fn get_hr_xml(&self, track: &TrackDetail, index: usize) -> String {
-        if let Some(hr_data) = &track.hr_data
-            && let Some(hr_array) = hr_data.as_array()
-            && index < hr_array.len()
-            && let Some(hr_val) = hr_array[index].as_i64()
-        {
-            return format!(
-                "<extensions><gpxtpx:TrackPointExtension><gpxtpx:hr>{hr_val}</gpxtpx:hr></gpxtpx:TrackPointExtension></extensions>"
-            );
+    /// Garmin TrackPointExtension with temperature, heart rate, cadence and power, in schema order
+    fn get_extensions_xml(&self, track: &TrackDetail, index: usize) -> String {
+        let mut fields = String::new();
+        if let Some(temp) = series_value(&track.temp_data, index).and_then(|v| v.as_f64()) {
+            fields.push_str(&format!("<gpxtpx:atemp>{temp:.1}</gpxtpx:atemp>"));
         }
-        String::new()
+        if let Some(hr) = series_value(&track.hr_data, index).and_then(|v| v.as_i64()) {
+            fields.push_str(&format!("<gpxtpx:hr>{hr}</gpxtpx:hr>"));
+        }
+        if let Some(cad) = series_value(&track.cadence_data, index).and_then(|v| v.as_i64()) {
+            fields.push_str(&format!("<gpxtpx:cad>{cad}</gpxtpx:cad>"));
+        }
+        if let Some(power) = series_value(&track.power_data, index).and_then(|v| v.as_i64()) {
+            fields.push_str(&format!("<gpxtpx:power>{power}</gpxtpx:power>"));
+        }
+        if fields.is_empty() {
+            return String::new();
+        }
+        format!(
+            "<extensions><gpxtpx:TrackPointExtension>{fields}</gpxtpx:TrackPointExtension></extensions>"
+        )
+    }
+
+    /// `<author>`, `<copyright>` and `<link>` in GPX 1.1 schema order
+    fn get_provenance_xml(&self, track: &TrackDetail) -> String {
+        let mut xml = String::new();
+        if let Some(author) = &track.metadata_author {
+            xml.push_str(&format!(
+                "    <author><name>{}</name></author>\n",
+                xml_escape(author)
+            ));
+        }
+        if let Some(copyright) = &track.metadata_copyright {
+            xml.push_str(&format!(
+                "    <copyright author=\"{}\"/>\n",
+                xml_escape(copyright)
+            ));
+        }
+        if let Some(link) = &track.metadata_link {
+            xml.push_str(&format!("    <link href=\"{}\"/>\n", xml_escape(link)));
+        }
+        xml
     }
 
     fn get_time_xml(&self, track: &TrackDetail, index: usize) -> String {
@@ -126,6 +392,11 @@ impl GpxExportService {
     }
 }
 
+/// Value at `index` of a per-point JSON array
+fn series_value(series: &Option<serde_json::Value>, index: usize) -> Option<&serde_json::Value> {
+    series.as_ref()?.as_array()?.get(index)
+}
+
 fn xml_escape(input: &str) -> String {
     input
         .replace('&', "&amp;")
@@ -157,10 +428,8 @@ mod tests {
         assert_eq!(xml_escape("Track \"quoted\""), "Track &quot;quoted&quot;");
     }
 
-    #[test]
-    fn test_generate_gpx_from_track() {
-        let service = GpxExportService::new();
-        let track = TrackDetail {
+    fn sample_track() -> TrackDetail {
+        TrackDetail {
             id: Uuid::new_v4(),
             name: "Test Track".to_string(),
             description: Some("Test Description".to_string()),
@@ -172,10 +441,13 @@ mod tests {
             }),
             segment_gaps: None,
             pause_gaps: None,
+            segments: None,
             length_km: 0.1,
             elevation_profile: Some(json!([200.0, 210.0])),
             hr_data: Some(json!([120, 125])),
             temp_data: None,
+            cadence_data: None,
+            power_data: None,
             time_data: None,
             elevation_gain: Some(10.0),
             elevation_loss: Some(0.0),
@@ -193,18 +465,38 @@ mod tests {
             avg_hr: Some(122),
             hr_min: Some(120),
             hr_max: Some(125),
+            cadence_avg: None,
+            cadence_min: None,
+            cadence_max: None,
+            power_avg: None,
+            power_max: None,
+            power_normalized: None,
             moving_time: None,
             pause_time: None,
             moving_avg_speed: None,
             moving_avg_pace: None,
+            moving_distance_km: None,
+            is_loop: false,
+            elevation_source: None,
+            classification_confidence: None,
             duration_seconds: None,
             created_at: Some(Utc::now()),
             updated_at: Some(Utc::now()),
             recorded_at: None,
             session_id: None,
+            metadata_author: None,
+            metadata_link: None,
+            metadata_copyright: None,
+            is_public: true,
             speed_data: None,
             pace_data: None,
-        };
+        }
+    }
+
+    #[test]
+    fn test_generate_gpx_from_track() {
+        let service = GpxExportService::new();
+        let track = sample_track();
 
         let gpx = service.generate_gpx(&track);
         assert!(gpx.contains("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
@@ -214,4 +506,88 @@ mod tests {
         assert!(gpx.contains("<ele>200.0</ele>"));
         assert!(gpx.contains("<gpxtpx:hr>120</gpxtpx:hr>"));
     }
+
+    #[test]
+    fn test_generate_gpx_includes_temperature_and_cadence() {
+        let service = GpxExportService::new();
+        let mut track = sample_track();
+        track.temp_data = Some(json!([21.5, null]));
+        track.cadence_data = Some(json!([88, 90]));
+
+        let gpx = service.generate_gpx(&track);
+        assert!(gpx.contains(
+            "<gpxtpx:TrackPointExtension><gpxtpx:atemp>21.5</gpxtpx:atemp><gpxtpx:hr>120</gpxtpx:hr><gpxtpx:cad>88</gpxtpx:cad></gpxtpx:TrackPointExtension>"
+        ));
+        assert!(gpx.contains(
+            "<gpxtpx:TrackPointExtension><gpxtpx:hr>125</gpxtpx:hr><gpxtpx:cad>90</gpxtpx:cad></gpxtpx:TrackPointExtension>"
+        ));
+
+        let parsed = parse_gpx_full(gpx.as_bytes()).unwrap();
+        assert_eq!(parsed.cadence_data, Some(vec![Some(88), Some(90)]));
+        assert_eq!(parsed.temp_data, Some(vec![Some(21.5), None]));
+    }
+
+    #[test]
+    fn test_generate_gpx_roundtrips_power() {
+        let service = GpxExportService::new();
+        let mut track = sample_track();
+        track.power_data = Some(json!([210, 230]));
+        track.power_avg = Some(220);
+
+        let gpx = service.generate_gpx(&track);
+        assert!(gpx.contains(
+            "<gpxtpx:TrackPointExtension><gpxtpx:hr>120</gpxtpx:hr><gpxtpx:power>210</gpxtpx:power></gpxtpx:TrackPointExtension>"
+        ));
+
+        let parsed = parse_gpx_full(gpx.as_bytes()).unwrap();
+        assert_eq!(parsed.power_data, Some(vec![Some(210), Some(230)]));
+
+        let diff = service.roundtrip_diff(&track).unwrap();
+        assert!(diff.preserved.contains(&"power_data".to_string()));
+        assert!(diff.preserved.contains(&"power_avg".to_string()));
+    }
+
+    #[test]
+    fn test_generate_gpx_roundtrips_metadata_provenance() {
+        let service = GpxExportService::new();
+        let mut track = sample_track();
+        track.metadata_author = Some("Trail Club".to_string());
+        track.metadata_link = Some("https://trails.example/route?id=1&v=2".to_string());
+        track.metadata_copyright = Some("OpenStreetMap contributors".to_string());
+
+        let gpx = service.generate_gpx(&track);
+        assert!(gpx.contains("<author><name>Trail Club</name></author>"));
+        assert!(gpx.contains("<link href=\"https://trails.example/route?id=1&amp;v=2\"/>"));
+
+        let parsed = parse_gpx_full(gpx.as_bytes()).unwrap();
+        assert_eq!(parsed.metadata_author.as_deref(), Some("Trail Club"));
+        assert_eq!(
+            parsed.metadata_link.as_deref(),
+            Some("https://trails.example/route?id=1&v=2")
+        );
+        assert_eq!(
+            parsed.metadata_copyright.as_deref(),
+            Some("OpenStreetMap contributors")
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_diff_reports_lost_and_degraded_fields() {
+        let service = GpxExportService::new();
+        let mut track = sample_track();
+        track.temp_data = Some(json!([12.5, 13.0]));
+        track.cadence_data = Some(json!([85, 87]));
+
+        let diff = service.roundtrip_diff(&track).unwrap();
+        assert!(diff.lost.contains(&"categories".to_string()));
+        assert!(diff.preserved.contains(&"temp_data".to_string()));
+        assert!(diff.preserved.contains(&"cadence_data".to_string()));
+        assert!(diff.preserved.contains(&"hr_data".to_string()));
+        assert!(diff.preserved.contains(&"elevation_profile".to_string()));
+        assert!(diff.preserved.contains(&"avg_hr".to_string()));
+        // Stored length (0.1 km) does not match the actual geometry (~13 m)
+        let length = diff.degraded.get("length_km").expect("length_km degraded");
+        assert_eq!(length.original, 0.1);
+        assert!(length.delta_pct < -50.0);
+    }
 }
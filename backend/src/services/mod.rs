@@ -1,3 +1,8 @@
+pub mod enrichment_progress;
 pub mod enrichment_queue;
 pub mod gpx_export;
+pub mod rate_limiter;
+pub mod thumbnail_service;
 pub mod track_upload;
+pub mod weather_service;
+pub mod webhooks;
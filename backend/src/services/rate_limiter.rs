@@ -0,0 +1,181 @@
+//! Per-client sliding-window rate limits for read endpoints that could be used for scraping.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, header::RETRY_AFTER};
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName =
+    HeaderName::from_static("x-ratelimit-remaining");
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Where a client stands after a request, reported in response headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests still allowed in the current window
+    pub remaining: usize,
+    /// Until the oldest counted request leaves the window
+    pub reset_after: Duration,
+}
+
+impl RateLimitStatus {
+    /// Adds `X-RateLimit-Remaining` and `X-RateLimit-Reset` (whole seconds)
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            RATE_LIMIT_REMAINING_HEADER,
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            RATE_LIMIT_RESET_HEADER,
+            HeaderValue::from(self.reset_secs()),
+        );
+    }
+
+    /// Rejections also carry `Retry-After`
+    pub fn apply_rejection_headers(&self, headers: &mut HeaderMap) {
+        self.apply_headers(headers);
+        headers.insert(RETRY_AFTER, HeaderValue::from(self.reset_secs()));
+    }
+
+    fn reset_secs(&self) -> u64 {
+        self.reset_after.as_secs() + u64::from(self.reset_after.subsec_nanos() > 0)
+    }
+}
+
+/// At most `max_requests` per client IP within any `window`
+pub struct RateLimiter {
+    max_requests: usize,
+    window: Duration,
+    hits: RwLock<HashMap<IpAddr, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests: usize, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+            hits: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `ip`; `Err` when it is over the limit and was not counted
+    pub async fn check(&self, ip: IpAddr) -> Result<RateLimitStatus, RateLimitStatus> {
+        self.check_at(ip, Instant::now()).await
+    }
+
+    async fn check_at(&self, ip: IpAddr, now: Instant) -> Result<RateLimitStatus, RateLimitStatus> {
+        let mut hits = self.hits.write().await;
+        let times = hits.entry(ip).or_default();
+        while times
+            .front()
+            .is_some_and(|&t| now.duration_since(t) >= self.window)
+        {
+            times.pop_front();
+        }
+
+        let allowed = times.len() < self.max_requests;
+        if allowed {
+            times.push_back(now);
+        }
+        let reset_after = times
+            .front()
+            .map_or(Duration::ZERO, |&t| self.window - now.duration_since(t));
+        let status = RateLimitStatus {
+            remaining: self.max_requests.saturating_sub(times.len()),
+            reset_after,
+        };
+        if allowed { Ok(status) } else { Err(status) }
+    }
+
+    /// Drop clients with no requests left in the window; returns how many were removed
+    pub async fn purge_idle(&self) -> usize {
+        let now = Instant::now();
+        let mut hits = self.hits.write().await;
+        let before = hits.len();
+        hits.retain(|_, times| {
+            times
+                .back()
+                .is_some_and(|&t| now.duration_since(t) < self.window)
+        });
+        before - hits.len()
+    }
+}
+
+/// IP to rate-limit a request by. Connections from private or loopback addresses are
+/// taken to be reverse proxies, so the last `X-Forwarded-For` hop (the one our proxy
+/// added) is used instead; public peers cannot dodge the limit by sending that header.
+pub fn client_ip(peer: SocketAddr, headers: &HeaderMap) -> IpAddr {
+    let peer_ip = peer.ip();
+    let from_proxy = match peer_ip {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_loopback(),
+        IpAddr::V6(ip) => ip.is_unique_local() || ip.is_loopback(),
+    };
+    if !from_proxy {
+        return peer_ip;
+    }
+    headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .and_then(|hop| hop.trim().parse().ok())
+        .unwrap_or(peer_ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn limits_requests_within_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = Instant::now();
+
+        assert_eq!(limiter.check_at(ip, start).await.unwrap().remaining, 1);
+        assert_eq!(
+            limiter
+                .check_at(ip, start + Duration::from_secs(10))
+                .await
+                .unwrap()
+                .remaining,
+            0
+        );
+        let rejected = limiter
+            .check_at(ip, start + Duration::from_secs(20))
+            .await
+            .unwrap_err();
+        assert_eq!(rejected.reset_after, Duration::from_secs(40));
+
+        // Other clients are counted separately
+        let other: IpAddr = "203.0.113.8".parse().unwrap();
+        assert!(limiter.check_at(other, start).await.is_ok());
+
+        // The first request has left the window
+        assert!(
+            limiter
+                .check_at(ip, start + Duration::from_secs(60))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn client_ip_trusts_forwarded_for_only_from_private_peers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.1, 203.0.113.9"),
+        );
+
+        let proxy: SocketAddr = "172.18.0.3:41000".parse().unwrap();
+        assert_eq!(
+            client_ip(proxy, &headers),
+            "203.0.113.9".parse::<IpAddr>().unwrap()
+        );
+
+        let public: SocketAddr = "192.0.2.10:41000".parse().unwrap();
+        assert_eq!(client_ip(public, &headers), public.ip());
+        assert_eq!(client_ip(proxy, &HeaderMap::new()), proxy.ip());
+    }
+}
@@ -0,0 +1,249 @@
+use image::{ImageFormat, Rgba, RgbaImage, imageops};
+use imageproc::drawing::{draw_filled_circle_mut, draw_line_segment_mut};
+use once_cell::sync::Lazy;
+use std::f64::consts::PI;
+use std::io::Cursor;
+use std::time::Duration;
+use tracing::debug;
+
+pub const THUMBNAIL_SIZE: u32 = 256;
+const TILE_SIZE: f64 = 256.0;
+const MAX_TILE_ZOOM: u8 = 18;
+/// Space kept free around the track so it does not touch the image border (pixels)
+const PADDING_PX: f64 = 16.0;
+/// Web Mercator cannot show the poles
+const MAX_MERCATOR_LAT: f64 = 85.051_128_78;
+const DEFAULT_TILE_SERVER_URL: &str = "https://tile.openstreetmap.org/{z}/{x}/{y}.png";
+
+const TRACK_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+// Shown where no tile exists, e.g. beyond the Mercator latitude limit
+const BACKGROUND_COLOR: Rgba<u8> = Rgba([242, 239, 233, 255]);
+
+// The OSM tile usage policy requires an identifying User-Agent
+static TILE_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("trackly/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, thiserror::Error)]
+pub enum ThumbnailError {
+    #[error("track has no points")]
+    EmptyTrack,
+    #[error("tile request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("tile server returned {0}")]
+    Status(reqwest::StatusCode),
+    #[error("image processing failed: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+/// Position in Web Mercator pixels at `zoom` for a (lat, lon) point
+fn world_pixel((lat, lon): (f64, f64), zoom: u8) -> (f64, f64) {
+    let scale = TILE_SIZE * f64::from(1u32 << zoom);
+    let lat = lat.clamp(-MAX_MERCATOR_LAT, MAX_MERCATOR_LAT).to_radians();
+    let x = (lon + 180.0) / 360.0 * scale;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * scale;
+    (x, y)
+}
+
+/// Highest tile zoom at which all points fit into the thumbnail with padding
+fn fit_zoom(points: &[(f64, f64)]) -> u8 {
+    let pixels: Vec<(f64, f64)> = points.iter().map(|&p| world_pixel(p, 0)).collect();
+    let span = |axis: fn(&(f64, f64)) -> f64| {
+        let (min, max) = pixels
+            .iter()
+            .map(axis)
+            .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        max - min
+    };
+    let extent = span(|p| p.0).max(span(|p| p.1));
+    let available = f64::from(THUMBNAIL_SIZE) - 2.0 * PADDING_PX;
+
+    (0..=MAX_TILE_ZOOM)
+        .rev()
+        .find(|&z| extent * f64::from(1u32 << z) <= available)
+        .unwrap_or(0)
+}
+
+/// Draw the segments (lat, lon) onto `canvas`, whose top left corner is at `origin` in
+/// world pixels for `zoom`
+fn draw_track(canvas: &mut RgbaImage, segments: &[Vec<(f64, f64)>], origin: (f64, f64), zoom: u8) {
+    let to_canvas = |p: (f64, f64)| {
+        let (x, y) = world_pixel(p, zoom);
+        ((x - origin.0) as f32, (y - origin.1) as f32)
+    };
+    for segment in segments {
+        if let [only] = segment.as_slice() {
+            let (x, y) = to_canvas(*only);
+            draw_filled_circle_mut(canvas, (x as i32, y as i32), 2, TRACK_COLOR);
+            continue;
+        }
+        for pair in segment.windows(2) {
+            let (start, end) = (to_canvas(pair[0]), to_canvas(pair[1]));
+            // Offset copies give the line a visible 3px width
+            for (dx, dy) in [(0.0, 0.0), (1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+                draw_line_segment_mut(
+                    canvas,
+                    (start.0 + dx, start.1 + dy),
+                    (end.0 + dx, end.1 + dy),
+                    TRACK_COLOR,
+                );
+            }
+        }
+    }
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, ThumbnailError> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// Renders track previews over OpenStreetMap tiles (or `TILE_SERVER_URL`, a template
+/// with `{z}`, `{x}` and `{y}` placeholders)
+pub struct ThumbnailService {
+    client: reqwest::Client,
+    tile_url: String,
+}
+
+impl Default for ThumbnailService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThumbnailService {
+    pub fn new() -> Self {
+        Self {
+            client: TILE_CLIENT.clone(),
+            tile_url: std::env::var("TILE_SERVER_URL")
+                .unwrap_or_else(|_| DEFAULT_TILE_SERVER_URL.to_string()),
+        }
+    }
+
+    async fn fetch_tile(&self, zoom: u8, x: i64, y: i64) -> Result<RgbaImage, ThumbnailError> {
+        let url = self
+            .tile_url
+            .replace("{z}", &zoom.to_string())
+            .replace("{x}", &x.to_string())
+            .replace("{y}", &y.to_string());
+        debug!(url = %url, "fetching map tile");
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(ThumbnailError::Status(response.status()));
+        }
+        let bytes = response.bytes().await?;
+        Ok(image::load_from_memory(&bytes)?.to_rgba8())
+    }
+
+    /// PNG of the track segments (lat, lon) centred on a map background
+    pub async fn render(&self, segments: &[Vec<(f64, f64)>]) -> Result<Vec<u8>, ThumbnailError> {
+        let points: Vec<(f64, f64)> = segments.iter().flatten().copied().collect();
+        if points.is_empty() {
+            return Err(ThumbnailError::EmptyTrack);
+        }
+        let zoom = fit_zoom(&points);
+
+        // Centre the window on the track's bounding box
+        let pixels: Vec<(f64, f64)> = points.iter().map(|&p| world_pixel(p, zoom)).collect();
+        let (min_x, max_x, min_y, max_y) = pixels.iter().fold(
+            (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+            |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+        );
+        let half = f64::from(THUMBNAIL_SIZE) / 2.0;
+        let origin = ((min_x + max_x) / 2.0 - half, (min_y + max_y) / 2.0 - half);
+
+        let mut canvas = RgbaImage::from_pixel(THUMBNAIL_SIZE, THUMBNAIL_SIZE, BACKGROUND_COLOR);
+        let tiles_per_axis = 1i64 << zoom;
+        let first = (
+            (origin.0 / TILE_SIZE).floor() as i64,
+            (origin.1 / TILE_SIZE).floor() as i64,
+        );
+        let last = (
+            ((origin.0 + 2.0 * half - 1.0) / TILE_SIZE).floor() as i64,
+            ((origin.1 + 2.0 * half - 1.0) / TILE_SIZE).floor() as i64,
+        );
+        for ty in first.1..=last.1 {
+            if !(0..tiles_per_axis).contains(&ty) {
+                continue;
+            }
+            for tx in first.0..=last.0 {
+                let tile = self
+                    .fetch_tile(zoom, tx.rem_euclid(tiles_per_axis), ty)
+                    .await?;
+                let left = (tx as f64 * TILE_SIZE - origin.0).round() as i64;
+                let top = (ty as f64 * TILE_SIZE - origin.1).round() as i64;
+                imageops::overlay(&mut canvas, &tile, left, top);
+            }
+        }
+
+        draw_track(&mut canvas, segments, origin, zoom);
+        encode_png(&canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_pixel_matches_web_mercator() {
+        let (x, y) = world_pixel((0.0, 0.0), 0);
+        assert!((x - 128.0).abs() < 1e-9 && (y - 128.0).abs() < 1e-9);
+        let (x, y) = world_pixel((MAX_MERCATOR_LAT, -180.0), 1);
+        assert!(x.abs() < 1e-9 && y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn fit_zoom_grows_for_smaller_tracks() {
+        let city = [(55.70, 37.50), (55.80, 37.70)];
+        let walk = [(55.750, 37.600), (55.752, 37.604)];
+        assert!(fit_zoom(&walk) > fit_zoom(&city));
+        assert_eq!(fit_zoom(&[(55.75, 37.6)]), MAX_TILE_ZOOM);
+        assert_eq!(fit_zoom(&[(60.0, -170.0), (-40.0, 170.0)]), 0);
+    }
+
+    #[tokio::test]
+    async fn render_draws_track_over_tiles() {
+        let tile =
+            encode_png(&RgbaImage::from_pixel(256, 256, Rgba([255, 255, 255, 255]))).unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let tiles = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/\d+/\d+/\d+\.png$".into()),
+            )
+            .with_status(200)
+            .with_body(tile)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        let service = ThumbnailService {
+            client: reqwest::Client::new(),
+            tile_url: format!("{}/{{z}}/{{x}}/{{y}}.png", server.url()),
+        };
+
+        let png = service
+            .render(&[vec![(55.750, 37.600), (55.752, 37.604)]])
+            .await
+            .unwrap();
+        tiles.assert_async().await;
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+        // The track's midpoint is the image centre, give or take pixel rounding
+        assert!(
+            (127..=129)
+                .flat_map(|x| (127..=129).map(move |y| (x, y)))
+                .any(|(x, y)| *image.get_pixel(x, y) == TRACK_COLOR)
+        );
+        assert_eq!(*image.get_pixel(2, 2), Rgba([255, 255, 255, 255]));
+
+        assert!(matches!(
+            service.render(&[]).await,
+            Err(ThumbnailError::EmptyTrack)
+        ));
+    }
+}
@@ -1,19 +1,22 @@
 use crate::{
+    category_taxonomy::expand_with_parents,
     db,
     input_validation::{
         MAX_CATEGORIES, MAX_CATEGORY_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_FIELD_SIZE,
-        MAX_NAME_LENGTH, sanitize_input, validate_file_extension, validate_file_size,
-        validate_text_field,
+        MAX_NAME_LENGTH, sanitize_for_log, sanitize_input, validate_file_extension,
+        validate_file_size, validate_text_field,
     },
     metrics,
     models::{ParsedTrackData, ParsedWaypoint, TrackUploadResponse},
     poi_deduplication::PoiDeduplicationService,
     services::enrichment_queue,
-    track_utils::{self, extract_coordinates_from_geojson, parse_gpx_full, parse_gpx_minimal},
+    track_utils::{
+        self, TrackParseError, extract_coordinates_from_geojson, parse_gpx_full, parse_gpx_minimal,
+    },
 };
 use axum::http::StatusCode;
 use bytes::Bytes;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgPool, Postgres, Transaction};
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{error, info, warn};
@@ -28,6 +31,71 @@ pub struct TrackUploadRequest {
     pub file_bytes: Bytes,
 }
 
+/// Result of an upload attempt that distinguishes duplicates from failures
+pub enum TrackUploadOutcome {
+    Uploaded(TrackUploadResponse),
+    Duplicate(Uuid),
+}
+
+enum ParseOutcome {
+    Parsed(Box<ParsedTrackData>),
+    Duplicate(Uuid),
+}
+
+/// Sanitize submitted categories, reject unknown slugs with 400 and add parent
+/// categories. Shared by uploads and category edits.
+pub async fn resolve_categories(
+    pool: &Arc<PgPool>,
+    categories: &[String],
+) -> Result<Vec<String>, StatusCode> {
+    let sanitized: Vec<String> = categories
+        .iter()
+        .map(|c| sanitize_input(c, MAX_CATEGORY_LENGTH))
+        .collect();
+    let taxonomy = db::list_categories(pool).await.map_err(|e| {
+        error!(?e, "failed to load category taxonomy");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    expand_with_parents(&taxonomy, &sanitized).map_err(|unknown| {
+        warn!(category = %unknown, "unknown category");
+        StatusCode::BAD_REQUEST
+    })
+}
+
+/// HTTP status for a file that failed to parse: malformed content is 422, an encoding or
+/// format we cannot read is 415, and I/O failures are our problem
+fn track_parse_error_status(err: &TrackParseError) -> StatusCode {
+    match err {
+        TrackParseError::InvalidXml(_)
+        | TrackParseError::InvalidKml(_)
+        | TrackParseError::MissingField(_)
+        | TrackParseError::InvalidCoordinate { .. }
+        | TrackParseError::EmptyTrack => StatusCode::UNPROCESSABLE_ENTITY,
+        TrackParseError::UnsupportedFormat(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        TrackParseError::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Run a CPU-bound parser on the blocking pool so large files do not stall the runtime
+/// for other requests; `Bytes` clones share the buffer
+async fn parse_blocking<T, F>(file_bytes: &Bytes, parse: F) -> Result<T, StatusCode>
+where
+    T: Send + 'static,
+    F: FnOnce(&[u8]) -> Result<T, TrackParseError> + Send + 'static,
+{
+    let bytes = file_bytes.clone();
+    let parsed = tokio::task::spawn_blocking(move || parse(bytes.as_ref()))
+        .await
+        .map_err(|e| {
+            error!(error = ?e, endpoint = "upload_track_service", "parse task failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    parsed.map_err(|e| {
+        warn!(error = ?e, endpoint = "upload_track_service", "failed to parse track file");
+        track_parse_error_status(&e)
+    })
+}
+
 pub struct TrackUploadService {
     pool: Arc<PgPool>,
 }
@@ -37,33 +105,53 @@ impl TrackUploadService {
         Self { pool }
     }
 
-    #[tracing::instrument(skip(self, request), fields(endpoint = "upload_track_service", file_name = %request.file_name))]
     pub async fn upload_track(
         &self,
         request: TrackUploadRequest,
     ) -> Result<TrackUploadResponse, StatusCode> {
+        match self.upload_track_outcome(request).await? {
+            TrackUploadOutcome::Uploaded(response) => Ok(response),
+            TrackUploadOutcome::Duplicate(_) => Err(StatusCode::CONFLICT),
+        }
+    }
+
+    /// Same as `upload_track`, but reports duplicates with the id of the existing track
+    #[tracing::instrument(skip(self, request), fields(endpoint = "upload_track_service", file_name = %sanitize_for_log(&request.file_name)))]
+    pub async fn upload_track_outcome(
+        &self,
+        request: TrackUploadRequest,
+    ) -> Result<TrackUploadOutcome, StatusCode> {
         let pipeline_start = Instant::now();
         self.validate_request(&request)?;
         validate_file_size(request.file_bytes.len())?;
         let extension = validate_file_extension(&request.file_name)?;
+        let sanitized_categories = resolve_categories(&self.pool, &request.categories)
+            .await
+            .inspect_err(|&status| {
+                if status == StatusCode::BAD_REQUEST {
+                    metrics::record_track_upload_failure("validation");
+                }
+            })?;
 
-        let parsed_data = self
+        let parsed_data = match self
             .parse_and_check_duplicates(&request.file_bytes, &extension)
-            .await?;
+            .await?
+        {
+            ParseOutcome::Parsed(parsed) => *parsed,
+            ParseOutcome::Duplicate(existing_id) => {
+                return Ok(TrackUploadOutcome::Duplicate(existing_id));
+            }
+        };
 
         let track_id = Uuid::new_v4();
         let sanitized_name = request
             .name
             .as_ref()
-            .map(|n| sanitize_input(n))
-            .or_else(|| Some(sanitize_input(&request.file_name)))
+            .map(|n| sanitize_input(n, MAX_NAME_LENGTH))
+            .or_else(|| Some(sanitize_input(&request.file_name, MAX_NAME_LENGTH)))
             .unwrap_or_else(|| "Unnamed track".to_string());
-        let sanitized_description = request.description.as_ref().map(|d| sanitize_input(d));
-        let sanitized_categories: Vec<String> = request
-            .categories
-            .into_iter()
-            .map(|c| sanitize_input(&c))
-            .collect();
+        // Description HTML is sanitized with ammonia when the track is stored
+        let sanitized_description = request.description.as_ref().map(|d| d.trim().to_string());
         let category_refs: Vec<&str> = sanitized_categories.iter().map(|c| c.as_str()).collect();
 
         let elevation_profile_json = parsed_data
@@ -82,6 +170,14 @@ impl TrackUploadService {
             .temp_data
             .as_ref()
             .and_then(|data| serde_json::to_value(data).ok());
+        let cadence_data_json = parsed_data
+            .cadence_data
+            .as_ref()
+            .and_then(|data| serde_json::to_value(data).ok());
+        let power_data_json = parsed_data
+            .power_data
+            .as_ref()
+            .and_then(|data| serde_json::to_value(data).ok());
         let speed_data_json = parsed_data
             .speed_data
             .as_ref()
@@ -91,53 +187,91 @@ impl TrackUploadService {
             .as_ref()
             .and_then(|data| serde_json::to_value(data).ok());
 
-        db::insert_track(db::InsertTrackParams {
-            pool: &self.pool,
-            id: track_id,
-            name: &sanitized_name,
-            description: sanitized_description.clone(),
-            categories: &category_refs,
-            auto_classifications: &parsed_data.auto_classifications,
-            geom_geojson: &parsed_data.geom_geojson,
-            length_km: parsed_data.length_km,
-            elevation_profile_json,
-            hr_data_json,
-            temp_data_json,
-            time_data_json,
-            elevation_gain: parsed_data.elevation_gain,
-            elevation_loss: parsed_data.elevation_loss,
-            elevation_min: parsed_data.elevation_min,
-            elevation_max: parsed_data.elevation_max,
-            elevation_enriched: Some(false),
-            elevation_enriched_at: None,
-            elevation_dataset: Some("original_gpx".to_string()),
-            elevation_api_calls: Some(0),
-            slope_min: parsed_data.slope_min,
-            slope_max: parsed_data.slope_max,
-            slope_avg: parsed_data.slope_avg,
-            slope_histogram: parsed_data.slope_histogram.clone(),
-            slope_segments: parsed_data.slope_segments.clone(),
-            avg_speed: parsed_data.avg_speed,
-            avg_hr: parsed_data.avg_hr,
-            hr_min: parsed_data.hr_min,
-            hr_max: parsed_data.hr_max,
-            moving_time: parsed_data.moving_time,
-            pause_time: parsed_data.pause_time,
-            moving_avg_speed: parsed_data.moving_avg_speed,
-            moving_avg_pace: parsed_data.moving_avg_pace,
-            duration_seconds: parsed_data.duration_seconds,
-            hash: &parsed_data.hash,
-            recorded_at: parsed_data.recorded_at,
-            session_id: request.session_id,
-            speed_data_json,
-            pace_data_json,
-        })
+        // The track row and its POI links are committed together so a crash in between
+        // cannot leave a half-initialized track behind
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            error!(?e, "[upload_track_service] failed to begin transaction");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        db::insert_track(
+            &mut *tx,
+            db::InsertTrackParams {
+                id: track_id,
+                name: &sanitized_name,
+                description: sanitized_description.clone(),
+                categories: &category_refs,
+                auto_classifications: &parsed_data.auto_classifications,
+                geom_geojson: &parsed_data.geom_geojson,
+                length_km: parsed_data.length_km,
+                elevation_profile_json,
+                hr_data_json,
+                temp_data_json,
+                cadence_data_json,
+                power_data_json,
+                time_data_json,
+                elevation_gain: parsed_data.elevation_gain,
+                elevation_loss: parsed_data.elevation_loss,
+                elevation_min: parsed_data.elevation_min,
+                elevation_max: parsed_data.elevation_max,
+                elevation_enriched: Some(false),
+                elevation_enriched_at: None,
+                elevation_dataset: Some("original_gpx".to_string()),
+                elevation_api_calls: Some(0),
+                slope_min: parsed_data.slope_min,
+                slope_max: parsed_data.slope_max,
+                slope_avg: parsed_data.slope_avg,
+                slope_histogram: parsed_data.slope_histogram.clone(),
+                slope_segments: parsed_data.slope_segments.clone(),
+                avg_speed: parsed_data.avg_speed,
+                avg_hr: parsed_data.avg_hr,
+                hr_min: parsed_data.hr_min,
+                hr_max: parsed_data.hr_max,
+                cadence_avg: parsed_data.cadence_avg,
+                cadence_min: parsed_data.cadence_min,
+                cadence_max: parsed_data.cadence_max,
+                power_avg: parsed_data.power_avg,
+                power_max: parsed_data.power_max,
+                power_normalized: parsed_data.power_normalized,
+                moving_time: parsed_data.moving_time,
+                pause_time: parsed_data.pause_time,
+                moving_avg_speed: parsed_data.moving_avg_speed,
+                moving_avg_pace: parsed_data.moving_avg_pace,
+                moving_distance_km: parsed_data.moving_distance_km,
+                is_loop: parsed_data.is_loop,
+                elevation_source: parsed_data.elevation_source.map(|s| s.as_str().to_string()),
+                classification_confidence: Some(parsed_data.classification_confidence.clone()),
+                duration_seconds: parsed_data.duration_seconds,
+                hash: &parsed_data.hash,
+                recorded_at: parsed_data.recorded_at,
+                metadata_author: parsed_data.metadata_author.clone(),
+                metadata_link: parsed_data.metadata_link.clone(),
+                metadata_copyright: parsed_data.metadata_copyright.clone(),
+                session_id: request.session_id,
+                speed_data_json,
+                pace_data_json,
+            },
+        )
         .await
         .map_err(|e| {
+            if db::is_unique_violation(&e) {
+                warn!(
+                    ?e,
+                    "[upload_track_service] concurrent upload of the same track"
+                );
+                return StatusCode::CONFLICT;
+            }
             error!(?e, "[upload_track_service] failed to insert track");
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
+        self.process_waypoints(&mut tx, track_id, parsed_data.waypoints.clone())
+            .await;
+        tx.commit().await.map_err(|e| {
+            error!(?e, "[upload_track_service] failed to commit track");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
+        metrics::record_track_upload_format(&extension);
         metrics::observe_track_length_km("anonymous", parsed_data.length_km);
         for category in &sanitized_categories {
             metrics::record_track_category(category);
@@ -145,8 +279,6 @@ impl TrackUploadService {
 
         self.maybe_start_elevation_enrichment(track_id, &parsed_data)
             .await;
-        self.process_waypoints(track_id, parsed_data.waypoints.clone())
-            .await;
 
         metrics::observe_track_pipeline_latency("success", pipeline_start.elapsed().as_secs_f64());
 
@@ -157,10 +289,10 @@ impl TrackUploadService {
             "track persisted"
         );
 
-        Ok(TrackUploadResponse {
+        Ok(TrackUploadOutcome::Uploaded(TrackUploadResponse {
             id: track_id,
             url: format!("/tracks/{track_id}"),
-        })
+        }))
     }
 
     fn validate_request(&self, request: &TrackUploadRequest) -> Result<(), StatusCode> {
@@ -199,32 +331,21 @@ impl TrackUploadService {
         &self,
         file_bytes: &Bytes,
         extension: &str,
-    ) -> Result<ParsedTrackData, StatusCode> {
+    ) -> Result<ParseOutcome, StatusCode> {
         match extension {
             "gpx" => {
                 let minimal_start = Instant::now();
-                let minimal = parse_gpx_minimal(file_bytes.as_ref()).map_err(|e| {
-                    warn!(
-                        error = ?e,
-                        endpoint = "upload_track_service",
-                        stage = "gpx_minimal",
-                        "failed to parse gpx"
-                    );
-                    StatusCode::UNPROCESSABLE_ENTITY
-                })?;
-                metrics::observe_track_parse_duration(
-                    "gpx_minimal",
-                    minimal_start.elapsed().as_secs_f64(),
-                );
+                let minimal = parse_blocking(file_bytes, parse_gpx_minimal).await?;
+                let minimal_elapsed = minimal_start.elapsed().as_secs_f64();
+                metrics::observe_track_parse_duration("gpx_minimal", minimal_elapsed);
 
                 let dedup_db_start = Instant::now();
-                if db::track_exists(&self.pool, &minimal.hash)
+                if let Some(existing_id) = db::track_exists(&self.pool, &minimal.hash)
                     .await
                     .map_err(|e| {
                         error!(?e, "[upload_track_service] db error on dedup");
                         StatusCode::INTERNAL_SERVER_ERROR
                     })?
-                    .is_some()
                 {
                     metrics::record_track_deduplicated("gpx_hash_match");
                     warn!(
@@ -232,7 +353,7 @@ impl TrackUploadService {
                         endpoint = "upload_track_service",
                         "duplicate track detected by hash"
                     );
-                    return Err(StatusCode::CONFLICT);
+                    return Ok(ParseOutcome::Duplicate(existing_id));
                 }
                 let dedup_elapsed = dedup_db_start.elapsed().as_secs_f64();
                 metrics::observe_db_query("track_exists", dedup_elapsed);
@@ -243,38 +364,24 @@ impl TrackUploadService {
                     );
                 }
                 let full_parse_start = Instant::now();
-                let parsed = parse_gpx_full(file_bytes.as_ref()).map_err(|e| {
-                    warn!(
-                        error = ?e,
-                        endpoint = "upload_track_service",
-                        stage = "gpx_full",
-                        "failed to parse gpx"
-                    );
-                    StatusCode::UNPROCESSABLE_ENTITY
-                })?;
+                let parsed = parse_blocking(file_bytes, parse_gpx_full).await?;
                 let full_elapsed = full_parse_start.elapsed().as_secs_f64();
                 metrics::observe_track_parse_duration("gpx_full", full_elapsed);
+                metrics::observe_track_parse_duration("gpx", minimal_elapsed + full_elapsed);
                 if full_elapsed > 2.0 {
                     warn!(
                         "[upload_track_service] full gpx parse took {:.2}s",
                         full_elapsed
                     );
                 }
-                Ok(parsed)
+                Ok(ParseOutcome::Parsed(Box::new(parsed)))
             }
             "kml" => {
                 let kml_parse_start = Instant::now();
-                let parsed = track_utils::parse_kml(file_bytes.as_ref()).map_err(|e| {
-                    warn!(
-                        error = ?e,
-                        endpoint = "upload_track_service",
-                        stage = "kml_full",
-                        "failed to parse kml"
-                    );
-                    StatusCode::UNPROCESSABLE_ENTITY
-                })?;
+                let parsed = parse_blocking(file_bytes, track_utils::parse_kml).await?;
                 let kml_full_elapsed = kml_parse_start.elapsed().as_secs_f64();
                 metrics::observe_track_parse_duration("kml_full", kml_full_elapsed);
+                metrics::observe_track_parse_duration("kml", kml_full_elapsed);
                 if kml_full_elapsed > 2.0 {
                     warn!(
                         "[upload_track_service] full kml parse took {:.2}s",
@@ -283,13 +390,12 @@ impl TrackUploadService {
                 }
 
                 let dedup_db_start = Instant::now();
-                if db::track_exists(&self.pool, &parsed.hash)
+                if let Some(existing_id) = db::track_exists(&self.pool, &parsed.hash)
                     .await
                     .map_err(|e| {
                         error!(?e, "[upload_track_service] db error on dedup");
                         StatusCode::INTERNAL_SERVER_ERROR
                     })?
-                    .is_some()
                 {
                     metrics::record_track_deduplicated("kml_hash_match");
                     warn!(
@@ -297,11 +403,11 @@ impl TrackUploadService {
                         endpoint = "upload_track_service",
                         "duplicate track detected by hash"
                     );
-                    return Err(StatusCode::CONFLICT);
+                    return Ok(ParseOutcome::Duplicate(existing_id));
                 }
                 metrics::observe_db_query("track_exists", dedup_db_start.elapsed().as_secs_f64());
 
-                Ok(parsed)
+                Ok(ParseOutcome::Parsed(Box::new(parsed)))
             }
             _ => {
                 warn!(
@@ -371,7 +477,13 @@ impl TrackUploadService {
             || parsed_data.elevation_loss == Some(0.0)
     }
 
-    async fn process_waypoints(&self, track_id: Uuid, waypoints: Vec<ParsedWaypoint>) {
+    /// Links waypoints inside a savepoint, so a failure drops the links but keeps the track
+    async fn process_waypoints(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        track_id: Uuid,
+        waypoints: Vec<ParsedWaypoint>,
+    ) {
         if waypoints.is_empty() {
             return;
         }
@@ -384,9 +496,22 @@ impl TrackUploadService {
         );
 
         let poi_start = Instant::now();
-        if let Err(e) =
-            PoiDeduplicationService::link_pois_to_track(&self.pool, track_id, waypoints).await
-        {
+        let result = match tx.begin().await {
+            Ok(mut savepoint) => {
+                match PoiDeduplicationService::link_pois_to_track(
+                    &mut savepoint,
+                    track_id,
+                    waypoints,
+                )
+                .await
+                {
+                    Ok(_) => savepoint.commit().await,
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
             error!(track_id = %track_id, error = ?e, endpoint = "upload_track_service", "failed to link POIs");
         }
         let elapsed = poi_start.elapsed().as_secs_f64();
@@ -401,3 +526,24 @@ impl TrackUploadService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_parse_errors_map_to_client_or_server_status() {
+        assert_eq!(
+            track_parse_error_status(&TrackParseError::EmptyTrack),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            track_parse_error_status(&TrackParseError::UnsupportedFormat("utf-16".into())),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        assert_eq!(
+            track_parse_error_status(&TrackParseError::IoError(std::io::Error::other("disk"))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
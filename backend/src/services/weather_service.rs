@@ -0,0 +1,225 @@
+use crate::models::{TrackWeather, WeatherObservation};
+use chrono::{DateTime, Timelike, Utc};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::debug;
+
+const DEFAULT_WEATHER_API_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+const HOURLY_VARIABLES: &str = "temperature_2m,wind_speed_10m,precipitation,weather_code";
+
+static WEATHER_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, thiserror::Error)]
+pub enum WeatherError {
+    #[error("weather API request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("weather API returned {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    /// The archive has no observation for the requested hour (e.g. it is too recent)
+    #[error("no weather data for {0}")]
+    NoData(DateTime<Utc>),
+}
+
+#[derive(Debug, Deserialize)]
+struct ArchiveResponse {
+    hourly: HourlySeries,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlySeries {
+    temperature_2m: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+    weather_code: Vec<Option<i32>>,
+}
+
+impl HourlySeries {
+    fn observation(&self, hour: usize) -> Option<WeatherObservation> {
+        Some(WeatherObservation {
+            temperature_c: (*self.temperature_2m.get(hour)?)?,
+            wind_speed_kmh: (*self.wind_speed_10m.get(hour)?)?,
+            precipitation_mm: (*self.precipitation.get(hour)?)?,
+            weather_code: (*self.weather_code.get(hour)?)?,
+        })
+    }
+}
+
+/// Client for the Open-Meteo historical weather archive (free, no API key)
+pub struct WeatherService {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for WeatherService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WeatherService {
+    pub fn new() -> Self {
+        Self {
+            client: WEATHER_CLIENT.clone(),
+            base_url: std::env::var("WEATHER_API_URL")
+                .unwrap_or_else(|_| DEFAULT_WEATHER_API_URL.to_string()),
+        }
+    }
+
+    /// Hourly conditions at `lat`/`lon` for the hour containing `at`
+    pub async fn fetch_historical_weather(
+        &self,
+        lat: f64,
+        lon: f64,
+        at: DateTime<Utc>,
+    ) -> Result<WeatherObservation, WeatherError> {
+        let date = at.format("%Y-%m-%d").to_string();
+        debug!(lat, lon, date = %date, "fetching historical weather");
+        let response = self
+            .client
+            .get(&self.base_url)
+            .query(&[
+                ("latitude", lat.to_string()),
+                ("longitude", lon.to_string()),
+                ("start_date", date.clone()),
+                ("end_date", date),
+                ("hourly", HOURLY_VARIABLES.to_string()),
+                ("timezone", "UTC".to_string()),
+                ("wind_speed_unit", "kmh".to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(WeatherError::Status { status, body });
+        }
+        let archive: ArchiveResponse = response.json().await?;
+        archive
+            .hourly
+            .observation(at.hour() as usize)
+            .ok_or(WeatherError::NoData(at))
+    }
+}
+
+/// Human readable text for a WMO weather interpretation code
+pub fn describe_weather_code(code: i32) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1 => "Mainly clear",
+        2 => "Partly cloudy",
+        3 => "Overcast",
+        45 | 48 => "Fog",
+        51 | 53 | 55 => "Drizzle",
+        56 | 57 => "Freezing drizzle",
+        61 => "Slight rain",
+        63 => "Moderate rain",
+        65 => "Heavy rain",
+        66 | 67 => "Freezing rain",
+        71 => "Slight snow fall",
+        73 => "Moderate snow fall",
+        75 => "Heavy snow fall",
+        77 => "Snow grains",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95 => "Thunderstorm",
+        96 | 99 => "Thunderstorm with hail",
+        _ => "Unknown",
+    }
+}
+
+impl From<&WeatherObservation> for TrackWeather {
+    fn from(observation: &WeatherObservation) -> Self {
+        TrackWeather {
+            temperature_c: observation.temperature_c,
+            wind_speed_kmh: observation.wind_speed_kmh,
+            precipitation_mm: observation.precipitation_mm,
+            description: describe_weather_code(observation.weather_code).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn service(base_url: String) -> WeatherService {
+        WeatherService {
+            client: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    fn archive_body(temperature_at_9: &str) -> String {
+        let mut temperature = vec!["1.0"; 24];
+        temperature[9] = temperature_at_9;
+        format!(
+            r#"{{"hourly":{{"time":[],"temperature_2m":[{}],"wind_speed_10m":[{}],"precipitation":[{}],"weather_code":[{}]}}}}"#,
+            temperature.join(","),
+            ["12.5"; 24].join(","),
+            ["0.4"; 24].join(","),
+            ["61"; 24].join(","),
+        )
+    }
+
+    #[tokio::test]
+    async fn fetch_picks_the_hour_of_the_track() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("start_date".into(), "2024-05-01".into()),
+                mockito::Matcher::UrlEncoded("latitude".into(), "55.75".into()),
+            ]))
+            .with_status(200)
+            .with_body(archive_body("17.3"))
+            .create_async()
+            .await;
+
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 9, 42, 0).unwrap();
+        let observation = service(server.url())
+            .fetch_historical_weather(55.75, 37.62, at)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(observation.temperature_c, 17.3);
+        assert_eq!(observation.wind_speed_kmh, 12.5);
+        assert_eq!(TrackWeather::from(&observation).description, "Slight rain");
+    }
+
+    #[tokio::test]
+    async fn fetch_reports_missing_observations() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(archive_body("null"))
+            .create_async()
+            .await;
+
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let err = service(server.url())
+            .fetch_historical_weather(55.75, 37.62, at)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WeatherError::NoData(_)));
+    }
+
+    #[test]
+    fn describe_weather_code_covers_wmo_groups() {
+        assert_eq!(describe_weather_code(0), "Clear sky");
+        assert_eq!(describe_weather_code(81), "Rain showers");
+        assert_eq!(describe_weather_code(99), "Thunderstorm with hail");
+        assert_eq!(describe_weather_code(42), "Unknown");
+    }
+}
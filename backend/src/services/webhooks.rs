@@ -0,0 +1,249 @@
+use crate::db;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+pub const EVENT_TRACK_UPLOADED: &str = "track.uploaded";
+pub const EVENT_ELEVATION_ENRICHED: &str = "elevation.enriched";
+pub const SUPPORTED_EVENTS: &[&str] = &[EVENT_TRACK_UPLOADED, EVENT_ELEVATION_ENRICHED];
+
+pub const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+pub const SIGNATURE_HEADER: &str = "X-Trackly-Signature";
+pub const EVENT_HEADER: &str = "X-Trackly-Event";
+
+// Redirects could point a vetted URL at an internal service, so they are not followed;
+// the resolver drops non-public addresses in case DNS changed since registration
+static WEBHOOK_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(PublicOnlyResolver))
+        .build()
+        .unwrap_or_default()
+});
+
+/// Resolves hostnames to their public addresses only
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+pub fn is_supported_event(event: &str) -> bool {
+    SUPPORTED_EVENTS.contains(&event)
+}
+
+pub fn is_valid_webhook_url(url: &str) -> bool {
+    url.len() <= MAX_WEBHOOK_URL_LENGTH
+        && (url.starts_with("https://") || url.starts_with("http://"))
+        && reqwest::Url::parse(url).is_ok()
+}
+
+/// Whether `ip` is reachable on the public internet, i.e. not loopback, private,
+/// link-local (including cloud metadata at 169.254.169.254) or otherwise reserved
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_ipv4(mapped),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        || ip.is_multicast())
+}
+
+/// Whether every address `url` points at is public. Checked when a webhook is
+/// registered and again before each delivery.
+pub async fn is_public_webhook_target(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    // IPv6 literals keep their brackets in the host string
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<IpAddr> = match host.parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => match tokio::net::lookup_host((host, port)).await {
+            Ok(addrs) => addrs.map(|addr| addr.ip()).collect(),
+            Err(e) => {
+                debug!(host, error = %e, "webhook host did not resolve");
+                return false;
+            }
+        },
+    };
+    !addrs.is_empty() && addrs.into_iter().all(is_public_ip)
+}
+
+/// HMAC-SHA256 of `payload`, hex encoded
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Notify the track owner's webhooks about `event` in the background.
+/// Delivery failures are logged and never affect the caller.
+pub fn dispatch_track_event(
+    pool: Arc<PgPool>,
+    event: &'static str,
+    track_id: Uuid,
+    data: serde_json::Value,
+) {
+    tokio::spawn(async move {
+        let webhooks = match db::find_webhooks_for_track_event(&pool, track_id, event).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                error!(error = ?e, %track_id, event, "failed to load webhooks");
+                return;
+            }
+        };
+        if webhooks.is_empty() {
+            return;
+        }
+
+        let payload = json!({
+            "event": event,
+            "track_id": track_id,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "data": data,
+        });
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(error = ?e, %track_id, event, "failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            if !is_public_webhook_target(&webhook.url).await {
+                warn!(webhook_id = %webhook.id, %track_id, event, "webhook target is not public, skipping delivery");
+                continue;
+            }
+            let signature = sign_payload(&webhook.secret, &body);
+            let result = WEBHOOK_CLIENT
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event)
+                .header(SIGNATURE_HEADER, format!("sha256={signature}"))
+                .body(body.clone())
+                .send()
+                .await;
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    debug!(webhook_id = %webhook.id, %track_id, event, "webhook delivered");
+                }
+                Ok(response) => {
+                    warn!(
+                        webhook_id = %webhook.id,
+                        %track_id,
+                        event,
+                        status = %response.status(),
+                        "webhook endpoint rejected delivery"
+                    );
+                }
+                Err(e) => {
+                    warn!(webhook_id = %webhook.id, %track_id, event, error = %e, "webhook delivery failed");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_payload_matches_rfc4231_vector() {
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn webhook_validation() {
+        assert!(is_supported_event("track.uploaded"));
+        assert!(!is_supported_event("track.deleted"));
+        assert!(is_valid_webhook_url("https://example.com/hooks/trackly"));
+        assert!(!is_valid_webhook_url("ftp://example.com"));
+        assert!(!is_valid_webhook_url("https://"));
+    }
+
+    #[test]
+    fn rejects_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+        assert!(is_public_ip("2606:2800:220:1::".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn webhook_target_must_not_be_internal() {
+        assert!(!is_public_webhook_target("http://169.254.169.254/latest/meta-data").await);
+        assert!(!is_public_webhook_target("http://127.0.0.1:8080/hook").await);
+        assert!(!is_public_webhook_target("http://[::1]/hook").await);
+        assert!(!is_public_webhook_target("http://localhost/hook").await);
+        assert!(is_public_webhook_target("https://93.184.216.34/hook").await);
+    }
+}
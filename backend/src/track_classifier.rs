@@ -17,10 +17,57 @@ pub enum TrackClassification {
     Trail,  // High elevation gain + running speed
     Hiking, // Low speed + elevation gain
     Walk,   // Very low speed
+    EBike,  // Sustained assisted-cycling speed (25-50 km/h) with steady heart rate
 }
 
+use serde::Serialize;
 use std::fmt;
 
+impl TrackClassification {
+    /// All known classifications, used to map stored labels back to variants
+    pub const ALL: [TrackClassification; 12] = [
+        TrackClassification::Marathon,
+        TrackClassification::HalfMarathon,
+        TrackClassification::LongRun,
+        TrackClassification::Interval,
+        TrackClassification::Fartlek,
+        TrackClassification::TempoRun,
+        TrackClassification::AerobicRun,
+        TrackClassification::RecoveryRun,
+        TrackClassification::Trail,
+        TrackClassification::Hiking,
+        TrackClassification::Walk,
+        TrackClassification::EBike,
+    ];
+
+    /// Slug of the matching entry in the category taxonomy (`categories` table)
+    pub fn category_slug(&self) -> &'static str {
+        match self {
+            TrackClassification::Trail => "trail_running",
+            TrackClassification::Walk => "walking",
+            TrackClassification::Marathon => "marathon",
+            TrackClassification::HalfMarathon => "half_marathon",
+            TrackClassification::LongRun => "long_run",
+            TrackClassification::Interval => "interval",
+            TrackClassification::Fartlek => "fartlek",
+            TrackClassification::TempoRun => "tempo_run",
+            TrackClassification::AerobicRun => "aerobic_run",
+            TrackClassification::RecoveryRun => "recovery_run",
+            TrackClassification::Hiking => "hiking",
+            TrackClassification::EBike => "e_bike",
+        }
+    }
+
+    /// Parse a stored label back into a classification.
+    /// Accepts taxonomy slugs as well as the older `Display` labels.
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|c| c.category_slug() == label || c.to_string() == label)
+            .cloned()
+    }
+}
+
 impl fmt::Display for TrackClassification {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
@@ -35,11 +82,56 @@ impl fmt::Display for TrackClassification {
             TrackClassification::Trail => "trail",
             TrackClassification::Hiking => "hiking",
             TrackClassification::Walk => "walk",
+            TrackClassification::EBike => "e_bike",
         };
         write!(f, "{s}")
     }
 }
 
+/// Marathon distance (km); tracks within `MARATHON_RANGE_KM` count as marathons
+const MARATHON_KM: f64 = 42.195;
+const MARATHON_RANGE_KM: std::ops::RangeInclusive<f64> = 40.2..=44.2;
+/// Half marathon distance (km); tracks within `HALF_MARATHON_RANGE_KM` count as half marathons
+const HALF_MARATHON_KM: f64 = 21.0975;
+const HALF_MARATHON_RANGE_KM: std::ops::RangeInclusive<f64> = 20.1..=22.1;
+/// Distance (km) beyond which a run is a long run
+const LONG_RUN_MIN_KM: f64 = 15.0;
+/// Moving average speed (km/h) below which a run is a recovery run
+const RECOVERY_RUN_MAX_KMH: f64 = 8.0;
+/// Moving average speed range (km/h) of aerobic runs
+const AEROBIC_RUN_RANGE_KMH: std::ops::RangeInclusive<f64> = 8.0..=12.0;
+/// Moving average speed (km/h) above which a run is a tempo run
+const TEMPO_RUN_MIN_KMH: f64 = 12.0;
+/// Moving average speed (km/h) below which a track is a walk
+const WALK_MAX_KMH: f64 = 5.0;
+/// Moving average speed range (km/h) and minimum elevation gain (m) of hikes
+const HIKING_SPEED_RANGE_KMH: std::ops::Range<f64> = 3.0..8.0;
+const HIKING_MIN_GAIN_M: f64 = 200.0;
+/// Minimum moving average speed (km/h) and elevation gain (m) of trail runs
+const TRAIL_MIN_SPEED_KMH: f64 = 8.0;
+const TRAIL_MIN_GAIN_M: f64 = 500.0;
+
+/// Moving average speed range (km/h) sustained by e-bike rides
+pub const EBIKE_SPEED_RANGE_KMH: std::ops::RangeInclusive<f64> = 25.0..=50.0;
+/// Heart rate coefficient of variation below which effort counts as steady, as when a
+/// motor does much of the work
+pub const EBIKE_MAX_HR_VARIATION: f64 = 0.1;
+
+/// Coefficient of variation (standard deviation / mean) of the recorded heart rate;
+/// `None` with fewer than three samples
+pub fn hr_variation(hr_data: &[Option<i32>]) -> Option<f64> {
+    let hrs: Vec<f64> = hr_data.iter().filter_map(|&hr| hr.map(f64::from)).collect();
+    if hrs.len() < 3 {
+        return None;
+    }
+    let mean = hrs.iter().sum::<f64>() / hrs.len() as f64;
+    if mean <= 0.0 {
+        return None;
+    }
+    let variance = hrs.iter().map(|hr| (hr - mean).powi(2)).sum::<f64>() / hrs.len() as f64;
+    Some(variance.sqrt() / mean)
+}
+
 /// Track metrics used for classification analysis
 #[derive(Debug, Clone)]
 pub struct TrackMetrics {
@@ -50,6 +142,8 @@ pub struct TrackMetrics {
     pub elevation_loss: Option<f64>,   // meters (unified field)
     pub moving_time: Option<i32>,      // seconds
     pub duration_seconds: Option<i32>, // total seconds
+    pub is_loop: bool,                 // starts and ends at the same point
+    pub hr_variation: Option<f64>,     // heart rate coefficient of variation, see `hr_variation`
 }
 
 /// Track classifier that analyzes metrics and determines classifications
@@ -77,17 +171,17 @@ impl TrackClassifier {
         let mut classifications = Vec::new();
 
         // Marathon: 42.195km ± 2km (40.2 - 44.2km)
-        if metrics.length_km >= 40.2 && metrics.length_km <= 44.2 {
+        if MARATHON_RANGE_KM.contains(&metrics.length_km) {
             classifications.push(TrackClassification::Marathon);
         }
 
         // Half Marathon: 21.1km ± 1km (20.1 - 22.1km)
-        if metrics.length_km >= 20.1 && metrics.length_km <= 22.1 {
+        if HALF_MARATHON_RANGE_KM.contains(&metrics.length_km) {
             classifications.push(TrackClassification::HalfMarathon);
         }
 
         // Long run: >15km
-        if metrics.length_km > 15.0 {
+        if metrics.length_km > LONG_RUN_MIN_KM {
             classifications.push(TrackClassification::LongRun);
         }
 
@@ -100,15 +194,15 @@ impl TrackClassifier {
 
         if let Some(avg_speed) = metrics.moving_avg_speed {
             // Recovery run: very low speed (< 8 km/h)
-            if avg_speed < 8.0 {
+            if avg_speed < RECOVERY_RUN_MAX_KMH {
                 classifications.push(TrackClassification::RecoveryRun);
             }
             // Aerobic run: moderate speed (8-12 km/h)
-            else if (8.0..=12.0).contains(&avg_speed) {
+            else if AEROBIC_RUN_RANGE_KMH.contains(&avg_speed) {
                 classifications.push(TrackClassification::AerobicRun);
             }
             // Tempo run: high speed (> 12 km/h)
-            else if avg_speed > 12.0 {
+            else if avg_speed > TEMPO_RUN_MIN_KMH {
                 classifications.push(TrackClassification::TempoRun);
             }
         }
@@ -124,27 +218,220 @@ impl TrackClassifier {
         let avg_speed = metrics.moving_avg_speed.unwrap_or(0.0);
 
         // Walk: very low speed (< 5 km/h)
-        if avg_speed < 5.0 {
+        if avg_speed < WALK_MAX_KMH {
             classifications.push(TrackClassification::Walk);
         }
 
         // Hiking: low speed + elevation gain
-        if (3.0..8.0).contains(&avg_speed) && elevation_gain > 200.0 {
+        if HIKING_SPEED_RANGE_KMH.contains(&avg_speed) && elevation_gain > HIKING_MIN_GAIN_M {
             classifications.push(TrackClassification::Hiking);
         }
 
         // Trail: running speed + significant elevation gain
-        if avg_speed >= 8.0 && elevation_gain > 500.0 {
+        if avg_speed >= TRAIL_MIN_SPEED_KMH && elevation_gain > TRAIL_MIN_GAIN_M {
             classifications.push(TrackClassification::Trail);
         }
 
+        // E-bike: sustained assisted-cycling speed with steady heart rate; without HR
+        // a fast human-powered ride looks the same
+        if EBIKE_SPEED_RANGE_KMH.contains(&avg_speed)
+            && metrics
+                .hr_variation
+                .is_some_and(|cv| cv < EBIKE_MAX_HR_VARIATION)
+        {
+            classifications.push(TrackClassification::EBike);
+        }
+
         classifications
     }
 }
 
-/// Public API: classify a track given its metrics
-pub fn classify_track(metrics: &TrackMetrics) -> Vec<TrackClassification> {
-    TrackClassifier::classify(metrics)
+/// Why a classification applies to a track and how clearly it does so
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassificationExplanation {
+    pub confidence: f64, // 0.5 at the threshold, 1.0 when well inside it, lower when implausible
+    pub reasoning: String,
+}
+
+/// Confidence added to training-run labels when the track is a loop
+const LOOP_RUN_CONFIDENCE_BONUS: f64 = 0.1;
+
+/// Map how far a metric lies inside a threshold onto a 0.5..=1.0 confidence
+fn margin_confidence(margin: f64, scale: f64) -> f64 {
+    0.5 + 0.5 * (margin / scale).clamp(0.0, 1.0)
+}
+
+impl TrackClassifier {
+    /// Explain why `classification` applies to the given metrics.
+    /// Returns `None` when the classification does not apply (or cannot be explained).
+    pub fn explain(
+        classification: &TrackClassification,
+        metrics: &TrackMetrics,
+    ) -> Option<ClassificationExplanation> {
+        let length = metrics.length_km;
+        let speed = metrics.moving_avg_speed;
+        let gain = metrics.elevation_gain.unwrap_or(0.0);
+
+        let (confidence, reasoning) = match classification {
+            TrackClassification::Marathon if MARATHON_RANGE_KM.contains(&length) => (
+                margin_confidence(2.0 - (length - MARATHON_KM).abs(), 2.0),
+                format!(
+                    "Distance {length:.2} km is within 2 km of the marathon distance ({MARATHON_KM} km)"
+                ),
+            ),
+            TrackClassification::HalfMarathon if HALF_MARATHON_RANGE_KM.contains(&length) => (
+                margin_confidence(1.0 - (length - HALF_MARATHON_KM).abs(), 1.0),
+                format!(
+                    "Distance {length:.2} km is within 1 km of the half marathon distance ({HALF_MARATHON_KM:.1} km)"
+                ),
+            ),
+            TrackClassification::LongRun if length > LONG_RUN_MIN_KM => (
+                margin_confidence(length - LONG_RUN_MIN_KM, 10.0),
+                format!(
+                    "Distance {length:.2} km exceeds the {LONG_RUN_MIN_KM} km long run threshold"
+                ),
+            ),
+            TrackClassification::RecoveryRun => {
+                let s = speed.filter(|s| *s < RECOVERY_RUN_MAX_KMH)?;
+                if s < WALK_MAX_KMH {
+                    // Walking pace: the speed rule matches, but this is unlikely to be a run
+                    (
+                        0.5 * s / WALK_MAX_KMH,
+                        format!(
+                            "Moving average speed {s:.1} km/h is below {RECOVERY_RUN_MAX_KMH} km/h, but at walking pace"
+                        ),
+                    )
+                } else {
+                    (
+                        margin_confidence((RECOVERY_RUN_MAX_KMH - s).min(s - WALK_MAX_KMH), 1.5),
+                        format!(
+                            "Moving average speed {s:.1} km/h is below {RECOVERY_RUN_MAX_KMH} km/h"
+                        ),
+                    )
+                }
+            }
+            TrackClassification::AerobicRun => {
+                let s = speed.filter(|s| AEROBIC_RUN_RANGE_KMH.contains(s))?;
+                let (low, high) = (*AEROBIC_RUN_RANGE_KMH.start(), *AEROBIC_RUN_RANGE_KMH.end());
+                (
+                    margin_confidence((s - low).min(high - s), 2.0),
+                    format!("Moving average speed {s:.1} km/h is between {low} and {high} km/h"),
+                )
+            }
+            TrackClassification::TempoRun => {
+                let s = speed.filter(|s| *s > TEMPO_RUN_MIN_KMH)?;
+                (
+                    margin_confidence(s - TEMPO_RUN_MIN_KMH, 2.0),
+                    format!("Moving average speed {s:.1} km/h is above {TEMPO_RUN_MIN_KMH} km/h"),
+                )
+            }
+            TrackClassification::Walk => {
+                let s = speed.unwrap_or(0.0);
+                if s >= WALK_MAX_KMH {
+                    return None;
+                }
+                (
+                    margin_confidence(WALK_MAX_KMH - s, 2.0),
+                    format!("Moving average speed {s:.1} km/h is below {WALK_MAX_KMH} km/h"),
+                )
+            }
+            TrackClassification::Hiking => {
+                let s = speed.unwrap_or(0.0);
+                if !HIKING_SPEED_RANGE_KMH.contains(&s) || gain <= HIKING_MIN_GAIN_M {
+                    return None;
+                }
+                (
+                    margin_confidence(gain - HIKING_MIN_GAIN_M, 300.0),
+                    format!(
+                        "Moving average speed {s:.1} km/h with {gain:.0} m of elevation gain (over {HIKING_MIN_GAIN_M} m)"
+                    ),
+                )
+            }
+            TrackClassification::Trail => {
+                let s = speed.unwrap_or(0.0);
+                if s < TRAIL_MIN_SPEED_KMH || gain <= TRAIL_MIN_GAIN_M {
+                    return None;
+                }
+                (
+                    margin_confidence(gain - TRAIL_MIN_GAIN_M, 500.0),
+                    format!(
+                        "Running speed {s:.1} km/h with {gain:.0} m of elevation gain (over {TRAIL_MIN_GAIN_M} m)"
+                    ),
+                )
+            }
+            TrackClassification::EBike => {
+                let s = speed.filter(|s| EBIKE_SPEED_RANGE_KMH.contains(s))?;
+                let cv = metrics
+                    .hr_variation
+                    .filter(|&cv| cv < EBIKE_MAX_HR_VARIATION)?;
+                let (low, high) = (*EBIKE_SPEED_RANGE_KMH.start(), *EBIKE_SPEED_RANGE_KMH.end());
+                (
+                    margin_confidence((s - low).min(high - s), 5.0),
+                    format!(
+                        "Moving average speed {s:.1} km/h is between {low} and {high} km/h with steady heart rate ({:.0}% variation)",
+                        cv * 100.0
+                    ),
+                )
+            }
+            _ => return None,
+        };
+
+        // Training runs usually return to where they started; races and commutes often do not
+        let is_training_run = matches!(
+            classification,
+            TrackClassification::LongRun
+                | TrackClassification::TempoRun
+                | TrackClassification::AerobicRun
+                | TrackClassification::RecoveryRun
+        );
+        let (confidence, reasoning) = if metrics.is_loop && is_training_run {
+            (
+                (confidence + LOOP_RUN_CONFIDENCE_BONUS).min(1.0),
+                format!("{reasoning}, on a loop course"),
+            )
+        } else {
+            (confidence, reasoning)
+        };
+
+        Some(ClassificationExplanation {
+            confidence,
+            reasoning,
+        })
+    }
+}
+
+/// A classification label (taxonomy slug) and how clearly the track matches it (0.0-1.0)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClassificationResult {
+    pub label: String,
+    pub confidence: f64,
+}
+
+impl ClassificationResult {
+    /// Label -> confidence map as stored in `tracks.classification_confidence`
+    pub fn confidence_map(results: &[ClassificationResult]) -> serde_json::Value {
+        results
+            .iter()
+            .map(|r| (r.label.clone(), serde_json::Value::from(r.confidence)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// Public API: classify a track given its metrics, most confident label first
+pub fn classify_track(metrics: &TrackMetrics) -> Vec<ClassificationResult> {
+    let mut results: Vec<ClassificationResult> = TrackClassifier::classify(metrics)
+        .iter()
+        .map(|c| ClassificationResult {
+            label: c.category_slug().to_string(),
+            confidence: TrackClassifier::explain(c, metrics)
+                .map(|e| e.confidence)
+                .unwrap_or(0.5),
+        })
+        .collect();
+    // Stable sort: equally confident labels keep the classifier's order
+    results.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    results
 }
 
 #[cfg(test)]
@@ -161,6 +448,8 @@ mod tests {
             elevation_loss: Some(100.0),
             moving_time: Some(3600),
             duration_seconds: Some(3600),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -178,6 +467,8 @@ mod tests {
             elevation_loss: Some(50.0),
             moving_time: Some(1800),
             duration_seconds: Some(1800),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -195,6 +486,8 @@ mod tests {
             elevation_loss: Some(20.0),
             moving_time: Some(2571), // ~43 min
             duration_seconds: Some(2571),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -211,6 +504,8 @@ mod tests {
             elevation_loss: Some(600.0),
             moving_time: Some(4000),
             duration_seconds: Some(4000),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -227,6 +522,8 @@ mod tests {
             elevation_loss: Some(400.0),
             moving_time: Some(6400),
             duration_seconds: Some(6400),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -243,6 +540,8 @@ mod tests {
             elevation_loss: Some(10.0),
             moving_time: Some(2700),
             duration_seconds: Some(2700),
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -259,6 +558,8 @@ mod tests {
             elevation_loss: None,
             moving_time: None,
             duration_seconds: None,
+            is_loop: false,
+            hr_variation: None,
         };
 
         let classifications = TrackClassifier::classify(&metrics);
@@ -268,6 +569,49 @@ mod tests {
         assert!(!classifications.contains(&TrackClassification::TempoRun));
     }
 
+    #[test]
+    fn test_explain_matches_classify() {
+        let metrics = TrackMetrics {
+            length_km: 42.195,
+            avg_speed: Some(10.0),
+            moving_avg_speed: Some(10.0),
+            elevation_gain: Some(100.0),
+            elevation_loss: Some(100.0),
+            moving_time: Some(15000),
+            duration_seconds: Some(15000),
+            is_loop: false,
+            hr_variation: None,
+        };
+
+        for classification in TrackClassifier::classify(&metrics) {
+            let explanation = TrackClassifier::explain(&classification, &metrics)
+                .expect("every assigned classification should be explainable");
+            assert!((0.5..=1.0).contains(&explanation.confidence));
+            assert!(!explanation.reasoning.is_empty());
+        }
+
+        let marathon = TrackClassifier::explain(&TrackClassification::Marathon, &metrics).unwrap();
+        assert_eq!(marathon.confidence, 1.0);
+        assert!(TrackClassifier::explain(&TrackClassification::Trail, &metrics).is_none());
+    }
+
+    #[test]
+    fn test_classification_from_label() {
+        assert_eq!(
+            TrackClassification::from_label("half_marathon"),
+            Some(TrackClassification::HalfMarathon)
+        );
+        assert_eq!(
+            TrackClassification::from_label("trail_running"),
+            Some(TrackClassification::Trail)
+        );
+        assert_eq!(
+            TrackClassification::from_label("trail"),
+            Some(TrackClassification::Trail)
+        );
+        assert_eq!(TrackClassification::from_label("running"), None);
+    }
+
     #[test]
     fn test_classification_to_string() {
         assert_eq!(TrackClassification::Marathon.to_string(), "marathon");
@@ -278,5 +622,127 @@ mod tests {
         assert_eq!(TrackClassification::Trail.to_string(), "trail");
         assert_eq!(TrackClassification::Hiking.to_string(), "hiking");
         assert_eq!(TrackClassification::Walk.to_string(), "walk");
+        assert_eq!(TrackClassification::EBike.to_string(), "e_bike");
+    }
+
+    #[test]
+    fn test_ebike_classification() {
+        let metrics = TrackMetrics {
+            length_km: 30.0,
+            avg_speed: Some(32.0),
+            moving_avg_speed: Some(34.0),
+            elevation_gain: Some(150.0),
+            elevation_loss: Some(150.0),
+            moving_time: Some(3200),
+            duration_seconds: Some(3400),
+            is_loop: false,
+            hr_variation: Some(0.04),
+        };
+
+        let classifications = TrackClassifier::classify(&metrics);
+        assert!(classifications.contains(&TrackClassification::EBike));
+        assert!(TrackClassifier::explain(&TrackClassification::EBike, &metrics).is_some());
+
+        // Speed alone cannot tell an e-bike from a fast road ride
+        for hr_variation in [None, Some(0.2)] {
+            let metrics = TrackMetrics {
+                hr_variation,
+                ..metrics.clone()
+            };
+            assert!(!TrackClassifier::classify(&metrics).contains(&TrackClassification::EBike));
+            assert!(TrackClassifier::explain(&TrackClassification::EBike, &metrics).is_none());
+        }
+        assert!(hr_variation(&[Some(110), Some(112), None, Some(111)]).unwrap() < 0.1);
+        assert_eq!(hr_variation(&[Some(110), None]), None);
+        assert_eq!(
+            TrackClassification::from_label("e_bike"),
+            Some(TrackClassification::EBike)
+        );
+    }
+
+    #[test]
+    fn test_walking_track_has_low_running_confidence() {
+        let running_confidence = |speed: f64| {
+            let metrics = TrackMetrics {
+                length_km: 3.0,
+                avg_speed: Some(speed),
+                moving_avg_speed: Some(speed),
+                elevation_gain: Some(10.0),
+                elevation_loss: Some(10.0),
+                moving_time: Some((3.0 / speed * 3600.0) as i32),
+                duration_seconds: Some((3.0 / speed * 3600.0) as i32),
+                is_loop: false,
+                hr_variation: None,
+            };
+            classify_track(&metrics)
+                .into_iter()
+                .filter(|r| {
+                    TrackClassification::from_label(&r.label).is_some_and(|c| {
+                        matches!(
+                            c,
+                            TrackClassification::RecoveryRun
+                                | TrackClassification::AerobicRun
+                                | TrackClassification::TempoRun
+                        )
+                    })
+                })
+                .map(|r| r.confidence)
+                .fold(0.0, f64::max)
+        };
+
+        let walk = running_confidence(3.5); // ~17 min/km
+        let run = running_confidence(10.0); // 6 min/km
+        assert!(
+            walk < 0.5,
+            "walking pace should not look like a run: {walk}"
+        );
+        assert!(walk < run, "walk {walk} vs 6 min/km run {run}");
+    }
+
+    #[test]
+    fn test_classify_track_orders_by_confidence() {
+        let metrics = TrackMetrics {
+            length_km: 3.0,
+            avg_speed: Some(3.5),
+            moving_avg_speed: Some(3.5),
+            elevation_gain: Some(10.0),
+            elevation_loss: Some(10.0),
+            moving_time: Some(3086),
+            duration_seconds: Some(3086),
+            is_loop: false,
+            hr_variation: None,
+        };
+        let results = classify_track(&metrics);
+        assert_eq!(results[0].label, "walking");
+        assert!(
+            results
+                .windows(2)
+                .all(|w| w[0].confidence >= w[1].confidence)
+        );
+
+        let map = ClassificationResult::confidence_map(&results);
+        assert_eq!(map.as_object().unwrap().len(), results.len());
+    }
+
+    #[test]
+    fn test_loop_raises_training_run_confidence() {
+        let mut metrics = TrackMetrics {
+            length_km: 8.0,
+            avg_speed: Some(8.5),
+            moving_avg_speed: Some(8.5),
+            elevation_gain: Some(20.0),
+            elevation_loss: Some(20.0),
+            moving_time: Some(3388),
+            duration_seconds: Some(3388),
+            is_loop: false,
+            hr_variation: None,
+        };
+        let point_to_point =
+            TrackClassifier::explain(&TrackClassification::AerobicRun, &metrics).unwrap();
+        metrics.is_loop = true;
+        let looped = TrackClassifier::explain(&TrackClassification::AerobicRun, &metrics).unwrap();
+
+        assert!((looped.confidence - point_to_point.confidence - 0.1).abs() < 1e-9);
+        assert!(looped.reasoning.ends_with("on a loop course"));
     }
 }
@@ -16,6 +16,48 @@ impl ElevationMetrics {
     }
 }
 
+/// Where a track's elevation values come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElevationSource {
+    Gps,        // GPS-derived altitude, noisy
+    Barometric, // Barometric altimeter, smooth
+    Enriched,   // Replaced with DEM data by elevation enrichment
+}
+
+/// Device makers whose watches and bike computers record barometric altitude
+const BAROMETRIC_CREATORS: [&str; 3] = ["garmin", "suunto", "polar"];
+
+impl ElevationSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ElevationSource::Gps => "gps",
+            ElevationSource::Barometric => "barometric",
+            ElevationSource::Enriched => "enriched",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "gps" => Some(ElevationSource::Gps),
+            "barometric" => Some(ElevationSource::Barometric),
+            "enriched" => Some(ElevationSource::Enriched),
+            _ => None,
+        }
+    }
+
+    /// Guess the source of recorded elevation from the file's creator string and
+    /// whether points carry temperature readings (`<gpxtpx:atemp>`), which come from
+    /// the same sensor package as a barometric altimeter
+    pub fn detect(creator: Option<&str>, has_temperature: bool) -> Self {
+        let creator = creator.unwrap_or_default().to_lowercase();
+        if has_temperature || BAROMETRIC_CREATORS.iter().any(|c| creator.contains(c)) {
+            ElevationSource::Barometric
+        } else {
+            ElevationSource::Gps
+        }
+    }
+}
+
 /// Calculate elevation metrics from a series of elevation points
 ///
 /// # Arguments
@@ -98,29 +140,56 @@ pub fn has_elevation_data(track_points: &[(f64, f64, Option<f64>)]) -> bool {
 }
 
 /// Smooth elevation data to reduce noise
-/// Uses a simple moving average filter
+/// Uses a Gaussian kernel over distance along the track, so dense and sparse recordings
+/// are smoothed over the same stretch of ground (like the slope smoothing in `slope.rs`)
 ///
 /// # Arguments
 /// * `elevations` - Raw elevation data
-/// * `window_size` - Size of smoothing window (default: 3)
+/// * `cumulative_distances` - Distance from the start for each point in meters, e.g. from
+///   `cumulative_distances_m`
+/// * `window_m` - Half width of the smoothing window in meters; the kernel's standard
+///   deviation is half of it and points farther away are ignored
 ///
 /// # Returns
-/// * `Vec<f64>` - Smoothed elevation data
-pub fn smooth_elevation_data(elevations: &[f64], window_size: usize) -> Vec<f64> {
-    if elevations.len() < window_size || window_size == 0 {
+/// * `Vec<f64>` - Smoothed elevation data; the input is returned unchanged when the
+///   distances do not line up with the elevations or the window is not positive
+pub fn smooth_elevation_data(
+    elevations: &[f64],
+    cumulative_distances: &[f64],
+    window_m: f64,
+) -> Vec<f64> {
+    if elevations.len() < 2
+        || cumulative_distances.len() != elevations.len()
+        || !(window_m.is_finite() && window_m > 0.0)
+    {
         return elevations.to_vec();
     }
 
-    let half_window = window_size / 2;
+    let sigma = window_m / 2.0;
     let mut smoothed = Vec::with_capacity(elevations.len());
+    let mut window_start = 0;
 
-    for i in 0..elevations.len() {
-        let start = i.saturating_sub(half_window);
-        let end = (i + half_window + 1).min(elevations.len());
+    for &center in cumulative_distances {
+        // Distances only grow along the track, so the window start only moves forward
+        while center - cumulative_distances[window_start] > window_m {
+            window_start += 1;
+        }
 
-        let sum: f64 = elevations[start..end].iter().sum();
-        let count = end - start;
-        smoothed.push(sum / count as f64);
+        let mut weighted_sum = 0.0;
+        let mut total_weight = 0.0;
+        for (&elevation, &distance) in elevations[window_start..]
+            .iter()
+            .zip(&cumulative_distances[window_start..])
+        {
+            let offset = distance - center;
+            if offset > window_m {
+                break;
+            }
+            let weight = (-0.5 * (offset / sigma).powi(2)).exp();
+            weighted_sum += elevation * weight;
+            total_weight += weight;
+        }
+        smoothed.push(weighted_sum / total_weight);
     }
 
     smoothed
@@ -207,19 +276,38 @@ mod tests {
     #[test]
     fn test_smooth_elevation_data() {
         let elevations = vec![100.0, 110.0, 90.0, 120.0, 80.0];
-        let smoothed = smooth_elevation_data(&elevations, 3);
-
-        // First point: (100 + 110) / 2 = 105
-        // Second point: (100 + 110 + 90) / 3 = 100
-        // Third point: (110 + 90 + 120) / 3 = 106.67
-        // Fourth point: (90 + 120 + 80) / 3 = 96.67
-        // Fifth point: (120 + 80) / 2 = 100
-
-        assert_approx_eq!(smoothed[0], 105.0, 1e-6);
-        assert_approx_eq!(smoothed[1], 100.0, 1e-6);
-        assert_approx_eq!(smoothed[2], 106.67, 0.01);
-        assert_approx_eq!(smoothed[3], 96.67, 0.01);
-        assert_approx_eq!(smoothed[4], 100.0, 1e-6);
+        let distances = vec![0.0, 10.0, 20.0, 30.0, 40.0];
+        let smoothed = smooth_elevation_data(&elevations, &distances, 10.0);
+
+        // Neighbours 10 m away are 2 sigma out: weight exp(-2) each
+        let w = (-2.0f64).exp();
+        assert_approx_eq!(smoothed[0], (100.0 + 110.0 * w) / (1.0 + w), 1e-9);
+        assert_approx_eq!(
+            smoothed[2],
+            (90.0 + (110.0 + 120.0) * w) / (1.0 + 2.0 * w),
+            1e-9
+        );
+        assert!(smoothed[3] < 120.0 && smoothed[4] > 80.0);
+    }
+
+    #[test]
+    fn test_smooth_elevation_data_uses_distance_not_point_count() {
+        let elevations: Vec<f64> = (0..50)
+            .map(|i| 100.0 + (i as f64 * 0.7).sin() * 10.0)
+            .collect();
+        let dense: Vec<f64> = (0..50).map(|i| i as f64 * 5.0).collect();
+        let sparse: Vec<f64> = (0..50).map(|i| i as f64 * 50.0).collect();
+
+        // Uniform spacing with the window scaled by the same factor gives identical output
+        let dense_smoothed = smooth_elevation_data(&elevations, &dense, 15.0);
+        let sparse_smoothed = smooth_elevation_data(&elevations, &sparse, 150.0);
+        for (d, s) in dense_smoothed.iter().zip(&sparse_smoothed) {
+            assert_approx_eq!(*d, *s, 1e-9);
+        }
+
+        // The same metric window covers fewer points on the sparse track
+        let sparse_same_window = smooth_elevation_data(&elevations, &sparse, 15.0);
+        assert_eq!(sparse_same_window, elevations);
     }
 
     #[test]
@@ -309,37 +397,33 @@ mod tests {
     fn test_smooth_elevation_data_edge_cases() {
         // Test with empty elevations
         let empty_elevations: Vec<f64> = vec![];
-        let smoothed = smooth_elevation_data(&empty_elevations, 3);
+        let smoothed = smooth_elevation_data(&empty_elevations, &[], 50.0);
         assert!(smoothed.is_empty());
 
         // Test with single elevation
         let single_elevation = vec![100.0];
-        let smoothed = smooth_elevation_data(&single_elevation, 3);
-        assert_eq!(smoothed.len(), 1);
-        assert_approx_eq!(smoothed[0], 100.0, 1e-6);
+        let smoothed = smooth_elevation_data(&single_elevation, &[0.0], 50.0);
+        assert_eq!(smoothed, single_elevation);
 
-        // Test with window size larger than data (should return original data)
+        // Test with distances that do not match the elevations (should return original data)
         let small_data = vec![100.0, 110.0];
-        let smoothed = smooth_elevation_data(&small_data, 5);
-        assert_eq!(smoothed.len(), 2);
-        // When window_size (5) > data.len() (2), function returns original data
-        assert_approx_eq!(smoothed[0], 100.0, 1e-6);
-        assert_approx_eq!(smoothed[1], 110.0, 1e-6);
+        let smoothed = smooth_elevation_data(&small_data, &[0.0], 50.0);
+        assert_eq!(smoothed, small_data);
 
-        // Test with window size 1 (each point averages just itself since half_window=0)
+        // Test with a window smaller than the point spacing (each point keeps its value)
         let original_data = vec![100.0, 110.0, 90.0];
-        let smoothed = smooth_elevation_data(&original_data, 1);
+        let smoothed = smooth_elevation_data(&original_data, &[0.0, 10.0, 20.0], 5.0);
+        assert_eq!(smoothed, original_data);
+        let smoothed = smooth_elevation_data(&original_data, &[0.0, 10.0, 20.0], 0.0);
         assert_eq!(smoothed, original_data);
 
-        // Test with negative elevations
-        let negative_data = vec![-100.0, -110.0, -90.0, -120.0];
-        let smoothed = smooth_elevation_data(&negative_data, 3);
-        assert_eq!(smoothed.len(), 4);
-        // With window=3, half_window=1
-        // Point 0: start=0, end=2 -> (-100 + -110) / 2 = -105.0
-        // Point 1: start=0, end=3 -> (-100 + -110 + -90) / 3 = -100.0
-        assert_approx_eq!(smoothed[0], -105.0, 1e-6);
-        assert_approx_eq!(smoothed[1], -100.0, 1e-6);
+        // Test with negative elevations (the middle point is pulled towards its neighbours)
+        let negative_data = vec![-100.0, -110.0, -90.0];
+        let smoothed = smooth_elevation_data(&negative_data, &[0.0, 10.0, 20.0], 20.0);
+        let w = (-0.5f64).exp();
+        assert_eq!(smoothed.len(), 3);
+        assert_approx_eq!(smoothed[1], -(110.0 + 190.0 * w) / (1.0 + 2.0 * w), 1e-9);
+        assert!(smoothed[0] < -100.0 && smoothed[2] > -100.0);
     }
 
     #[test]
@@ -392,4 +476,25 @@ mod tests {
         };
         assert_approx_eq!(zero_range.elevation_range().unwrap(), 0.0, 1e-6);
     }
+
+    #[test]
+    fn test_elevation_source_detection() {
+        assert_eq!(
+            ElevationSource::detect(Some("Garmin Connect"), false),
+            ElevationSource::Barometric
+        );
+        assert_eq!(
+            ElevationSource::detect(Some("StravaGPX iPhone"), true),
+            ElevationSource::Barometric
+        );
+        assert_eq!(
+            ElevationSource::detect(Some("StravaGPX iPhone"), false),
+            ElevationSource::Gps
+        );
+        assert_eq!(ElevationSource::detect(None, false), ElevationSource::Gps);
+        assert_eq!(
+            ElevationSource::from_label(ElevationSource::Enriched.as_str()),
+            Some(ElevationSource::Enriched)
+        );
+    }
 }
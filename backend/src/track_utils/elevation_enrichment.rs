@@ -46,6 +46,112 @@ struct Location {
     lng: f64,
 }
 
+/// Failure of an elevation API request. Callers can tell exhausted retries from bad responses
+/// by downcasting the `anyhow::Error` returned from enrichment.
+#[derive(Debug, thiserror::Error)]
+pub enum ElevationApiError {
+    #[error("elevation API request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("elevation API returned {status}: {body}")]
+    Status {
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+        body: String,
+    },
+    #[error("failed to parse elevation API response: {0}")]
+    Parse(String),
+    #[error("elevation API still failing after {attempts} attempts: {last}")]
+    RetriesExhausted {
+        attempts: u32,
+        last: Box<ElevationApiError>,
+    },
+}
+
+impl ElevationApiError {
+    /// Rate limiting, server errors and network failures are worth another attempt
+    fn is_retryable(&self) -> bool {
+        match self {
+            ElevationApiError::Transport(_) => true,
+            ElevationApiError::Status { status, .. } => {
+                *status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+
+    async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = response.text().await.unwrap_or_default();
+        ElevationApiError::Status {
+            status,
+            retry_after,
+            body,
+        }
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Retry policy for elevation API requests: exponential backoff with jitter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl RetryConfig {
+    fn from_env() -> Self {
+        let var = |key: &str, default: u64| {
+            std::env::var(key)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_attempts: var("ELEVATION_RETRY_ATTEMPTS", 3) as u32,
+            initial_delay_ms: var("ELEVATION_RETRY_INITIAL_DELAY_MS", 1000),
+            max_delay_ms: var("ELEVATION_RETRY_MAX_DELAY_MS", 30_000),
+        }
+    }
+
+    /// Delay before retry number `attempt` (1-based): doubles each time, plus up to 50% jitter,
+    /// capped at `max_delay_ms`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self
+            .initial_delay_ms
+            .saturating_mul(1u64 << (attempt - 1).min(32))
+            .min(self.max_delay_ms);
+        let jitter = base / 2 * u64::from(jitter_nanos()) / 1_000_000_000;
+        Duration::from_millis(base.saturating_add(jitter).min(self.max_delay_ms))
+    }
+}
+
+/// Cheap source of jitter (0..1e9); only needs to spread retries, not be random
+fn jitter_nanos() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0)
+}
+
 /// Enrichment result containing updated metrics and metadata
 #[derive(Debug)]
 pub struct EnrichmentResult {
@@ -64,14 +170,56 @@ pub struct ElevationEnrichmentService {
     max_points_per_request: usize,
     rate_limit_delay: Duration,
     daily_limit: u32,
+    /// Upper bound on API calls a single enrichment may use; longer tracks are downsampled
+    max_api_calls_per_track: u32,
     timeout: Duration,
-    retry_attempts: u32,
+    retry: RetryConfig,
     fallback_service: Option<String>,
     #[allow(dead_code)]
     fallback_url: Option<String>,
     pool: Option<Arc<PgPool>>, // Database connection for API usage tracking
 }
 
+fn max_api_calls_per_track_from_env() -> u32 {
+    std::env::var("MAX_ELEVATION_API_CALLS_PER_TRACK")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(500)
+}
+
+/// Evenly spaced indices (always including the first and last point) selecting `target` of `len` points
+fn budget_sample_indices(len: usize, target: usize) -> Vec<usize> {
+    if target >= len {
+        return (0..len).collect();
+    }
+    if target < 2 {
+        return vec![0, len - 1];
+    }
+    let step = (len - 1) as f64 / (target - 1) as f64;
+    let mut indices: Vec<usize> = (0..target)
+        .map(|k| ((k as f64 * step).round() as usize).min(len - 1))
+        .collect();
+    indices.dedup();
+    indices
+}
+
+/// Linearly interpolate elevations sampled at `indices` back onto all `len` track points
+fn expand_sampled_elevations(sampled: &[f64], indices: &[usize], len: usize) -> Vec<f64> {
+    let mut result = Vec::with_capacity(len);
+    for (w, pair) in indices.windows(2).enumerate() {
+        let (start, end) = (pair[0], pair[1]);
+        let (from, to) = (sampled[w], sampled[w + 1]);
+        for i in start..end {
+            let t = (i - start) as f64 / (end - start) as f64;
+            result.push(from + (to - from) * t);
+        }
+    }
+    if let (Some(&last_index), Some(&last)) = (indices.last(), sampled.last()) {
+        result.resize(result.len().max(last_index + 1), last);
+    }
+    result
+}
+
 impl Default for ElevationEnrichmentService {
     fn default() -> Self {
         Self::new()
@@ -121,16 +269,14 @@ impl ElevationEnrichmentService {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(1000),
+            max_api_calls_per_track: max_api_calls_per_track_from_env(),
             timeout: Duration::from_secs(
                 std::env::var("ELEVATION_TIMEOUT")
                     .ok()
                     .and_then(|s| s.parse().ok())
                     .unwrap_or(30),
             ),
-            retry_attempts: std::env::var("ELEVATION_RETRY_ATTEMPTS")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(3),
+            retry: RetryConfig::from_env(),
             fallback_service: std::env::var("ELEVATION_FALLBACK_SERVICE").ok(),
             fallback_url: None,
             pool: None,
@@ -158,16 +304,14 @@ impl ElevationEnrichmentService {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(1000),
+            max_api_calls_per_track: max_api_calls_per_track_from_env(),
             timeout: Duration::from_secs(
                 std::env::var("ELEVATION_TIMEOUT")
                     .ok()
                     .and_then(|s| s.parse().ok())
                     .unwrap_or(30),
             ),
-            retry_attempts: std::env::var("ELEVATION_RETRY_ATTEMPTS")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(3),
+            retry: RetryConfig::from_env(),
             fallback_service: None,
             fallback_url: None,
             pool: None,
@@ -183,14 +327,25 @@ impl ElevationEnrichmentService {
             max_points_per_request: 0,
             rate_limit_delay: Duration::from_secs(1),
             daily_limit: 0,
+            max_api_calls_per_track: 0,
             timeout: Duration::from_secs(30),
-            retry_attempts: 0,
+            retry: RetryConfig {
+                max_attempts: 0,
+                initial_delay_ms: 0,
+                max_delay_ms: 0,
+            },
             fallback_service: None,
             fallback_url: None,
             pool: None,
         }
     }
 
+    /// Override the retry policy read from the environment
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Set the database connection pool for API usage tracking
     pub fn with_pool(mut self, pool: Arc<PgPool>) -> Self {
         self.pool = Some(pool);
@@ -224,6 +379,18 @@ impl ElevationEnrichmentService {
     pub async fn enrich_track_elevation(
         &self,
         track_points: Vec<(f64, f64)>, // (lat, lon) pairs
+    ) -> Result<EnrichmentResult> {
+        self.enrich_track_elevation_with_progress(track_points, &|_, _| {})
+            .await
+    }
+
+    /// Like [`Self::enrich_track_elevation`], calling `on_progress(points_processed,
+    /// total_points)` after every API batch. Counts refer to track points, also when the
+    /// track is downsampled to fit the API budget.
+    pub async fn enrich_track_elevation_with_progress(
+        &self,
+        track_points: Vec<(f64, f64)>, // (lat, lon) pairs
+        on_progress: &(dyn Fn(usize, usize) + Send + Sync),
     ) -> Result<EnrichmentResult> {
         if track_points.is_empty() {
             return Err(anyhow!("Track has no points to enrich"));
@@ -240,6 +407,31 @@ impl ElevationEnrichmentService {
             self.dataset
         );
 
+        // Keep one long track from using up the daily budget: query a subset of points and
+        // interpolate the rest
+        let required_calls = track_points.len().div_ceil(self.max_points_per_request);
+        let sample_indices = if self.max_api_calls_per_track > 0
+            && required_calls > self.max_api_calls_per_track as usize
+        {
+            let budget_points = self.max_api_calls_per_track as usize * self.max_points_per_request;
+            let indices = budget_sample_indices(track_points.len(), budget_points);
+            tracing::warn!(
+                points = track_points.len(),
+                sampled_points = indices.len(),
+                required_calls,
+                max_calls = self.max_api_calls_per_track,
+                ratio = track_points.len() as f64 / indices.len() as f64,
+                "elevation enrichment exceeds per-track API budget, downsampling coordinates"
+            );
+            Some(indices)
+        } else {
+            None
+        };
+        let query_points: Vec<(f64, f64)> = match &sample_indices {
+            Some(indices) => indices.iter().map(|&i| track_points[i]).collect(),
+            None => track_points.clone(),
+        };
+
         let mut enriched_points = Vec::new();
         let mut total_api_calls = 0u32;
         // Track pending usage (persisted DB usage + in-job calls) to avoid exceeding daily limit
@@ -251,8 +443,14 @@ impl ElevationEnrichmentService {
             }
         }
 
+        let report_progress = |queried: usize| {
+            let processed =
+                (queried * track_points.len() / query_points.len()).min(track_points.len());
+            on_progress(processed, track_points.len());
+        };
+
         // Process points in chunks to respect API limits
-        for chunk in track_points.chunks(self.max_points_per_request) {
+        for chunk in query_points.chunks(self.max_points_per_request) {
             // Check daily API limit before making request - include in-flight usage of this job
             if current_usage + (total_api_calls as i32) + 1 >= self.daily_limit as i32 {
                 error!("Daily API limit exceeded for service {}", self.dataset);
@@ -262,9 +460,10 @@ impl ElevationEnrichmentService {
                 ));
             }
 
-            match self.fetch_elevations_batch_with_retry(chunk).await {
+            match self.with_retry(|| self.fetch_elevations_batch(chunk)).await {
                 Ok(elevations) => {
                     enriched_points.extend(elevations);
+                    report_progress(enriched_points.len());
 
                     // Record API usage in Prometheus metric incrementally and update in-job counter.
                     metrics::record_elevation_api_calls(&self.dataset, 1);
@@ -273,7 +472,7 @@ impl ElevationEnrichmentService {
                     // Rate limiting - wait between requests
                     if chunk.len() == self.max_points_per_request
                         && total_api_calls
-                            < (track_points.len() / self.max_points_per_request) as u32
+                            < (query_points.len() / self.max_points_per_request) as u32
                     {
                         sleep(self.rate_limit_delay).await;
                     }
@@ -284,19 +483,25 @@ impl ElevationEnrichmentService {
                     // Try fallback service if available
                     if let Some(fallback_result) = self.try_fallback_service(chunk).await {
                         enriched_points.extend(fallback_result);
+                        report_progress(enriched_points.len());
                         // Record API usage for fallback service too
                         metrics::record_elevation_api_calls(&self.dataset, 1);
                         total_api_calls += 1;
                         continue;
                     }
 
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         }
 
         // Extract elevations with NODATA handling and interpolation
-        let elevations = self.interpolate_missing_elevations(&enriched_points);
+        let mut elevations = self.interpolate_missing_elevations(&enriched_points);
+        if let Some(indices) = &sample_indices
+            && !elevations.is_empty()
+        {
+            elevations = expand_sampled_elevations(&elevations, indices, track_points.len());
+        }
         let nodata_count = enriched_points
             .iter()
             .filter(|p| p.elevation.is_none())
@@ -344,33 +549,45 @@ impl ElevationEnrichmentService {
         })
     }
 
-    /// Fetch elevation data with retry logic
-    async fn fetch_elevations_batch_with_retry(
-        &self,
-        points: &[(f64, f64)],
-    ) -> Result<Vec<ElevationPoint>> {
-        let mut last_error = None;
-
-        for attempt in 1..=self.retry_attempts {
-            match self.fetch_elevations_batch(points).await {
+    /// Run an API request, retrying transient failures (429, 5xx, network) with exponential
+    /// backoff. A `Retry-After` header takes precedence over the computed delay, still capped at
+    /// `max_delay_ms`. Non-transient errors are returned immediately.
+    async fn with_retry<T, F, Fut>(&self, mut request: F) -> Result<T, ElevationApiError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, ElevationApiError>>,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let error = match request().await {
                 Ok(result) => return Ok(result),
-                Err(e) => {
-                    last_error = Some(e);
-                    if attempt < self.retry_attempts {
-                        let delay = Duration::from_secs(2u64.pow(attempt - 1)); // Exponential backoff
-                        tracing::warn!(
-                            "Elevation API request failed (attempt {}/{}), retrying in {:?}",
-                            attempt,
-                            self.retry_attempts,
-                            delay
-                        );
-                        sleep(delay).await;
-                    }
-                }
+                Err(e) if !e.is_retryable() => return Err(e),
+                Err(e) => e,
+            };
+            if attempt >= max_attempts {
+                return Err(ElevationApiError::RetriesExhausted {
+                    attempts: attempt,
+                    last: Box::new(error),
+                });
             }
+            let delay = match &error {
+                ElevationApiError::Status {
+                    retry_after: Some(retry_after),
+                    ..
+                } => (*retry_after).min(Duration::from_millis(self.retry.max_delay_ms)),
+                _ => self.retry.backoff(attempt),
+            };
+            tracing::warn!(
+                "Elevation API request failed (attempt {}/{}): {}; retrying in {:?}",
+                attempt,
+                max_attempts,
+                error,
+                delay
+            );
+            sleep(delay).await;
+            attempt += 1;
         }
-
-        Err(last_error.unwrap_or_else(|| anyhow!("All retry attempts failed")))
     }
 
     /// Try fallback service if primary service fails
@@ -389,7 +606,10 @@ impl ElevationEnrichmentService {
                         return None;
                     }
 
-                    match self.fetch_open_elevation_batch(points).await {
+                    match self
+                        .with_retry(|| self.fetch_open_elevation_batch(points))
+                        .await
+                    {
                         Ok(result) => {
                             info!("Fallback service succeeded");
                             return Some(result);
@@ -409,7 +629,10 @@ impl ElevationEnrichmentService {
     }
 
     /// Fetch elevation data for a batch of points
-    async fn fetch_elevations_batch(&self, points: &[(f64, f64)]) -> Result<Vec<ElevationPoint>> {
+    async fn fetch_elevations_batch(
+        &self,
+        points: &[(f64, f64)],
+    ) -> Result<Vec<ElevationPoint>, ElevationApiError> {
         if points.is_empty() {
             return Ok(Vec::new());
         }
@@ -432,13 +655,7 @@ impl ElevationEnrichmentService {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "OpenTopoData API request failed with status {}: {}",
-                status,
-                text
-            ));
+            return Err(ElevationApiError::from_response(response).await);
         }
 
         let status = response.status();
@@ -451,22 +668,19 @@ impl ElevationEnrichmentService {
                     "Failed to parse OpenTopoData API response: {}. Status: {}, Body: {}",
                     e, status, response_text
                 );
-                return Err(anyhow!(
-                    "Failed to parse API response: {}. Status: {}, Body: {}",
-                    e,
-                    status,
-                    response_text
-                ));
+                return Err(ElevationApiError::Parse(format!(
+                    "{e}. Status: {status}, Body: {response_text}"
+                )));
             }
         };
 
         // Validate that we got the expected number of results
         if api_response.results.len() != points.len() {
-            return Err(anyhow!(
+            return Err(ElevationApiError::Parse(format!(
                 "API returned {} results but expected {}",
                 api_response.results.len(),
                 points.len()
-            ));
+            )));
         }
 
         Ok(api_response.results)
@@ -476,7 +690,7 @@ impl ElevationEnrichmentService {
     async fn fetch_open_elevation_batch(
         &self,
         points: &[(f64, f64)],
-    ) -> Result<Vec<ElevationPoint>> {
+    ) -> Result<Vec<ElevationPoint>, ElevationApiError> {
         if points.is_empty() {
             return Ok(Vec::new());
         }
@@ -505,13 +719,7 @@ impl ElevationEnrichmentService {
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "Open-Elevation API request failed with status {}: {}",
-                status,
-                text
-            ));
+            return Err(ElevationApiError::from_response(response).await);
         }
 
         let status = response.status();
@@ -524,12 +732,9 @@ impl ElevationEnrichmentService {
                     "Failed to parse Open-Elevation API response: {}. Status: {}, Body: {}",
                     e, status, response_text
                 );
-                return Err(anyhow!(
-                    "Failed to parse API response: {}. Status: {}, Body: {}",
-                    e,
-                    status,
-                    response_text
-                ));
+                return Err(ElevationApiError::Parse(format!(
+                    "{e}. Status: {status}, Body: {response_text}"
+                )));
             }
         };
 
@@ -764,6 +969,24 @@ mod tests {
         assert_eq!(result, Vec::<f64>::new());
     }
 
+    #[test]
+    fn budget_sampling_keeps_endpoints_and_restores_length() {
+        let indices = budget_sample_indices(1001, 11);
+        assert_eq!(indices.len(), 11);
+        assert_eq!(indices.first(), Some(&0));
+        assert_eq!(indices.last(), Some(&1000));
+
+        // Elevation rising 1 m per point is reconstructed exactly by linear interpolation
+        let sampled: Vec<f64> = indices.iter().map(|&i| i as f64).collect();
+        let expanded = expand_sampled_elevations(&sampled, &indices, 1001);
+        assert_eq!(expanded.len(), 1001);
+        assert_eq!(expanded[0], 0.0);
+        assert_eq!(expanded[550], 550.0);
+        assert_eq!(expanded[1000], 1000.0);
+
+        assert_eq!(budget_sample_indices(5, 10), vec![0, 1, 2, 3, 4]);
+    }
+
     #[test]
     fn test_needs_enrichment_edge_cases() {
         let service = ElevationEnrichmentService::new();
@@ -1053,6 +1276,113 @@ mod tests {
         });
     }
 
-    // Mock tests would go here using mockito or similar
-    // but require adding dev-dependencies to Cargo.toml
+    fn opentopodata_service(base_url: String) -> ElevationEnrichmentService {
+        let mut service = ElevationEnrichmentService::new_opentopodata();
+        service.base_url = base_url;
+        service.dataset = "srtm90m".to_string();
+        service.with_retry_config(RetryConfig {
+            max_attempts: 3,
+            initial_delay_ms: 1,
+            max_delay_ms: 10,
+        })
+    }
+
+    const ONE_POINT_BODY: &str = r#"{"results":[{"dataset":"srtm90m","elevation":120.5,"location":{"lat":45.0,"lng":7.0}}]}"#;
+
+    #[tokio::test]
+    async fn test_fetch_retries_rate_limited_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let rate_limited = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(ONE_POINT_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let service = opentopodata_service(server.url());
+        let points = [(45.0, 7.0)];
+        let result = service
+            .with_retry(|| service.fetch_elevations_batch(&points))
+            .await
+            .unwrap();
+
+        assert_eq!(result[0].elevation, Some(120.5));
+        rate_limited.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gives_up_after_max_attempts() {
+        let mut server = mockito::Server::new_async().await;
+        let failing = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let service = opentopodata_service(server.url());
+        let points = [(45.0, 7.0)];
+        let err = service
+            .with_retry(|| service.fetch_elevations_batch(&points))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ElevationApiError::RetriesExhausted { attempts: 3, .. }
+        ));
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_does_not_retry_parse_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let garbage = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("not json")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let service = opentopodata_service(server.url());
+        let points = [(45.0, 7.0)];
+        let err = service
+            .with_retry(|| service.fetch_elevations_batch(&points))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ElevationApiError::Parse(_)));
+        garbage.assert_async().await;
+    }
+
+    #[test]
+    fn test_retry_backoff_and_retry_after() {
+        let retry = RetryConfig {
+            max_attempts: 5,
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+        };
+        let first = retry.backoff(1);
+        assert!(first >= Duration::from_millis(100) && first <= Duration::from_millis(150));
+        let third = retry.backoff(3);
+        assert!(third >= Duration::from_millis(400) && third <= Duration::from_millis(600));
+        assert_eq!(retry.backoff(10), Duration::from_millis(1000));
+
+        assert_eq!(parse_retry_after("7"), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
 }
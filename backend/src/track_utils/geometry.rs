@@ -1,12 +1,21 @@
 // Geometry utilities for trackly
 // Contains functions for geospatial calculations and WKT parsing
 
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
 use serde_json::{Value, json};
 
 /// Maximum allowed gap between consecutive points before starting a new segment (meters)
 /// Keep generous to avoid over-splitting normal tracks; still cuts obvious teleports.
 const DEFAULT_MAX_GAP_METERS: f64 = 100_000.0; // 100 km
 
+/// Implied speed above which a point is treated as a GPS glitch (km/h). High enough to
+/// keep trains and cars, low enough to catch teleports; aircraft tracks are not supported.
+const DEFAULT_MAX_PLAUSIBLE_SPEED_KMH: f64 = 300.0;
+
+/// Start and end points closer than this make a track a loop (meters)
+const DEFAULT_LOOP_THRESHOLD_M: f64 = 100.0;
+
 /// Calculates the distance between two points (lat, lon) in meters using the haversine formula
 /// TODO: maybe switch to https://github.com/georust/geo?tab=readme-ov-file
 pub fn haversine_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
@@ -22,6 +31,134 @@ pub fn haversine_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
     r * c
 }
 
+/// Whether a track (lat, lon points) ends where it started, within `TRACK_LOOP_THRESHOLD_M`
+/// meters (100 by default). Tracks with fewer than three points are never loops.
+pub fn is_loop_track(points: &[(f64, f64)]) -> bool {
+    let threshold = std::env::var("TRACK_LOOP_THRESHOLD_M")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_LOOP_THRESHOLD_M);
+    match (points.first(), points.last()) {
+        (Some(&first), Some(&last)) if points.len() > 2 => {
+            haversine_distance(first, last) < threshold
+        }
+        _ => false,
+    }
+}
+
+// WGS84 ellipsoid parameters used by the Vincenty formula
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+const VINCENTY_MAX_ITERATIONS: usize = 200;
+const VINCENTY_CONVERGENCE: f64 = 1e-12;
+
+/// Calculates the ellipsoidal distance between two points (lat, lon) in meters using the
+/// Vincenty inverse formula on WGS84. More precise than haversine (which assumes a sphere)
+/// but several times slower. Falls back to haversine for nearly antipodal points where the
+/// iteration does not converge.
+pub fn vincenty_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = a;
+    let (lat2, lon2) = b;
+    let l = (lon2 - lon1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    let mut converged = false;
+    let (mut sin_sigma, mut cos_sigma, mut sigma) = (0.0, 0.0, 0.0);
+    let (mut cos_sq_alpha, mut cos_2sigma_m) = (0.0, 0.0);
+
+    for _ in 0..VINCENTY_MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+        .sqrt();
+        if sin_sigma == 0.0 {
+            return 0.0; // coincident points
+        }
+        cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+        cos_2sigma_m = if cos_sq_alpha != 0.0 {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+        } else {
+            0.0 // equatorial line
+        };
+        let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+        let lambda_prev = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m
+                            + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+        if (lambda - lambda_prev).abs() <= VINCENTY_CONVERGENCE {
+            converged = true;
+            break;
+        }
+    }
+
+    if !converged {
+        return haversine_distance(a, b);
+    }
+
+    let u_sq = cos_sq_alpha * (WGS84_A * WGS84_A - WGS84_B * WGS84_B) / (WGS84_B * WGS84_B);
+    let big_a = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+    let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+    let delta_sigma = big_b
+        * sin_sigma
+        * (cos_2sigma_m
+            + big_b / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                    - big_b / 6.0
+                        * cos_2sigma_m
+                        * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                        * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+    WGS84_B * big_a * (sigma - delta_sigma)
+}
+
+/// Formula used for track length calculations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceFormula {
+    Haversine,
+    Vincenty,
+}
+
+impl DistanceFormula {
+    /// Read `DISTANCE_FORMULA` (`haversine` | `vincenty`); haversine is the default
+    pub fn from_env() -> Self {
+        match std::env::var("DISTANCE_FORMULA")
+            .map(|v| v.trim().to_lowercase())
+            .as_deref()
+        {
+            Ok("vincenty") => DistanceFormula::Vincenty,
+            _ => DistanceFormula::Haversine,
+        }
+    }
+}
+
+static DISTANCE_FORMULA: Lazy<DistanceFormula> = Lazy::new(DistanceFormula::from_env);
+
+/// Configured distance formula (read once from the environment)
+pub fn distance_formula() -> DistanceFormula {
+    *DISTANCE_FORMULA
+}
+
+/// Distance in meters between two points (lat, lon) using the configured formula
+pub fn point_distance_m(a: (f64, f64), b: (f64, f64)) -> f64 {
+    match distance_formula() {
+        DistanceFormula::Haversine => haversine_distance(a, b),
+        DistanceFormula::Vincenty => vincenty_distance(a, b),
+    }
+}
+
 /// Parses WKT string LINESTRING to vector (lat, lon)
 pub fn parse_linestring_wkt(wkt: &str) -> Option<Vec<(f64, f64)>> {
     let wkt = wkt.trim();
@@ -44,7 +181,8 @@ pub fn parse_linestring_wkt(wkt: &str) -> Option<Vec<(f64, f64)>> {
         .collect::<Option<Vec<_>>>()
 }
 
-/// Extract coordinates from GeoJSON LineString
+/// Extract (lat, lon) coordinates from a LineString, or from a MultiLineString
+/// with its segments flattened in order
 pub fn extract_coordinates_from_geojson(geom_geojson: &Value) -> Result<Vec<(f64, f64)>, String> {
     match geom_geojson.get("type").and_then(|t| t.as_str()) {
         Some("LineString") => extract_linestring_coords(geom_geojson),
@@ -69,6 +207,7 @@ pub fn extract_segments_from_geojson(geom_geojson: &Value) -> Result<Vec<Vec<(f6
 }
 
 /// Split a sequence of points into segments whenever the gap exceeds `max_gap_meters`.
+/// Without an explicit threshold, `TRACK_MAX_GAP_METERS` or 100 km is used.
 /// Points are (lat, lon).
 pub fn split_points_by_gap(
     points: &[(f64, f64)],
@@ -78,7 +217,13 @@ pub fn split_points_by_gap(
         return Vec::new();
     }
 
-    let threshold = max_gap_meters.unwrap_or(DEFAULT_MAX_GAP_METERS);
+    let threshold = max_gap_meters
+        .or_else(|| {
+            std::env::var("TRACK_MAX_GAP_METERS")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+        })
+        .unwrap_or(DEFAULT_MAX_GAP_METERS);
     let mut segments: Vec<Vec<(f64, f64)>> = Vec::new();
     let mut current: Vec<(f64, f64)> = vec![points[0]];
 
@@ -110,17 +255,102 @@ pub fn split_points_by_gap(
     segments
 }
 
+/// Maximum plausible speed between consecutive points, from `MAX_PLAUSIBLE_SPEED_KMH`
+pub fn max_plausible_speed_kmh() -> f64 {
+    std::env::var("MAX_PLAUSIBLE_SPEED_KMH")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .unwrap_or(DEFAULT_MAX_PLAUSIBLE_SPEED_KMH)
+}
+
+/// Which points to keep after dropping single-point teleports: a point is dropped when
+/// reaching it and leaving it both imply more than `max_speed_kmh`, while its neighbours
+/// are consistent with each other. Points without timestamps are always kept, and nothing
+/// is dropped when `times` does not match `points`.
+pub fn teleport_point_mask(
+    points: &[(f64, f64)],
+    times: &[Option<DateTime<Utc>>],
+    max_speed_kmh: f64,
+) -> Vec<bool> {
+    let n = points.len();
+    let mut keep = vec![true; n];
+    if n < 3 || times.len() != n {
+        return keep;
+    }
+
+    let too_fast = |a: usize, b: usize| {
+        let (Some(ta), Some(tb)) = (times[a], times[b]) else {
+            return false;
+        };
+        let hours = (tb - ta).num_milliseconds() as f64 / 3_600_000.0;
+        hours > 0.0 && haversine_distance(points[a], points[b]) / 1000.0 / hours > max_speed_kmh
+    };
+
+    // Last two kept points, so a dropped spike is never used as a reference
+    let mut prev: Option<usize> = None;
+    let mut prev_prev: Option<usize> = None;
+    for (i, kept) in keep.iter_mut().enumerate() {
+        let next = (i + 1 < n).then_some(i + 1);
+        let is_spike = match (prev, next) {
+            (Some(p), Some(nx)) => too_fast(p, i) && too_fast(i, nx) && !too_fast(p, nx),
+            (None, Some(nx)) => nx + 1 < n && too_fast(i, nx) && !too_fast(nx, nx + 1),
+            (Some(p), None) => too_fast(p, i) && prev_prev.is_some_and(|pp| !too_fast(pp, p)),
+            (None, None) => false,
+        };
+        if is_spike {
+            *kept = false;
+        } else {
+            prev_prev = prev;
+            prev = Some(i);
+        }
+    }
+    keep
+}
+
+/// Drop single-point GPS teleports, see [`teleport_point_mask`]
+pub fn remove_teleport_points(
+    points: &[(f64, f64)],
+    times: &[Option<DateTime<Utc>>],
+    max_speed_kmh: f64,
+) -> Vec<(f64, f64)> {
+    let keep = teleport_point_mask(points, times, max_speed_kmh);
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&p, k)| k.then_some(p))
+        .collect()
+}
+
 /// Calculate total length in km for multiple segments (lat, lon) skipping jumps.
 pub fn length_km_for_segments(segments: &[Vec<(f64, f64)>]) -> f64 {
     let mut length_m = 0.0;
     for segment in segments {
         for w in segment.windows(2) {
-            length_m += haversine_distance(w[0], w[1]);
+            length_m += point_distance_m(w[0], w[1]);
         }
     }
     length_m / 1000.0
 }
 
+/// Cumulative distance in meters at each point across all segments (lat, lon).
+/// Jumps between segments are not counted, consistent with `length_km_for_segments`.
+pub fn cumulative_distances_m(segments: &[Vec<(f64, f64)>]) -> Vec<f64> {
+    let mut distances = Vec::with_capacity(segments.iter().map(|s| s.len()).sum());
+    let mut total = 0.0;
+    for segment in segments {
+        let mut prev: Option<(f64, f64)> = None;
+        for &point in segment {
+            if let Some(p) = prev {
+                total += point_distance_m(p, point);
+            }
+            distances.push(total);
+            prev = Some(point);
+        }
+    }
+    distances
+}
+
 /// Build GeoJSON from segments. Single segment => LineString, otherwise MultiLineString.
 pub fn geojson_from_segments(segments: &[Vec<(f64, f64)>]) -> Value {
     if segments.len() <= 1 {
@@ -210,6 +440,19 @@ mod tests {
     use assert_approx_eq::assert_approx_eq;
     use serde_json::json;
 
+    #[test]
+    fn split_points_by_gap_prefers_explicit_threshold() {
+        // Points ~11 km apart
+        let points = [(55.0, 37.0), (55.1, 37.0)];
+        crate::test_utils::with_temp_env("TRACK_MAX_GAP_METERS", Some("5000"), || {
+            assert_eq!(split_points_by_gap(&points, None).len(), 2);
+            assert_eq!(split_points_by_gap(&points, Some(20_000.0)).len(), 1);
+        });
+        crate::test_utils::with_temp_env("TRACK_MAX_GAP_METERS", None::<&str>, || {
+            assert_eq!(split_points_by_gap(&points, None).len(), 1);
+        });
+    }
+
     #[test]
     fn test_haversine_distance_zero() {
         let a = (55.0, 37.0);
@@ -225,6 +468,70 @@ mod tests {
         assert!((d - 11119.5).abs() < 100.0); // ~11.1km
     }
 
+    #[test]
+    fn test_vincenty_distance_known() {
+        // Flinders Peak -> Buninyong, the classic reference from Vincenty's paper
+        let a = (-37.951_033_42, 144.424_867_89);
+        let b = (-37.652_821_14, 143.926_495_54);
+        assert_approx_eq!(vincenty_distance(a, b), 54_972.271, 1e-3);
+        assert_approx_eq!(vincenty_distance(a, a), 0.0, 1e-9);
+    }
+
+    #[test]
+    fn test_vincenty_nearly_antipodal_points_stay_finite() {
+        let d = vincenty_distance((0.0, 0.0), (0.5, 179.7));
+        assert!(d.is_finite());
+        assert!(d > 19_000_000.0);
+    }
+
+    #[test]
+    fn test_is_loop_track_uses_threshold() {
+        // ~55 m between start and end
+        let loop_points = vec![
+            (55.0, 37.0),
+            (55.01, 37.01),
+            (55.0, 37.0005),
+            (55.0005, 37.0),
+        ];
+        let one_way = vec![(55.0, 37.0), (55.01, 37.01), (55.02, 37.02)];
+        temp_env::with_var("TRACK_LOOP_THRESHOLD_M", None::<&str>, || {
+            assert!(is_loop_track(&loop_points));
+            assert!(!is_loop_track(&one_way));
+            assert!(!is_loop_track(&[(55.0, 37.0)]));
+        });
+        temp_env::with_var("TRACK_LOOP_THRESHOLD_M", Some("30"), || {
+            assert!(!is_loop_track(&loop_points));
+        });
+    }
+
+    #[test]
+    fn test_distance_formula_from_env() {
+        temp_env::with_var("DISTANCE_FORMULA", Some("Vincenty"), || {
+            assert_eq!(DistanceFormula::from_env(), DistanceFormula::Vincenty);
+        });
+        temp_env::with_var("DISTANCE_FORMULA", None::<&str>, || {
+            assert_eq!(DistanceFormula::from_env(), DistanceFormula::Haversine);
+        });
+    }
+
+    #[test]
+    fn test_cumulative_distances_skip_segment_jumps() {
+        let segments = vec![
+            vec![(0.0, 0.0), (0.0, 0.001)],
+            vec![(10.0, 10.0), (10.0, 10.001)],
+        ];
+        let distances = cumulative_distances_m(&segments);
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[0], 0.0);
+        // Second segment starts where the first ended, without the teleport jump
+        assert_approx_eq!(distances[2], distances[1], 1e-9);
+        assert_approx_eq!(
+            distances[3],
+            length_km_for_segments(&segments) * 1000.0,
+            1e-6
+        );
+    }
+
     #[test]
     fn test_parse_linestring_wkt() {
         let wkt = "LINESTRING(37.0 55.0, 38.0 56.0)";
@@ -258,4 +565,66 @@ mod tests {
         let result = extract_coordinates_from_geojson(&empty_geojson).unwrap();
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_extract_coordinates_from_geojson_multilinestring_flattens_in_order() {
+        let geojson = json!({
+            "type": "MultiLineString",
+            "coordinates": [
+                [[37.0, 55.0], [37.1, 55.1]],
+                [],
+                [[38.0, 56.0]]
+            ]
+        });
+        let result = extract_coordinates_from_geojson(&geojson).unwrap();
+        assert_eq!(result, vec![(55.0, 37.0), (55.1, 37.1), (56.0, 38.0)]);
+    }
+
+    #[test]
+    fn test_extract_coordinates_from_geojson_multilinestring_invalid() {
+        let geojson = json!({
+            "type": "MultiLineString",
+            "coordinates": [[[37.0]]]
+        });
+        assert!(extract_coordinates_from_geojson(&geojson).is_err());
+        assert!(extract_coordinates_from_geojson(&json!({"type": "Point"})).is_err());
+    }
+
+    type TimedPoints = (Vec<(f64, f64)>, Vec<Option<DateTime<Utc>>>);
+
+    fn timed_walk(len: usize) -> TimedPoints {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let points = (0..len).map(|i| (55.0 + i as f64 * 0.0001, 37.0)).collect();
+        let times = (0..len)
+            .map(|i| Some(start + chrono::Duration::seconds(i as i64 * 5)))
+            .collect();
+        (points, times)
+    }
+
+    #[test]
+    fn removes_single_point_teleports() {
+        let (mut points, times) = timed_walk(6);
+        points[0] = (10.0, 10.0);
+        points[3] = (-33.9, 151.2);
+        points[5] = (40.7, -74.0);
+
+        let kept = remove_teleport_points(&points, &times, 300.0);
+        assert_eq!(kept, vec![points[1], points[2], points[4]]);
+    }
+
+    #[test]
+    fn keeps_points_without_timestamps_or_plausible_speed() {
+        let (mut points, mut times) = timed_walk(5);
+        assert_eq!(remove_teleport_points(&points, &times, 300.0), points);
+
+        points[2] = (10.0, 10.0);
+        times[2] = None;
+        assert_eq!(remove_teleport_points(&points, &times, 300.0), points);
+        assert_eq!(
+            teleport_point_mask(&points, &times[..4], 300.0),
+            vec![true; 5]
+        );
+    }
 }
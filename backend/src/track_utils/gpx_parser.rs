@@ -3,27 +3,37 @@
 
 use crate::models::ParsedTrackData;
 use crate::track_utils::elevation::{
-    calculate_elevation_metrics, extract_elevations_from_track_points, has_elevation_data,
+    ElevationSource, calculate_elevation_metrics, extract_elevations_from_track_points,
+    has_elevation_data,
 };
 use crate::track_utils::geometry::{
-    geojson_from_segments, haversine_distance, length_km_for_segments, split_points_by_gap,
+    geojson_from_segments, haversine_distance, is_loop_track, length_km_for_segments,
+    max_plausible_speed_kmh, split_points_by_gap, teleport_point_mask,
 };
-use crate::track_utils::time_utils::parse_gpx_time;
+use crate::track_utils::hash::calculate_file_hash;
+use crate::track_utils::metrics::calculate_normalized_power;
+use crate::track_utils::time_utils::{
+    PAUSE_GAP_THRESHOLD_SECS, calculate_moving_distance, parse_gpx_time,
+};
+use crate::track_utils::{TrackParseError, parse_point_coordinates};
 use quick_xml::Reader;
-use quick_xml::events::Event;
-use sha2::{Digest, Sha256};
+use quick_xml::events::{BytesStart, Event};
 use tracing::{debug, info};
 
 /// Parses GPX file, returns ParsedTrackData
-pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
+pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, TrackParseError> {
     let mut reader = Reader::from_reader(bytes);
     reader.config_mut().trim_text(true);
     let mut buf = Vec::new();
 
     let mut points = Vec::new();
+    // Indices into `points` where each <trkseg> starts
+    let mut trkseg_starts: Vec<usize> = Vec::new();
     let mut elevation_profile_data = Vec::new();
     let mut hr_data_points = Vec::new();
     let mut temp_data_points = Vec::new();
+    let mut cadence_data_points = Vec::new();
+    let mut power_data_points = Vec::new();
     let mut time_points = Vec::new(); // Add time points collection
     let mut total_elevation_gain = 0.0;
     let mut total_elevation_loss = 0.0;
@@ -47,8 +57,14 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     let mut ele: Option<f64> = None;
     let mut hr: Option<i32> = None;
     let mut temp: Option<f64> = None;
+    let mut cad: Option<i32> = None;
+    let mut power: Option<i32> = None;
     let mut point_time: Option<String> = None; // Time for current point
     let mut recorded_at: Option<String> = None;
+    let mut creator: Option<String> = None;
+    let mut metadata_author: Option<String> = None;
+    let mut metadata_link: Option<String> = None;
+    let mut metadata_copyright: Option<String> = None;
     let mut element_stack: Vec<String> = Vec::new();
     let mut capture_text = false;
     let mut text_target: Option<String> = None;
@@ -57,6 +73,8 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     let mut rte_elevation_profile_data = Vec::new();
     let mut rte_hr_data_points = Vec::new();
     let mut rte_temp_data_points = Vec::new();
+    let mut rte_cadence_data_points = Vec::new();
+    let mut rte_power_data_points = Vec::new();
     let mut rte_time_points = Vec::new(); // Add route time points collection
     let mut rte_total_elevation_gain = 0.0;
     let mut rte_total_elevation_loss = 0.0;
@@ -68,52 +86,51 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                 let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
                 let tag_stripped = tag.split(':').next_back().unwrap_or(&tag);
                 element_stack.push(tag_stripped.to_string());
+                // Power is only read from leaf elements; one with children is a container
+                if text_target.as_deref() == Some("power") {
+                    capture_text = false;
+                    text_target = None;
+                }
                 match tag_stripped {
+                    "gpx" => {
+                        creator = e
+                            .try_get_attribute("creator")
+                            .ok()
+                            .flatten()
+                            .and_then(|a| a.unescape_value().ok())
+                            .map(|v| v.to_string());
+                    }
                     "metadata" => {}
+                    "link" | "copyright" => {
+                        let parent = element_stack.iter().rev().nth(1).map(String::as_str);
+                        read_metadata_attribute(
+                            tag_stripped,
+                            e,
+                            parent,
+                            &mut metadata_link,
+                            &mut metadata_copyright,
+                        );
+                    }
+                    "trkseg" => {
+                        trkseg_starts.push(points.len());
+                    }
                     "trkpt" => {
                         in_trkpt = true;
-                        lat = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lat" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
-                        lon = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lon" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                        let (point_lat, point_lon) = parse_point_coordinates(e)?;
+                        lat = Some(point_lat);
+                        lon = Some(point_lon);
                         ele = None;
                         hr = None;
                         temp = None;
+                        cad = None;
+                        power = None;
                     }
                     "wpt" => {
                         in_wpt = true;
-                        lat = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lat" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
-                        lon = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lon" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                        // Waypoints are optional extras, so a malformed one is skipped
+                        let coordinates = parse_point_coordinates(e).ok();
+                        lat = coordinates.map(|(lat, _)| lat);
+                        lon = coordinates.map(|(_, lon)| lon);
                         ele = None;
                         wpt_name = None;
                         wpt_desc = None;
@@ -122,79 +139,88 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                     }
                     "rtept" => {
                         in_rtept = true;
-                        lat = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lat" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
-                        lon = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lon" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                        let (point_lat, point_lon) = parse_point_coordinates(e)?;
+                        lat = Some(point_lat);
+                        lon = Some(point_lon);
                         ele = None;
                         hr = None;
                         temp = None;
+                        cad = None;
+                        power = None;
                     }
                     "name" => {
                         if in_wpt {
                             capture_text = true;
                             text_target = Some("wpt_name".to_string());
-                        }
-                    }
-                    "desc" => {
-                        if in_wpt {
+                        } else if element_stack
+                            .iter()
+                            .rev()
+                            .take(3)
+                            .map(String::as_str)
+                            .eq(["name", "author", "metadata"])
+                        {
                             capture_text = true;
-                            text_target = Some("wpt_desc".to_string());
+                            text_target = Some("metadata_author".to_string());
                         }
                     }
-                    "type" => {
-                        if in_wpt {
-                            capture_text = true;
-                            text_target = Some("wpt_type".to_string());
-                        }
+                    "desc" if in_wpt => {
+                        capture_text = true;
+                        text_target = Some("wpt_desc".to_string());
                     }
-                    "sym" => {
-                        if in_wpt {
-                            capture_text = true;
-                            text_target = Some("wpt_sym".to_string());
-                        }
+                    "type" if in_wpt => {
+                        capture_text = true;
+                        text_target = Some("wpt_type".to_string());
                     }
-                    "ele" => {
-                        if in_trkpt || in_rtept || in_wpt {
-                            capture_text = true;
-                            text_target = Some("ele".to_string());
-                        }
+                    "sym" if in_wpt => {
+                        capture_text = true;
+                        text_target = Some("wpt_sym".to_string());
                     }
-                    "extensions" => {
-                        if in_trkpt || in_rtept {
-                            in_extensions = true;
-                        }
+                    "ele" if in_trkpt || in_rtept || in_wpt => {
+                        capture_text = true;
+                        text_target = Some("ele".to_string());
                     }
-                    "TrackPointExtension" => {
-                        if in_extensions {
-                            in_trackpoint_extension = true;
-                        }
+                    "extensions" if in_trkpt || in_rtept => {
+                        in_extensions = true;
                     }
-                    "hr" | "heartrate" => {
-                        if (!in_extensions || in_trackpoint_extension) && (in_rtept || in_trkpt) {
-                            capture_text = true;
-                            text_target = Some("hr".to_string());
-                        }
+                    "TrackPointExtension" if in_extensions => {
+                        in_trackpoint_extension = true;
                     }
-                    "atemp" | "temp" | "temperature" => {
-                        if (!in_extensions || in_trackpoint_extension) && (in_rtept || in_trkpt) {
-                            capture_text = true;
-                            text_target = Some("temp".to_string());
-                        }
+                    "hr" | "heartrate"
+                        if (!in_extensions || in_trackpoint_extension)
+                            && (in_rtept || in_trkpt) =>
+                    {
+                        capture_text = true;
+                        text_target = Some("hr".to_string());
+                    }
+                    "atemp" | "temp" | "temperature"
+                        if (!in_extensions || in_trackpoint_extension)
+                            && (in_rtept || in_trkpt) =>
+                    {
+                        capture_text = true;
+                        text_target = Some("temp".to_string());
+                    }
+                    "cad" | "cadence"
+                        if (!in_extensions || in_trackpoint_extension)
+                            && (in_rtept || in_trkpt) =>
+                    {
+                        capture_text = true;
+                        text_target = Some("cad".to_string());
+                    }
+                    // Power meter readings: <power> directly under <extensions> (Strava,
+                    // Wahoo) or inside a TrackPointExtension, and Garmin's <pwr:PowerInWatts>
+                    "power" | "PowerInWatts" if in_extensions && (in_rtept || in_trkpt) => {
+                        capture_text = true;
+                        text_target = Some("power".to_string());
+                    }
+                    // Other vendors' power elements; a known element above still wins
+                    other
+                        if in_extensions
+                            && (in_rtept || in_trkpt)
+                            && power.is_none()
+                            && is_power_reading_name(other) =>
+                    {
+                        capture_text = true;
+                        text_target = Some("power".to_string());
                     }
                     "time" => {
                         // If inside <metadata>, prefer this as recorded_at
@@ -218,67 +244,110 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                     _ => {}
                 }
             }
-            Ok(Event::Text(e)) => {
-                if capture_text {
-                    if let Some(target) = &text_target {
-                        match target.as_str() {
-                            "ele" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                ele = text.parse::<f64>().ok();
-                            }
-                            "hr" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                hr = text.parse::<i32>().ok();
-                            }
-                            "temp" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                temp = text.parse::<f64>().ok();
-                            }
-                            "metadata_time" => {
-                                if !found_metadata_time {
-                                    let text = std::str::from_utf8(&e).unwrap_or_default();
-                                    recorded_at = Some(text.to_string());
-                                    found_metadata_time = true;
-                                }
-                            }
-                            "point_time" => {
-                                // Capture time for individual points
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                point_time = Some(text.to_string());
-                            }
-                            "trkpt_time" => {
-                                // Use as both point time and fallback recorded_at
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                let time_str = text.to_string();
-                                point_time = Some(time_str.clone());
-                                if recorded_at.is_none() && !found_metadata_time {
-                                    recorded_at = Some(time_str);
-                                }
-                            }
-                            "wpt_name" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                wpt_name = Some(text.to_string());
-                            }
-                            "wpt_desc" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                wpt_desc = Some(text.to_string());
-                            }
-                            "wpt_type" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                wpt_type = Some(text.to_string());
+            Ok(Event::Text(e)) if capture_text => {
+                if let Some(target) = &text_target {
+                    match target.as_str() {
+                        "ele" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            ele = text.parse::<f64>().ok();
+                        }
+                        "hr" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            hr = text.parse::<i32>().ok();
+                        }
+                        "temp" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            temp = text.parse::<f64>().ok();
+                        }
+                        "cad" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            cad = text.parse::<i32>().ok();
+                        }
+                        "power" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            power = text
+                                .trim()
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|w| w.is_finite() && *w >= 0.0)
+                                .map(|w| w.round() as i32);
+                        }
+                        "metadata_time" if !found_metadata_time => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            recorded_at = Some(text.to_string());
+                            found_metadata_time = true;
+                        }
+                        "point_time" => {
+                            // Capture time for individual points
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            point_time = Some(text.to_string());
+                        }
+                        "trkpt_time" => {
+                            // Use as both point time and fallback recorded_at
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            let time_str = text.to_string();
+                            point_time = Some(time_str.clone());
+                            if recorded_at.is_none() && !found_metadata_time {
+                                recorded_at = Some(time_str);
                             }
-                            "wpt_sym" => {
-                                let text = std::str::from_utf8(&e).unwrap_or_default();
-                                wpt_sym = Some(text.to_string());
+                        }
+                        "metadata_author" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default().trim();
+                            if metadata_author.is_none() && !text.is_empty() {
+                                metadata_author = Some(ammonia::clean(text));
                             }
-                            _ => {}
                         }
+                        // Waypoint text may be split around entity references, so it is
+                        // collected until the element ends and sanitized as a whole
+                        "wpt_name" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            wpt_name.get_or_insert_default().push_str(text);
+                        }
+                        "wpt_desc" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            wpt_desc.get_or_insert_default().push_str(text);
+                        }
+                        "wpt_type" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            wpt_type = Some(text.to_string());
+                        }
+                        "wpt_sym" => {
+                            let text = std::str::from_utf8(&e).unwrap_or_default();
+                            wpt_sym = Some(text.to_string());
+                        }
+                        _ => {}
                     }
+                }
+                if !matches!(text_target.as_deref(), Some("wpt_name" | "wpt_desc")) {
                     capture_text = false;
                     text_target = None;
                 }
             }
+            Ok(Event::GeneralRef(e))
+                if capture_text
+                    && matches!(text_target.as_deref(), Some("wpt_name" | "wpt_desc")) =>
+            {
+                let resolved = match e.resolve_char_ref() {
+                    Ok(Some(c)) => Some(c.to_string()),
+                    _ => std::str::from_utf8(&e)
+                        .ok()
+                        .and_then(quick_xml::escape::resolve_predefined_entity)
+                        .map(str::to_string),
+                };
+                if let Some(resolved) = resolved {
+                    let target = if text_target.as_deref() == Some("wpt_name") {
+                        &mut wpt_name
+                    } else {
+                        &mut wpt_desc
+                    };
+                    target.get_or_insert_default().push_str(&resolved);
+                }
+            }
             Ok(Event::End(ref e)) => {
+                if matches!(text_target.as_deref(), Some("wpt_name" | "wpt_desc")) {
+                    capture_text = false;
+                    text_target = None;
+                }
                 let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
                 let tag_stripped = tag.split(':').next_back().unwrap_or(&tag);
                 if let Some(last) = element_stack.pop() {
@@ -294,6 +363,8 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                             elevation_profile_data.push(ele);
                             hr_data_points.push(hr);
                             temp_data_points.push(temp);
+                            cadence_data_points.push(cad);
+                            power_data_points.push(power);
                             // Parse and add point time
                             let parsed_time = point_time.as_ref().and_then(|t| parse_gpx_time(t));
                             time_points.push(parsed_time);
@@ -313,6 +384,8 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                         ele = None;
                         hr = None;
                         temp = None;
+                        cad = None;
+                        power = None;
                         point_time = None; // Reset point time
                         in_extensions = false;
                         in_trackpoint_extension = false;
@@ -323,6 +396,8 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                             rte_elevation_profile_data.push(ele);
                             rte_hr_data_points.push(hr);
                             rte_temp_data_points.push(temp);
+                            rte_cadence_data_points.push(cad);
+                            rte_power_data_points.push(power);
                             // Parse and add route point time
                             let parsed_time = point_time.as_ref().and_then(|t| parse_gpx_time(t));
                             rte_time_points.push(parsed_time);
@@ -342,18 +417,24 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                         ele = None;
                         hr = None;
                         temp = None;
+                        cad = None;
+                        power = None;
                         point_time = None; // Reset point time
                         in_extensions = false;
                         in_trackpoint_extension = false;
                     }
                     "wpt" => {
+                        // Waypoint text is user-controlled and ends up in the pois table
+                        let name = wpt_name.as_deref().map(ammonia::clean);
                         // Store waypoint if we have required data
-                        if let (Some(lat), Some(lon), Some(name)) = (lat, lon, wpt_name.clone()) {
+                        if let (Some(lat), Some(lon), Some(name)) = (lat, lon, name)
+                            && !name.trim().is_empty()
+                        {
                             use crate::models::ParsedWaypoint;
 
                             waypoints.push(ParsedWaypoint {
                                 name: name.trim().to_string(),
-                                description: wpt_desc.clone(),
+                                description: wpt_desc.as_deref().map(ammonia::clean),
                                 category: wpt_type.clone().or(wpt_sym.clone()),
                                 lat,
                                 lon,
@@ -379,8 +460,20 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                     _ => {}
                 }
             }
+            Ok(Event::Empty(ref e)) => {
+                // Self-closing <link href="..."/> and <copyright author="..."/>
+                let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                let tag_stripped = tag.split(':').next_back().unwrap_or(&tag);
+                read_metadata_attribute(
+                    tag_stripped,
+                    e,
+                    element_stack.last().map(String::as_str),
+                    &mut metadata_link,
+                    &mut metadata_copyright,
+                );
+            }
             Ok(Event::Eof) => break,
-            Err(e) => return Err(format!("Error parsing GPX: {e}")),
+            Err(e) => return Err(e.into()),
             _ => {}
         }
         buf.clear();
@@ -391,20 +484,25 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     // println!("GPX parse counts: trkpt={}, rtept={}", points.len(), rte_points.len());
 
     // If no track points, but route points exist, use them
+    let use_route_points = points.is_empty() && !rte_points.is_empty();
     let (
         points,
         elevation_profile_data,
         hr_data_points,
         temp_data_points,
+        cadence_data_points,
+        power_data_points,
         time_points,
         total_elevation_gain,
         total_elevation_loss,
-    ) = if points.is_empty() && !rte_points.is_empty() {
+    ) = if use_route_points {
         (
             rte_points,
             rte_elevation_profile_data,
             rte_hr_data_points,
             rte_temp_data_points,
+            rte_cadence_data_points,
+            rte_power_data_points,
             rte_time_points,
             rte_total_elevation_gain,
             rte_total_elevation_loss,
@@ -415,6 +513,8 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
             elevation_profile_data,
             hr_data_points,
             temp_data_points,
+            cadence_data_points,
+            power_data_points,
             time_points,
             total_elevation_gain,
             total_elevation_loss,
@@ -422,21 +522,76 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     };
 
     if points.is_empty() {
-        return Err("No points in GPX".to_string());
+        return Err(TrackParseError::EmptyTrack);
     }
 
-    let max_gap_meters = std::env::var("TRACK_MAX_GAP_METERS")
-        .ok()
-        .and_then(|v| v.parse::<f64>().ok());
-    let segments = split_points_by_gap(&points, max_gap_meters);
+    // Keep <trkseg> boundaries as separate segments, then split teleport gaps inside each
+    let trkseg_starts = if use_route_points {
+        Vec::new()
+    } else {
+        trkseg_starts
+    };
+
+    // Drop single-point GPS teleports so they are not stored in the geometry
+    let keep = teleport_point_mask(&points, &time_points, max_plausible_speed_kmh());
+    let removed = keep.iter().filter(|&&k| !k).count();
+    let (
+        points,
+        elevation_profile_data,
+        hr_data_points,
+        temp_data_points,
+        cadence_data_points,
+        power_data_points,
+        time_points,
+        trkseg_starts,
+    ) = if removed > 0 {
+        info!(removed, "GPX: removed points implying implausible speed");
+        let kept_before: Vec<usize> = keep
+            .iter()
+            .scan(0, |kept, &k| {
+                let before = *kept;
+                *kept += usize::from(k);
+                Some(before)
+            })
+            .collect();
+        (
+            retain_by_mask(points, &keep),
+            retain_by_mask(elevation_profile_data, &keep),
+            retain_by_mask(hr_data_points, &keep),
+            retain_by_mask(temp_data_points, &keep),
+            retain_by_mask(cadence_data_points, &keep),
+            retain_by_mask(power_data_points, &keep),
+            retain_by_mask(time_points, &keep),
+            trkseg_starts
+                .iter()
+                .map(|&start| {
+                    kept_before
+                        .get(start)
+                        .copied()
+                        .unwrap_or_else(|| start - removed)
+                })
+                .collect(),
+        )
+    } else {
+        (
+            points,
+            elevation_profile_data,
+            hr_data_points,
+            temp_data_points,
+            cadence_data_points,
+            power_data_points,
+            time_points,
+            trkseg_starts,
+        )
+    };
+    let segments: Vec<Vec<(f64, f64)>> = split_at_indices(&points, &trkseg_starts)
+        .into_iter()
+        .flat_map(|segment| split_points_by_gap(segment, None))
+        .collect();
     let geom_geojson = geojson_from_segments(&segments);
     let length_km = length_km_for_segments(&segments);
 
-    let hash = {
-        let mut hasher = Sha256::new();
-        hasher.update(bytes);
-        format!("{:x}", hasher.finalize())
-    };
+    let hash = calculate_file_hash(bytes);
 
     let recorded_at = if let Some(time_str) = recorded_at {
         parse_gpx_time(&time_str)
@@ -479,6 +634,12 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     } else {
         None
     };
+    let elevation_source = final_elevation_profile.as_ref().map(|_| {
+        ElevationSource::detect(
+            creator.as_deref(),
+            temp_data_points.iter().any(|t| t.is_some()),
+        )
+    });
 
     let final_hr_data = if hr_data_points.iter().any(|hr| hr.is_some()) {
         Some(hr_data_points)
@@ -546,6 +707,25 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     // Calculate duration_seconds (total duration) before moving time_points
     let duration_seconds = crate::track_utils::time_utils::calculate_track_duration(&time_points);
 
+    // Power meter aggregates; normalized power also needs the point times
+    let valid_power: Vec<i32> = power_data_points.iter().filter_map(|&p| p).collect();
+    let (power_avg, power_max, power_normalized) = if valid_power.is_empty() {
+        (None, None, None)
+    } else {
+        (
+            Some(
+                (valid_power.iter().map(|&p| i64::from(p)).sum::<i64>() as f64
+                    / valid_power.len() as f64) as i32,
+            ),
+            valid_power.iter().max().copied(),
+            calculate_normalized_power(&power_data_points, &time_points),
+        )
+    };
+
+    let moving_distance_km =
+        calculate_moving_distance(&points, &time_points, PAUSE_GAP_THRESHOLD_SECS);
+    let is_loop = is_loop_track(&points);
+
     let final_time_data = if time_points.iter().any(|t| t.is_some()) {
         Some(time_points)
     } else {
@@ -580,6 +760,18 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         }
     }
 
+    // Cadence aggregates, ignoring points without a reading like the heart rate ones
+    let valid_cadence: Vec<i32> = cadence_data_points.iter().filter_map(|&c| c).collect();
+    let (cadence_avg, cadence_min, cadence_max) = if valid_cadence.is_empty() {
+        (None, None, None)
+    } else {
+        (
+            Some((valid_cadence.iter().sum::<i32>() as f64 / valid_cadence.len() as f64) as i32),
+            valid_cadence.iter().min().copied(),
+            valid_cadence.iter().max().copied(),
+        )
+    };
+
     if total_moving_secs > 0.0 {
         moving_time = Some(total_moving_secs.round() as i32);
         moving_avg_speed = Some((moving_distance / 1000.0) / (total_moving_secs / 3600.0)); // km/h
@@ -597,7 +789,9 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     let avg_speed = crate::track_utils::metrics::avg_speed_kmh(length_km, duration_seconds);
 
     // Perform automatic track classification
-    use crate::track_classifier::{TrackMetrics, classify_track};
+    use crate::track_classifier::{
+        ClassificationResult, TrackClassification, TrackMetrics, classify_track, hr_variation,
+    };
     let metrics = TrackMetrics {
         length_km,
         avg_speed,
@@ -606,9 +800,20 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         elevation_loss: final_elevation_loss,
         moving_time,
         duration_seconds,
+        is_loop,
+        hr_variation: final_hr_data.as_deref().and_then(hr_variation),
     };
-    let classifications = classify_track(&metrics);
-    let auto_classifications: Vec<String> = classifications.iter().map(|c| c.to_string()).collect();
+    let classification_results = classify_track(&metrics);
+    let auto_classifications: Vec<String> = classification_results
+        .iter()
+        .map(|r| r.label.clone())
+        .collect();
+    let classification_confidence = ClassificationResult::confidence_map(&classification_results);
+    // Most confident first, so pace filtering picks the config of the likeliest activity
+    let classifications: Vec<TrackClassification> = classification_results
+        .iter()
+        .filter_map(|r| TrackClassification::from_label(&r.label))
+        .collect();
 
     // Calculate new elevation metrics using the elevation module
     let track_points_with_elevation: Vec<(f64, f64, Option<f64>)> = points
@@ -626,9 +831,20 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
 
     // Calculate slope metrics if elevation data is available
     let slope_result = if let Some(elevation_profile) = &final_elevation_profile {
-        use crate::track_utils::slope::calculate_slope_metrics;
+        use crate::track_utils::slope::{
+            calculate_slope_metrics_with_window, smoothing_window_for_source,
+        };
 
-        calculate_slope_metrics(&points, elevation_profile, "GPX Track")
+        calculate_slope_metrics_with_window(
+            &points,
+            elevation_profile,
+            "GPX Track",
+            smoothing_window_for_source(elevation_source),
+        )
+        .unwrap_or_else(|e| {
+            e.log("GPX Track");
+            Default::default()
+        })
     } else {
         Default::default()
     };
@@ -636,7 +852,16 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     // Apply adaptive pace filtering based on track classification
     let filtered_pace_data =
         if !pace_data_points.is_empty() && pace_data_points.iter().any(|p| p.is_some()) {
-            use crate::track_utils::pace_filter::filter_pace_data;
+            use crate::track_utils::pace_filter::{
+                filter_pace_by_window, filter_pace_data, pace_outlier_window_secs,
+            };
+            // Drop short-lived spikes (e.g. a stop at a traffic light) before the
+            // track-wide filters so they do not skew the global statistics
+            let pace_data_points = filter_pace_by_window(
+                &pace_data_points,
+                final_time_data.as_deref().unwrap_or_default(),
+                pace_outlier_window_secs(),
+            );
             debug!(
                 "Applying adaptive pace filtering with {} classifications",
                 classifications.len()
@@ -645,6 +870,7 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                 &pace_data_points,
                 &speed_data_points,
                 &time_diff_data,
+                final_hr_data.as_deref(),
                 &classifications,
             )
         } else {
@@ -676,12 +902,23 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         } else {
             Some(temp_data_points)
         },
+        cadence_data: if cadence_data_points.iter().all(|c| c.is_none()) {
+            None
+        } else {
+            Some(cadence_data_points)
+        },
+        power_data: if valid_power.is_empty() {
+            None
+        } else {
+            Some(power_data_points)
+        },
         time_data: final_time_data, // Store raw time data points
         // New elevation fields from elevation module
         elevation_gain: elevation_metrics.elevation_gain,
         elevation_loss: elevation_metrics.elevation_loss,
         elevation_min: elevation_metrics.elevation_min,
         elevation_max: elevation_metrics.elevation_max,
+        elevation_source,
         // Slope fields from universal calculator
         slope_min: slope_result.slope_min,
         slope_max: slope_result.slope_max,
@@ -692,13 +929,25 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         avg_hr: avg_hr_value, // Calculated average HR
         hr_min,
         hr_max,
+        cadence_avg,
+        cadence_min,
+        cadence_max,
+        power_avg,
+        power_max,
+        power_normalized,
         moving_time,
         pause_time,
         moving_avg_speed,
         moving_avg_pace,
+        moving_distance_km,
+        is_loop,
         duration_seconds, // Calculated duration
         hash,
         recorded_at,
+        metadata_author,
+        metadata_link,
+        metadata_copyright,
+        classification_confidence,
         auto_classifications,         // Add automatic classifications
         speed_data: final_speed_data, // Add calculated speed data
         pace_data: final_pace_data,   // Add calculated pace data
@@ -706,9 +955,90 @@ pub fn parse_gpx(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     })
 }
 
+/// Whether an unrecognised extension element looks like a power reading in watts rather
+/// than a derived value (left/right balance, zone, percentage) or a container
+fn is_power_reading_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.contains("power")
+        && !["balance", "zone", "pct", "percent", "extension"]
+            .iter()
+            .any(|derived| name.contains(derived))
+}
+
+/// Pick up provenance carried in attributes of direct `<metadata>` children.
+/// Only the first link and copyright are kept; links must be http(s) since the
+/// frontend renders them as anchors.
+fn read_metadata_attribute(
+    tag: &str,
+    e: &BytesStart,
+    parent: Option<&str>,
+    link: &mut Option<String>,
+    copyright: &mut Option<String>,
+) {
+    if parent != Some("metadata") {
+        return;
+    }
+    let attribute = |name: &str| {
+        e.try_get_attribute(name)
+            .ok()
+            .flatten()
+            .and_then(|a| a.unescape_value().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    match tag {
+        "link" if link.is_none() => {
+            *link = attribute("href").filter(|href| {
+                let lower = href.to_ascii_lowercase();
+                lower.starts_with("https://") || lower.starts_with("http://")
+            });
+        }
+        "copyright" if copyright.is_none() => {
+            *copyright = attribute("author").map(|holder| ammonia::clean(&holder));
+        }
+        _ => {}
+    }
+}
+
+/// Keep the values whose flag in `keep` is set; per-point series of another length are
+/// returned unchanged.
+fn retain_by_mask<T>(values: Vec<T>, keep: &[bool]) -> Vec<T> {
+    if values.len() != keep.len() {
+        return values;
+    }
+    values
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(v, &k)| k.then_some(v))
+        .collect()
+}
+
+/// Split points into consecutive slices starting at the given indices.
+/// Empty slices (e.g. empty `<trkseg>` elements) are skipped.
+fn split_at_indices<'a>(points: &'a [(f64, f64)], starts: &[usize]) -> Vec<&'a [(f64, f64)]> {
+    let mut bounds: Vec<usize> = starts
+        .iter()
+        .copied()
+        .filter(|&i| i > 0 && i < points.len())
+        .collect();
+    bounds.dedup();
+    bounds.push(points.len());
+
+    let mut slices = Vec::with_capacity(bounds.len());
+    let mut from = 0;
+    for to in bounds {
+        if to > from {
+            slices.push(&points[from..to]);
+        }
+        from = to;
+    }
+    slices
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_gpx;
+    use crate::track_utils::ElevationSource;
 
     fn with_env_var(key: &str, value: &str, f: impl FnOnce()) {
         // Delegate to `temp-env` to safely set/unset for the closure
@@ -747,6 +1077,74 @@ mod tests {
         assert!(parsed.length_km > 100.0 && parsed.length_km < 120.0);
     }
 
+    #[test]
+    fn detects_elevation_source_from_creator() {
+        let track = |creator: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="{creator}">
+    <trk><trkseg>
+        <trkpt lat="55.0" lon="37.0"><ele>120.0</ele></trkpt>
+        <trkpt lat="55.001" lon="37.0"><ele>121.0</ele></trkpt>
+    </trkseg></trk>
+</gpx>"#
+            )
+        };
+
+        let garmin = parse_gpx(track("Garmin Connect").as_bytes()).unwrap();
+        assert_eq!(garmin.elevation_source, Some(ElevationSource::Barometric));
+
+        let phone = parse_gpx(track("OsmAnd").as_bytes()).unwrap();
+        assert_eq!(phone.elevation_source, Some(ElevationSource::Gps));
+    }
+
+    #[test]
+    fn keeps_trkseg_boundaries_without_distance_gap() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+    <trk><name>Two Segments</name><trkseg>
+        <trkpt lat="55.0" lon="37.0"></trkpt>
+        <trkpt lat="55.001" lon="37.0"></trkpt>
+    </trkseg>
+    <trkseg>
+        <trkpt lat="55.002" lon="37.0"></trkpt>
+        <trkpt lat="55.003" lon="37.0"></trkpt>
+    </trkseg></trk>
+</gpx>"#;
+
+        let parsed = parse_gpx(gpx.as_bytes()).expect("parse success");
+        assert_eq!(parsed.geom_geojson["type"], "MultiLineString");
+        let segments = parsed
+            .geom_geojson
+            .get("coordinates")
+            .and_then(|c| c.as_array())
+            .expect("coordinates array");
+        assert_eq!(segments.len(), 2, "expected one segment per trkseg");
+        assert_eq!(segments[0].as_array().map(|s| s.len()), Some(2));
+        assert_eq!(segments[1].as_array().map(|s| s.len()), Some(2));
+    }
+
+    #[test]
+    fn sanitizes_waypoint_names() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+    <wpt lat="1.0" lon="2.0">
+        <name>&lt;script&gt;alert(1)&lt;/script&gt;Spring</name>
+        <desc>Fresh water</desc>
+    </wpt>
+    <trk><name>With Waypoints</name><trkseg>
+        <trkpt lat="0.0" lon="0.0"><ele>0.0</ele></trkpt>
+        <trkpt lat="0.0" lon="0.1"><ele>0.0</ele></trkpt>
+    </trkseg></trk>
+</gpx>"#;
+
+        let parsed = parse_gpx(gpx.as_bytes()).expect("parse success");
+        assert_eq!(parsed.waypoints.len(), 1);
+        let poi = &parsed.waypoints[0];
+        assert_eq!(poi.name, "Spring");
+        assert_eq!(poi.description.as_deref(), Some("Fresh water"));
+    }
+
     #[test]
     fn parses_waypoints_into_parsed_track() {
         let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -835,5 +1233,145 @@ mod tests {
         assert_eq!(recorded.to_rfc3339(), "2024-01-01T00:00:00+00:00");
     }
 
+    #[test]
+    fn drops_teleport_points_when_times_are_present() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+    <trk><name>Glitch</name><trkseg>
+        <trkpt lat="55.0000" lon="37.0"><time>2024-01-01T00:00:00Z</time></trkpt>
+        <trkpt lat="55.0005" lon="37.0"><time>2024-01-01T00:00:30Z</time></trkpt>
+        <trkpt lat="-33.900" lon="151.2"><time>2024-01-01T00:01:00Z</time></trkpt>
+        <trkpt lat="55.0010" lon="37.0"><time>2024-01-01T00:01:30Z</time></trkpt>
+    </trkseg><trkseg>
+        <trkpt lat="55.0015" lon="37.0"><time>2024-01-01T00:02:00Z</time></trkpt>
+    </trkseg></trk>
+</gpx>"#;
+
+        let parsed = parse_gpx(gpx.as_bytes()).expect("parse success");
+        assert_eq!(parsed.time_data.map(|t| t.len()), Some(4));
+        assert!(parsed.length_km < 1.0);
+        let segments = parsed.geom_geojson["coordinates"].as_array().unwrap();
+        assert_eq!(parsed.geom_geojson["type"], "MultiLineString");
+        assert_eq!(segments[0].as_array().unwrap().len(), 3);
+        assert_eq!(segments[1].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parses_metadata_provenance() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+    <metadata>
+        <name>Ridge Loop</name>
+        <author><name>Trail Club</name><link href="https://author.example"/></author>
+        <copyright author="OpenStreetMap contributors"><year>2024</year></copyright>
+        <link href="https://trails.example/ridge-loop"><text>Ridge Loop</text></link>
+        <time>2024-03-01T08:00:00Z</time>
+    </metadata>
+    <trk><name>Ridge Loop</name><trkseg>
+        <trkpt lat="0.0" lon="0.0"><time>2024-03-01T09:00:00Z</time></trkpt>
+        <trkpt lat="0.0" lon="0.1"><time>2024-03-01T09:01:00Z</time></trkpt>
+    </trkseg></trk>
+</gpx>"#;
+
+        let parsed = parse_gpx(gpx.as_bytes()).expect("parse success");
+        assert_eq!(parsed.metadata_author.as_deref(), Some("Trail Club"));
+        assert_eq!(
+            parsed.metadata_link.as_deref(),
+            Some("https://trails.example/ridge-loop")
+        );
+        assert_eq!(
+            parsed.metadata_copyright.as_deref(),
+            Some("OpenStreetMap contributors")
+        );
+        assert_eq!(
+            parsed.recorded_at.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2024-03-01T08:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn drops_non_http_metadata_link() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test">
+    <metadata><link href="javascript:alert(1)"/></metadata>
+    <trk><trkseg>
+        <trkpt lat="0.0" lon="0.0"></trkpt>
+        <trkpt lat="0.0" lon="0.1"></trkpt>
+    </trkseg></trk>
+</gpx>"#;
+
+        let parsed = parse_gpx(gpx.as_bytes()).expect("parse success");
+        assert!(parsed.metadata_link.is_none());
+        assert!(parsed.metadata_author.is_none());
+    }
+
+    fn power_track(extensions: [&str; 2]) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test"
+    xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v2"
+    xmlns:pwr="http://www.garmin.com/xmlschemas/PowerExtension/v1"
+    xmlns:wahoo="http://www.wahoofitness.com/gpx">
+    <trk><trkseg>
+        <trkpt lat="55.0" lon="37.0"><time>2024-05-01T10:00:00Z</time><extensions>{}</extensions></trkpt>
+        <trkpt lat="55.001" lon="37.0"><time>2024-05-01T10:00:10Z</time><extensions>{}</extensions></trkpt>
+    </trkseg></trk>
+</gpx>"#,
+            extensions[0], extensions[1]
+        )
+    }
+
+    #[test]
+    fn reads_power_from_vendor_extensions() {
+        let cases = [
+            [
+                "<gpxtpx:TrackPointExtension><gpxtpx:power>210</gpxtpx:power></gpxtpx:TrackPointExtension>",
+                "<gpxtpx:TrackPointExtension><gpxtpx:power>250</gpxtpx:power></gpxtpx:TrackPointExtension>",
+            ],
+            [
+                "<pwr:PowerExtension><pwr:PowerInWatts>210</pwr:PowerInWatts></pwr:PowerExtension>",
+                "<pwr:PowerExtension><pwr:PowerInWatts>250</pwr:PowerInWatts></pwr:PowerExtension>",
+            ],
+            [
+                "<wahoo:power>210</wahoo:power>",
+                "<wahoo:power>250.4</wahoo:power>",
+            ],
+        ];
+        for extensions in cases {
+            let parsed = parse_gpx(power_track(extensions).as_bytes()).expect("parse success");
+            assert_eq!(
+                parsed.power_data,
+                Some(vec![Some(210), Some(250)]),
+                "{}",
+                extensions[0]
+            );
+            assert_eq!(parsed.power_max, Some(250));
+        }
+    }
+
+    #[test]
+    fn falls_back_to_unknown_power_elements() {
+        let parsed = parse_gpx(
+            power_track([
+                "<ns3:BikePowerWatts>180</ns3:BikePowerWatts>",
+                "<hr>140</hr>",
+            ])
+            .as_bytes(),
+        )
+        .expect("parse success");
+        assert_eq!(parsed.power_data, Some(vec![Some(180), None]));
+
+        // Derived values, containers and non-numeric text are not power readings
+        let parsed = parse_gpx(
+            power_track([
+                "<ns3:PowerBalance>52</ns3:PowerBalance><ns3:PowerZone>3</ns3:PowerZone><ns3:PowerPct>85</ns3:PowerPct>",
+                "<ns3:PowerData><ns3:Watts>200</ns3:Watts></ns3:PowerData><ns3:PowerSource>NaN</ns3:PowerSource>",
+            ])
+            .as_bytes(),
+        )
+        .expect("parse success");
+        assert_eq!(parsed.power_data, None);
+    }
+
     // Integration/local-only test: removed because it depends on a local developer file
 }
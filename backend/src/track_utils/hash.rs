@@ -1,14 +1,35 @@
 // Hash utilities for trackly
 // Fast hash calculation without full file parsing
 
-use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, Read};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Size of the chunks fed to the hasher by `calculate_hash_streaming`
+const HASH_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Calculate file hash quickly without parsing GPX content
-/// This is much faster than full GPX parsing for existence checks
+/// This is much faster than full GPX parsing for existence checks.
+/// The hash only identifies duplicate uploads, so a fast non-cryptographic
+/// 128-bit xxHash (32 hex characters) is used instead of SHA-256.
 pub fn calculate_file_hash(bytes: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(bytes);
-    format!("{:x}", hasher.finalize())
+    // Reading from a slice never fails
+    calculate_hash_streaming(bytes).unwrap_or_default()
+}
+
+/// Same hash as `calculate_file_hash`, read from `reader` in 64 KB chunks so the whole
+/// file never has to be in memory at once.
+pub fn calculate_hash_streaming(mut reader: impl Read) -> std::io::Result<String> {
+    let mut hasher = Xxh3::new();
+    let mut buf = vec![0u8; HASH_CHUNK_SIZE];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(format!("{:032x}", hasher.digest128()))
 }
 
 #[cfg(test)]
@@ -23,7 +44,7 @@ mod tests {
 
         // Same content should produce same hash
         assert_eq!(hash1, hash2);
-        assert_eq!(hash1.len(), 64); // SHA256 produces 64 character hex string
+        assert_eq!(hash1.len(), 32); // xxh3_128 produces 32 character hex string
 
         // Different content should produce different hash
         let different_data = b"different content";
@@ -31,15 +52,28 @@ mod tests {
         assert_ne!(hash1, hash3);
     }
 
+    #[test]
+    fn test_streaming_hash_matches_one_shot() {
+        // Spans several chunks and ends mid-chunk
+        let data: Vec<u8> = (0..HASH_CHUNK_SIZE * 3 + 123)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        assert_eq!(
+            calculate_hash_streaming(data.as_slice()).unwrap(),
+            format!("{:032x}", xxhash_rust::xxh3::xxh3_128(&data))
+        );
+        assert_eq!(
+            calculate_hash_streaming(std::io::Cursor::new(b"")).unwrap(),
+            "99aa06d3014798d86001c324468d497f"
+        );
+    }
+
     #[test]
     fn test_empty_file_hash() {
         let empty_data = b"";
         let hash = calculate_file_hash(empty_data);
-        assert_eq!(hash.len(), 64);
+        assert_eq!(hash.len(), 32);
         // Empty file should produce consistent hash
-        assert_eq!(
-            hash,
-            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
-        );
+        assert_eq!(hash, "99aa06d3014798d86001c324468d497f");
     }
 }
@@ -3,18 +3,24 @@
 // TODO: switch to https://github.com/georust/kml
 
 use crate::models::ParsedTrackData;
+use crate::track_utils::TrackParseError;
 use crate::track_utils::elevation::{
-    calculate_elevation_metrics, extract_elevations_from_track_points, has_elevation_data,
+    ElevationSource, calculate_elevation_metrics, extract_elevations_from_track_points,
+    has_elevation_data,
 };
-use crate::track_utils::geometry::haversine_distance;
+use crate::track_utils::geometry::{haversine_distance, is_loop_track};
+use crate::track_utils::hash::calculate_file_hash;
+use crate::track_utils::time_utils::{PAUSE_GAP_THRESHOLD_SECS, calculate_moving_distance};
 use chrono::{DateTime, Utc};
 use kml::types::{Element, Geometry, Kml};
-use sha2::Digest;
 
 /// Parses a KML file, returns ParsedTrackData
-pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
-    let s = std::str::from_utf8(bytes).map_err(|e| format!("utf8 error: {e}"))?;
-    let kml_doc: Kml = s.parse().map_err(|e| format!("kml parse error: {e}"))?;
+pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, TrackParseError> {
+    let s = std::str::from_utf8(bytes)
+        .map_err(|e| TrackParseError::UnsupportedFormat(format!("KML must be UTF-8: {e}")))?;
+    let kml_doc: Kml = s
+        .parse()
+        .map_err(|e| TrackParseError::InvalidKml(format!("{e}")))?;
 
     let mut points = Vec::new();
     let mut elevation_profile_data = Vec::new();
@@ -179,6 +185,44 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         }
     }
 
+    // The kml crate drops namespace prefixes, so <gx:Track> and <gx:MultiTrack> arrive
+    // here as "Track" and "MultiTrack"
+    fn extract_track_elements(
+        element: &Element,
+        points: &mut Vec<(f64, f64)>,
+        elevations: &mut Vec<Option<f64>>,
+        time_data: &mut Vec<Option<DateTime<Utc>>>,
+        last_elevation: &mut Option<f64>,
+        total_elevation_gain: &mut f64,
+        total_elevation_loss: &mut f64,
+    ) {
+        match element.name.as_str() {
+            "Track" => extract_track_data(
+                element,
+                points,
+                elevations,
+                time_data,
+                last_elevation,
+                total_elevation_gain,
+                total_elevation_loss,
+            ),
+            "MultiTrack" => {
+                for child in &element.children {
+                    extract_track_elements(
+                        child,
+                        points,
+                        elevations,
+                        time_data,
+                        last_elevation,
+                        total_elevation_gain,
+                        total_elevation_loss,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn extract_kml_elements(
         kml_element: &Kml,
         points: &mut Vec<(f64, f64)>,
@@ -202,17 +246,15 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                 }
                 // Also process any children inside Placemark (e.g., Track)
                 for child in &pm.children {
-                    if child.name == "Track" {
-                        extract_track_data(
-                            child,
-                            points,
-                            elevations,
-                            time_data,
-                            last_elevation,
-                            total_elevation_gain,
-                            total_elevation_loss,
-                        );
-                    }
+                    extract_track_elements(
+                        child,
+                        points,
+                        elevations,
+                        time_data,
+                        last_elevation,
+                        total_elevation_gain,
+                        total_elevation_loss,
+                    );
                 }
             }
             Kml::Document { elements, .. } => {
@@ -242,17 +284,15 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
                 }
             }
             Kml::Element(element) => {
-                if element.name == "Track" {
-                    extract_track_data(
-                        element,
-                        points,
-                        elevations,
-                        time_data,
-                        last_elevation,
-                        total_elevation_gain,
-                        total_elevation_loss,
-                    );
-                }
+                extract_track_elements(
+                    element,
+                    points,
+                    elevations,
+                    time_data,
+                    last_elevation,
+                    total_elevation_gain,
+                    total_elevation_loss,
+                );
                 // For other elements, recursively process children if they are known KML elements
                 // But since Element is for unknown, we skip for now
             }
@@ -289,7 +329,7 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     }
 
     if points.is_empty() {
-        return Err("No points in KML".to_string());
+        return Err(TrackParseError::EmptyTrack);
     }
 
     fn points_to_geojson(points: &[(f64, f64)]) -> serde_json::Value {
@@ -307,11 +347,7 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     }
     length_km /= 1000.0;
 
-    let hash = {
-        let mut hasher = sha2::Sha256::new();
-        hasher.update(bytes);
-        format!("{:x}", hasher.finalize())
-    };
+    let hash = calculate_file_hash(bytes);
 
     let final_elevation_gain = if !points.is_empty() {
         Some(total_elevation_gain)
@@ -328,6 +364,14 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     } else {
         None
     };
+    // KML carries no device information, so recorded altitude is assumed to be GPS
+    let elevation_source = final_elevation_profile
+        .as_ref()
+        .map(|_| ElevationSource::Gps);
+
+    let moving_distance_km =
+        calculate_moving_distance(&points, &time_data, PAUSE_GAP_THRESHOLD_SECS);
+    let is_loop = is_loop_track(&points);
 
     let final_time_data = if time_data.iter().any(|t| t.is_some()) {
         Some(time_data)
@@ -336,7 +380,7 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
     };
 
     // Perform automatic track classification
-    use crate::track_classifier::{TrackMetrics, classify_track};
+    use crate::track_classifier::{ClassificationResult, TrackMetrics, classify_track};
     let metrics = TrackMetrics {
         length_km,
         avg_speed: None,
@@ -345,9 +389,15 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         elevation_loss: final_elevation_loss,
         moving_time: None,
         duration_seconds: None,
+        is_loop,
+        hr_variation: None,
     };
-    let classifications = classify_track(&metrics);
-    let auto_classifications: Vec<String> = classifications.iter().map(|c| c.to_string()).collect();
+    let classification_results = classify_track(&metrics);
+    let auto_classifications: Vec<String> = classification_results
+        .iter()
+        .map(|r| r.label.clone())
+        .collect();
+    let classification_confidence = ClassificationResult::confidence_map(&classification_results);
 
     // Calculate new elevation metrics using the elevation module
     let track_points_with_elevation: Vec<(f64, f64, Option<f64>)> = points
@@ -365,9 +415,20 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
 
     // Calculate slope metrics if elevation data is available
     let slope_result = if let Some(elevation_profile) = &final_elevation_profile {
-        use crate::track_utils::slope::calculate_slope_metrics;
+        use crate::track_utils::slope::{
+            calculate_slope_metrics_with_window, smoothing_window_for_source,
+        };
 
-        calculate_slope_metrics(&points, elevation_profile, "KML Track")
+        calculate_slope_metrics_with_window(
+            &points,
+            elevation_profile,
+            "KML Track",
+            smoothing_window_for_source(elevation_source),
+        )
+        .unwrap_or_else(|e| {
+            e.log("KML Track");
+            Default::default()
+        })
     } else {
         Default::default()
     };
@@ -378,12 +439,15 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         elevation_profile: final_elevation_profile,
         hr_data: None,   // KML does not typically contain HR data
         temp_data: None, // KML does not typically contain temperature data
+        cadence_data: None,
+        power_data: None,
         time_data: final_time_data,
         // New elevation fields from elevation module
         elevation_gain: elevation_metrics.elevation_gain,
         elevation_loss: elevation_metrics.elevation_loss,
         elevation_min: elevation_metrics.elevation_min,
         elevation_max: elevation_metrics.elevation_max,
+        elevation_source,
         // Slope fields from universal calculator
         slope_min: slope_result.slope_min,
         slope_max: slope_result.slope_max,
@@ -394,13 +458,25 @@ pub fn parse_kml(bytes: &[u8]) -> Result<ParsedTrackData, String> {
         avg_hr: None, // KML does not typically contain HR data
         hr_min: None,
         hr_max: None,
+        cadence_avg: None,
+        cadence_min: None,
+        cadence_max: None,
+        power_avg: None,
+        power_max: None,
+        power_normalized: None,
         moving_time: None,
         pause_time: None,
         moving_avg_speed: None,
         moving_avg_pace: None,
+        moving_distance_km,
+        is_loop,
         duration_seconds: None,
         hash,
         recorded_at: None,
+        metadata_author: None,
+        metadata_link: None,
+        metadata_copyright: None,
+        classification_confidence,
         auto_classifications,  // Add automatic classifications
         speed_data: None,      // KML typically doesn't contain speed data
         pace_data: None,       // KML typically doesn't contain pace data
@@ -1,6 +1,82 @@
 // Metrics utilities for trackly
 // Extracted from track_utils.rs for modularization
 
+use chrono::{DateTime, Utc};
+
+/// Rolling window used for normalized power, in seconds
+const NORMALIZED_POWER_WINDOW_SECS: usize = 30;
+
+/// Samples further apart than this are treated as a pause rather than held power
+const MAX_POWER_HOLD_SECS: i64 = 30;
+
+/// Upper bounds of the Coggan power zones 1-6 as a fraction of FTP; zone 7 is open-ended
+pub const POWER_ZONE_UPPER_BOUNDS: [f64; 6] = [0.55, 0.75, 0.90, 1.05, 1.20, 1.50];
+
+/// Resample power readings to one value per second, holding each reading until the next
+/// timestamped one. Points without power or time are skipped, and gaps longer than
+/// `MAX_POWER_HOLD_SECS` only contribute their first seconds.
+fn power_per_second(power_data: &[Option<i32>], time_data: &[Option<DateTime<Utc>>]) -> Vec<f64> {
+    let samples: Vec<(DateTime<Utc>, i32)> = power_data
+        .iter()
+        .zip(time_data)
+        .filter_map(|(&power, &time)| Some((time?, power?)))
+        .collect();
+
+    let mut series = Vec::new();
+    for pair in samples.windows(2) {
+        let (start, watts) = pair[0];
+        let secs = (pair[1].0 - start)
+            .num_seconds()
+            .clamp(0, MAX_POWER_HOLD_SECS);
+        series.extend(std::iter::repeat_n(f64::from(watts.max(0)), secs as usize));
+    }
+    series
+}
+
+/// Normalized power: 30-second rolling average of the power, raised to the 4th power,
+/// averaged, and the 4th root taken. Needs at least 30 seconds of timed power data.
+pub fn calculate_normalized_power(
+    power_data: &[Option<i32>],
+    time_data: &[Option<DateTime<Utc>>],
+) -> Option<i32> {
+    let series = power_per_second(power_data, time_data);
+    if series.len() < NORMALIZED_POWER_WINDOW_SECS {
+        return None;
+    }
+
+    let mut window_sum: f64 = series[..NORMALIZED_POWER_WINDOW_SECS].iter().sum();
+    let mut fourth_power_sum = (window_sum / NORMALIZED_POWER_WINDOW_SECS as f64).powi(4);
+    for i in NORMALIZED_POWER_WINDOW_SECS..series.len() {
+        window_sum += series[i] - series[i - NORMALIZED_POWER_WINDOW_SECS];
+        fourth_power_sum += (window_sum / NORMALIZED_POWER_WINDOW_SECS as f64).powi(4);
+    }
+    let windows = series.len() - NORMALIZED_POWER_WINDOW_SECS + 1;
+    Some((fourth_power_sum / windows as f64).powf(0.25).round() as i32)
+}
+
+/// Seconds spent in each of the 7 Coggan power zones for the given FTP in watts.
+/// Returns `None` when the track has no timed power data.
+pub fn power_zone_seconds(
+    power_data: &[Option<i32>],
+    time_data: &[Option<DateTime<Utc>>],
+    ftp: u32,
+) -> Option<[u32; 7]> {
+    let series = power_per_second(power_data, time_data);
+    if series.is_empty() || ftp == 0 {
+        return None;
+    }
+
+    let mut zones = [0u32; 7];
+    for watts in series {
+        let zone = POWER_ZONE_UPPER_BOUNDS
+            .iter()
+            .position(|bound| watts <= bound * f64::from(ftp))
+            .unwrap_or(POWER_ZONE_UPPER_BOUNDS.len());
+        zones[zone] += 1;
+    }
+    Some(zones)
+}
+
 /// Calculate average speed in km/h
 pub fn avg_speed_kmh(length_km: f64, duration_seconds: Option<i32>) -> Option<f64> {
     if let Some(duration) = duration_seconds {
@@ -50,6 +126,41 @@ mod tests {
         assert_eq!(avg_pace_min_per_km(5.0, Some(1500)), Some(5.0)); // 25min for 5km = 5min/km
     }
 
+    fn seconds(n: i64) -> Option<DateTime<Utc>> {
+        Some(DateTime::from_timestamp(1_700_000_000 + n, 0).unwrap())
+    }
+
+    #[test]
+    fn test_normalized_power_constant_and_variable_effort() {
+        let times: Vec<_> = (0..=120).map(seconds).collect();
+        let steady = vec![Some(200); times.len()];
+        assert_eq!(calculate_normalized_power(&steady, &times), Some(200));
+
+        // Alternating 60 s blocks of 100 W and 300 W average 200 W but weigh more
+        let blocks: Vec<_> = (0..=120)
+            .map(|i| Some(if (i / 60) % 2 == 0 { 100 } else { 300 }))
+            .collect();
+        let np = calculate_normalized_power(&blocks, &times).unwrap();
+        assert!(np > 200 && np < 300, "np = {np}");
+
+        // Too short, or no timestamps
+        assert_eq!(
+            calculate_normalized_power(&steady[..20], &times[..20]),
+            None
+        );
+        assert_eq!(calculate_normalized_power(&steady, &[None; 121]), None);
+    }
+
+    #[test]
+    fn test_power_zone_seconds_uses_time_and_caps_pauses() {
+        // 10 s at 100 W (zone 1 for FTP 250), 10 s at 250 W (zone 4), then a 10 min gap
+        let times = vec![seconds(0), seconds(10), seconds(20), seconds(620)];
+        let power = vec![Some(100), Some(250), Some(400), Some(400)];
+        let zones = power_zone_seconds(&power, &times, 250).unwrap();
+        assert_eq!(zones, [10, 0, 0, 10, 0, 0, 30]);
+        assert_eq!(power_zone_seconds(&power, &times, 0), None);
+    }
+
     #[test]
     fn test_avg_pace_min_per_km_zero_length_or_time() {
         assert_eq!(avg_pace_min_per_km(0.0, Some(3600)), None);
@@ -16,30 +16,80 @@ pub mod time_utils;
 pub mod zoom_adaptation;
 
 pub use elevation::{
-    ElevationMetrics, calculate_elevation_metrics, extract_elevations_from_track_points,
-    has_elevation_data, smooth_elevation_data,
+    ElevationMetrics, ElevationSource, calculate_elevation_metrics,
+    extract_elevations_from_track_points, has_elevation_data, smooth_elevation_data,
 };
 pub use elevation_enrichment::{ElevationEnrichmentService, EnrichmentResult};
 pub use geometry::{
-    extract_coordinates_from_geojson, extract_segments_from_geojson, geojson_from_segments,
-    haversine_distance, length_km_for_segments, parse_linestring_wkt, split_points_by_gap,
+    cumulative_distances_m, extract_coordinates_from_geojson, extract_segments_from_geojson,
+    geojson_from_segments, haversine_distance, is_loop_track, length_km_for_segments,
+    parse_linestring_wkt, point_distance_m, split_points_by_gap, vincenty_distance,
 };
 pub use gpx_parser::parse_gpx;
-pub use hash::calculate_file_hash;
+pub use hash::{calculate_file_hash, calculate_hash_streaming};
 pub use kml_parser::parse_kml;
 pub use optimized_gpx_parser::{parse_gpx_full, parse_gpx_minimal};
 pub use pace_filter::{
     PaceFilterConfig, detect_cycling_and_get_config, filter_pace_data, get_pace_filter_config,
 };
 pub use simplification::{
-    get_simplification_stats, get_tolerance_for_zoom, simplify_json_array,
-    simplify_profile_array_adaptive, simplify_profile_data, simplify_track,
-    simplify_track_for_zoom,
+    get_simplification_stats, lttb_simplify, simplify_json_array, simplify_profile_array_adaptive,
+    simplify_profile_data, simplify_track, simplify_track_for_zoom,
 };
 pub use slope::{
-    SlopeMetrics, calculate_slope_metrics, can_calculate_slopes, recalculate_slope_metrics,
+    SlopeCalculationError, SlopeMetrics, calculate_slope_metrics,
+    calculate_slope_metrics_with_window, can_calculate_slopes, recalculate_slope_metrics,
+    recalculate_slope_metrics_with_window, smoothing_window_for_source,
 };
-pub use zoom_adaptation::{SimplificationParams, get_simplification_params, tolerance_for_zoom};
+pub use zoom_adaptation::{
+    SimplificationParams, degrees_per_pixel, get_simplification_params, tolerance_for_zoom,
+};
+
+/// Why a GPX or KML file could not be turned into a track
+#[derive(Debug, thiserror::Error)]
+pub enum TrackParseError {
+    #[error("invalid XML: {0}")]
+    InvalidXml(#[from] quick_xml::Error),
+    #[error("invalid KML: {0}")]
+    InvalidKml(String),
+    #[error("missing required field: {0}")]
+    MissingField(String),
+    #[error("invalid coordinate lat={lat:?} lon={lon:?}")]
+    InvalidCoordinate { lat: String, lon: String },
+    #[error("file contains no track points")]
+    EmptyTrack,
+    #[error("unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Read and validate the `lat`/`lon` attributes of a GPX point element
+pub(crate) fn parse_point_coordinates(
+    element: &quick_xml::events::BytesStart<'_>,
+) -> Result<(f64, f64), TrackParseError> {
+    let mut raw_lat = None;
+    let mut raw_lon = None;
+    for attr in element.attributes().flatten() {
+        let value = String::from_utf8_lossy(&attr.value).into_owned();
+        match attr.key.as_ref() {
+            b"lat" => raw_lat = Some(value),
+            b"lon" => raw_lon = Some(value),
+            _ => {}
+        }
+    }
+    let raw_lat = raw_lat.ok_or_else(|| TrackParseError::MissingField("lat".to_string()))?;
+    let raw_lon = raw_lon.ok_or_else(|| TrackParseError::MissingField("lon".to_string()))?;
+    match (raw_lat.trim().parse::<f64>(), raw_lon.trim().parse::<f64>()) {
+        (Ok(lat), Ok(lon)) if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) => {
+            Ok((lat, lon))
+        }
+        _ => Err(TrackParseError::InvalidCoordinate {
+            lat: raw_lat,
+            lon: raw_lon,
+        }),
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -148,6 +198,23 @@ mod tests {
         let parsed_data = res.unwrap();
         assert!(parsed_data.hr_data.is_none(), "hr_data should be None");
         assert!(parsed_data.avg_hr.is_none(), "avg_hr should be None");
+        assert!(parsed_data.cadence_avg.is_none() && parsed_data.cadence_max.is_none());
+    }
+
+    #[test]
+    fn test_parse_gpx_cadence_stats() {
+        let gpx_with_cadence = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v1">
+  <trk><name>Test Cadence</name><trkseg>
+    <trkpt lat="55.0" lon="37.0"><extensions><gpxtpx:TrackPointExtension><gpxtpx:cad>80</gpxtpx:cad></gpxtpx:TrackPointExtension></extensions></trkpt>
+    <trkpt lat="55.001" lon="37.0"></trkpt>
+    <trkpt lat="55.002" lon="37.0"><extensions><gpxtpx:TrackPointExtension><gpxtpx:cad>91</gpxtpx:cad></gpxtpx:TrackPointExtension></extensions></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+        let parsed_data = parse_gpx(gpx_with_cadence.as_bytes()).unwrap();
+        assert_eq!(parsed_data.cadence_avg, Some(85)); // (80+91)/2, the point without cadence is skipped
+        assert_eq!(parsed_data.cadence_min, Some(80));
+        assert_eq!(parsed_data.cadence_max, Some(91));
     }
 
     #[test]
@@ -204,6 +271,87 @@ mod tests {
         assert!(!parsed_data.hash.is_empty());
     }
 
+    #[test]
+    fn test_parse_kml_with_gx_track() {
+        // Layout written by Google Earth: all <when> values first, then all <gx:coord>
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+<Document>
+<name>Morning ride</name>
+<Placemark>
+<name>Morning ride</name>
+<gx:MultiTrack>
+<altitudeMode>absolute</altitudeMode>
+<gx:interpolate>0</gx:interpolate>
+<gx:Track>
+<when>2024-05-12T06:30:00Z</when>
+<when>2024-05-12T06:30:10Z</when>
+<when>2024-05-12T06:30:20Z</when>
+<gx:coord>10.61540 51.79910 1125.0</gx:coord>
+<gx:coord>10.61610 51.79950 1128.5</gx:coord>
+<gx:coord>10.61690 51.79990 1131.0</gx:coord>
+</gx:Track>
+<gx:Track>
+<when>2024-05-12T06:45:00Z</when>
+<when>2024-05-12T06:45:10Z</when>
+<gx:coord>10.62100 51.80200 1135.0</gx:coord>
+<gx:coord>10.62180 51.80240 1133.0</gx:coord>
+</gx:Track>
+</gx:MultiTrack>
+</Placemark>
+</Document>
+</kml>"#;
+        let parsed_data = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(parsed_data.geom_geojson["type"], "LineString");
+        assert_eq!(
+            parsed_data.geom_geojson["coordinates"][0],
+            serde_json::json!([10.6154, 51.7991])
+        );
+        let time_data = parsed_data.time_data.unwrap();
+        assert_eq!(time_data.len(), 5);
+        assert_eq!(
+            time_data[0].unwrap().to_rfc3339(),
+            "2024-05-12T06:30:00+00:00"
+        );
+        assert_eq!(
+            time_data[4].unwrap().to_rfc3339(),
+            "2024-05-12T06:45:10+00:00"
+        );
+        let elevations = parsed_data.elevation_profile.unwrap();
+        assert_eq!(elevations.len(), 5);
+        assert_eq!(elevations[1], Some(1128.5));
+    }
+
+    #[test]
+    fn test_parse_gpx_errors_are_structured() {
+        let bad_coordinate = r#"<gpx><trk><trkseg>
+    <trkpt lat="north" lon="37.0"></trkpt>
+  </trkseg></trk></gpx>"#;
+        assert!(matches!(
+            parse_gpx(bad_coordinate.as_bytes()),
+            Err(TrackParseError::InvalidCoordinate { ref lat, .. }) if lat == "north"
+        ));
+
+        let missing_lon = r#"<gpx><trk><trkseg><trkpt lat="55.0"></trkpt></trkseg></trk></gpx>"#;
+        assert!(matches!(
+            parse_gpx(missing_lon.as_bytes()),
+            Err(TrackParseError::MissingField(ref field)) if field == "lon"
+        ));
+
+        assert!(matches!(
+            parse_gpx(b"<gpx><trk></trk></gpx>"),
+            Err(TrackParseError::EmptyTrack)
+        ));
+        assert!(matches!(
+            parse_gpx(b"<gpx><trk></gpx>"),
+            Err(TrackParseError::InvalidXml(_))
+        ));
+        assert!(matches!(
+            parse_kml(&[0xff, 0xfe, 0x00]),
+            Err(TrackParseError::UnsupportedFormat(_))
+        ));
+    }
+
     #[test]
     fn test_parse_gpx_route_only() {
         let gpx_route = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -295,9 +443,9 @@ mod tests {
         assert!(mid_zoom_tolerance > high_zoom_tolerance);
 
         // Specific expected values
-        assert_eq!(low_zoom_tolerance, 100.0); // ~100m for world view
-        assert_eq!(mid_zoom_tolerance, 25.0); // ~25m for city view
-        assert_eq!(high_zoom_tolerance, 2.0); // ~2m for street view
+        assert_approx_eq!(low_zoom_tolerance, 100.0, 1e-9); // ~100m for world view
+        assert_approx_eq!(mid_zoom_tolerance, 20.9, 0.1); // ~21m for city view
+        assert_approx_eq!(high_zoom_tolerance, 2.0, 1e-9); // ~2m for street view
     }
 
     #[test]
@@ -2,10 +2,11 @@
 // This version separates fast parsing from expensive metric calculations
 
 use crate::models::ParsedTrackData;
+use crate::track_utils::hash::calculate_file_hash;
 use crate::track_utils::time_utils::parse_gpx_time;
+use crate::track_utils::{TrackParseError, parse_point_coordinates};
 use quick_xml::Reader;
 use quick_xml::events::Event;
-use sha2::{Digest, Sha256};
 
 /// Fast minimal GPX data for duplicate checking
 #[derive(Debug)]
@@ -17,7 +18,7 @@ pub struct MinimalGpxData {
 
 /// Parse GPX file quickly for duplicate checking (minimal processing)
 /// This is much faster than full parsing for large files
-pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, String> {
+pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, TrackParseError> {
     let mut reader = Reader::from_reader(bytes);
     reader.config_mut().trim_text(true);
     let mut buf = Vec::new();
@@ -45,71 +46,38 @@ pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, String> {
 
                 match tag_stripped {
                     "trkpt" => {
-                        lat = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lat" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
-                        lon = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lon" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                        let (point_lat, point_lon) = parse_point_coordinates(e)?;
+                        lat = Some(point_lat);
+                        lon = Some(point_lon);
                     }
                     "rtept" => {
-                        lat = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lat" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
-                        lon = e.attributes().find_map(|a| {
-                            a.ok().and_then(|attr| {
-                                if attr.key.as_ref() == b"lon" {
-                                    std::str::from_utf8(&attr.value).ok()?.parse::<f64>().ok()
-                                } else {
-                                    None
-                                }
-                            })
-                        });
+                        let (point_lat, point_lon) = parse_point_coordinates(e)?;
+                        lat = Some(point_lat);
+                        lon = Some(point_lon);
                     }
-                    "time" => {
-                        // If inside <metadata>, prefer this as recorded_at
+                    // If inside <metadata>, prefer this as recorded_at
+                    "time"
                         if element_stack.len() >= 2
                             && element_stack[element_stack.len() - 2] == "metadata"
-                            && !found_metadata_time
-                        {
-                            capture_text = true;
-                            text_target = Some("metadata_time".to_string());
-                        }
+                            && !found_metadata_time =>
+                    {
+                        capture_text = true;
+                        text_target = Some("metadata_time".to_string());
                     }
                     _ => {}
                 }
             }
-            Ok(Event::Text(e)) => {
-                if capture_text {
-                    if let Some(target) = &text_target
-                        && target.as_str() == "metadata_time"
-                        && !found_metadata_time
-                    {
-                        let text = std::str::from_utf8(&e).unwrap_or_default();
-                        recorded_at = Some(text.to_string());
-                        found_metadata_time = true;
-                    }
-                    capture_text = false;
-                    text_target = None;
+            Ok(Event::Text(e)) if capture_text => {
+                if let Some(target) = &text_target
+                    && target.as_str() == "metadata_time"
+                    && !found_metadata_time
+                {
+                    let text = std::str::from_utf8(&e).unwrap_or_default();
+                    recorded_at = Some(text.to_string());
+                    found_metadata_time = true;
                 }
+                capture_text = false;
+                text_target = None;
             }
             Ok(Event::End(ref e)) => {
                 let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
@@ -139,7 +107,7 @@ pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, String> {
                 }
             }
             Ok(Event::Eof) => break,
-            Err(e) => return Err(format!("Error parsing GPX: {e}")),
+            Err(e) => return Err(e.into()),
             _ => {}
         }
         buf.clear();
@@ -153,15 +121,11 @@ pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, String> {
     };
 
     if final_points.is_empty() {
-        return Err("No points in GPX".to_string());
+        return Err(TrackParseError::EmptyTrack);
     }
 
     // Calculate hash from original file content
-    let hash = {
-        let mut hasher = Sha256::new();
-        hasher.update(bytes);
-        format!("{:x}", hasher.finalize())
-    };
+    let hash = calculate_file_hash(bytes);
 
     let recorded_at_parsed = if let Some(time_str) = recorded_at {
         parse_gpx_time(&time_str)
@@ -178,7 +142,7 @@ pub fn parse_gpx_minimal(bytes: &[u8]) -> Result<MinimalGpxData, String> {
 
 /// Parse full GPX data with all metrics (expensive operation)
 /// Should only be called after duplicate check passes
-pub fn parse_gpx_full(bytes: &[u8]) -> Result<ParsedTrackData, String> {
+pub fn parse_gpx_full(bytes: &[u8]) -> Result<ParsedTrackData, TrackParseError> {
     // We already have the minimal data, but for now just delegate to full parser
     // In the future we could optimize this further by reusing minimal parsing results
     crate::track_utils::gpx_parser::parse_gpx(bytes)
@@ -208,7 +172,7 @@ mod tests {
         assert_eq!(minimal.points[0], (55.0, 37.0));
         assert_eq!(minimal.points[1], (55.1, 37.1));
         assert!(minimal.recorded_at.is_some());
-        assert_eq!(minimal.hash.len(), 64); // SHA256 hash length
+        assert_eq!(minimal.hash.len(), 32); // xxh3_128 hash length
     }
 
     #[test]
@@ -218,8 +182,7 @@ mod tests {
 </gpx>"#;
 
         let result = parse_gpx_minimal(gpx_content.as_bytes());
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("No points"));
+        assert!(matches!(result, Err(TrackParseError::EmptyTrack)));
     }
 
     #[test]
@@ -1,8 +1,15 @@
 /// Adaptive pace filtering module for trackly
 /// Filters pace spikes and anomalies based on track activity type and context
-use crate::track_classifier::TrackClassification;
+use crate::track_classifier::{
+    EBIKE_MAX_HR_VARIATION, EBIKE_SPEED_RANGE_KMH, TrackClassification, hr_variation,
+};
+use chrono::{DateTime, Utc};
 use tracing::debug;
 
+/// With n samples the largest possible Z-score is (n-1)/sqrt(n), which only exceeds
+/// 2.5 from 8 samples on; smaller windows are left untouched
+const MIN_WINDOW_SAMPLES: usize = 8;
+
 /// Configuration for pace filtering parameters
 #[derive(Debug, Clone)]
 pub struct PaceFilterConfig {
@@ -48,6 +55,7 @@ pub fn get_pace_filter_config(classifications: &[TrackClassification]) -> PaceFi
                 max_time_gap: get_env_u32("PACE_MAX_TIME_GAP_WALKING", 60),
                 ..Default::default()
             },
+            TrackClassification::EBike => ebike_config(),
             TrackClassification::Trail
             | TrackClassification::Marathon
             | TrackClassification::HalfMarathon
@@ -69,14 +77,52 @@ pub fn get_pace_filter_config(classifications: &[TrackClassification]) -> PaceFi
     }
 }
 
-/// Detect if track might be cycling based on speed patterns and return appropriate config
-pub fn detect_cycling_and_get_config(speed_data: &[Option<f64>]) -> Option<PaceFilterConfig> {
+/// Pace filter preset for e-bikes: motor assistance keeps speeds high and steady,
+/// so the accepted range is narrower than for human-powered cycling
+fn ebike_config() -> PaceFilterConfig {
+    PaceFilterConfig {
+        spike_multiplier: get_env_f64("PACE_SPIKE_MULTIPLIER_EBIKE", 4.0),
+        max_pace: get_env_f64("PACE_MAX_EBIKE", 4.0),
+        min_pace: get_env_f64("PACE_MIN_EBIKE", 1.0),
+        max_time_gap: get_env_u32("PACE_MAX_TIME_GAP_EBIKE", 60),
+        ..Default::default()
+    }
+}
+
+/// E-bike rides hold 25-50 km/h for most of the ride with a flat heart rate, because the
+/// rider is not the only power source. Without heart rate a fast human-powered ride
+/// looks the same, so it does not count as an e-bike.
+fn is_ebike_pattern(valid_speeds: &[f64], hr_data: Option<&[Option<i32>]>) -> bool {
+    let in_range = valid_speeds
+        .iter()
+        .filter(|s| EBIKE_SPEED_RANGE_KMH.contains(s))
+        .count();
+    if (in_range as f64) < valid_speeds.len() as f64 * 0.7 {
+        return false;
+    }
+
+    hr_data
+        .and_then(hr_variation)
+        .is_some_and(|cv| cv < EBIKE_MAX_HR_VARIATION)
+}
+
+/// Detect if track might be cycling based on speed patterns and return appropriate config.
+/// E-bike rides get their own preset; `hr_data` is used to tell them apart from fast
+/// human-powered rides.
+pub fn detect_cycling_and_get_config(
+    speed_data: &[Option<f64>],
+    hr_data: Option<&[Option<i32>]>,
+) -> Option<PaceFilterConfig> {
     let valid_speeds: Vec<f64> = speed_data.iter().filter_map(|&s| s).collect();
 
     if valid_speeds.len() < 3 {
         return None; // Not enough data to determine
     }
 
+    if is_ebike_pattern(&valid_speeds, hr_data) {
+        return Some(ebike_config());
+    }
+
     let avg_speed = valid_speeds.iter().sum::<f64>() / valid_speeds.len() as f64;
     let max_speed = valid_speeds.iter().fold(0.0_f64, |a, &b| a.max(b));
 
@@ -102,6 +148,7 @@ pub fn filter_pace_data(
     pace_data: &[Option<f64>],
     speed_data: &[Option<f64>],
     time_diffs: &[Option<f64>], // time differences in seconds
+    hr_data: Option<&[Option<i32>]>,
     classifications: &[TrackClassification],
 ) -> Vec<Option<f64>> {
     if pace_data.is_empty() {
@@ -114,7 +161,7 @@ pub fn filter_pace_data(
     // If no specific classification matched, try cycling detection based on speed patterns
     if (classifications.is_empty()
         || config.spike_multiplier == get_env_f64("PACE_SPIKE_MULTIPLIER_DEFAULT", 3.0))
-        && let Some(cycling_config) = detect_cycling_and_get_config(speed_data)
+        && let Some(cycling_config) = detect_cycling_and_get_config(speed_data, hr_data)
     {
         config = cycling_config;
         debug!("Detected cycling activity based on speed patterns");
@@ -151,6 +198,68 @@ pub fn filter_pace_data(
     filtered_data
 }
 
+/// Width of the time window used by [`filter_pace_by_window`]
+pub fn pace_outlier_window_secs() -> u64 {
+    u64::from(get_env_u32("PACE_OUTLIER_WINDOW_SECS", 30))
+}
+
+/// Remove pace points that are outliers relative to their neighbours in time.
+///
+/// For every point the mean and standard deviation of the valid paces within
+/// `window_secs` (centred on the point) are computed, and the point is dropped when
+/// its Z-score exceeds `PACE_OUTLIER_STD_DEV_THRESHOLD` (2.5 by default). Decisions are
+/// made against the unfiltered input, so one removal does not shift the next window.
+pub fn filter_pace_by_window(
+    pace_data: &[Option<f64>],
+    time_data: &[Option<DateTime<Utc>>],
+    window_secs: u64,
+) -> Vec<Option<f64>> {
+    if time_data.len() != pace_data.len() || window_secs == 0 {
+        return pace_data.to_vec();
+    }
+
+    let half_window_ms = (window_secs * 1000 / 2) as i64;
+    let z_threshold = get_env_f64("PACE_OUTLIER_STD_DEV_THRESHOLD", 2.5);
+    let samples: Vec<Option<(i64, f64)>> = pace_data
+        .iter()
+        .zip(time_data)
+        .map(|(pace, time)| Some((time.as_ref()?.timestamp_millis(), (*pace)?)))
+        .collect();
+
+    let mut filtered_data = pace_data.to_vec();
+    for (i, sample) in samples.iter().enumerate() {
+        let Some((center_ms, current_pace)) = *sample else {
+            continue;
+        };
+        let in_window = |s: &&Option<(i64, f64)>| {
+            s.is_none_or(|(t, _)| (t - center_ms).abs() <= half_window_ms)
+        };
+        let before = samples[..i].iter().rev().take_while(in_window);
+        let after = samples[i + 1..].iter().take_while(in_window);
+        let window: Vec<f64> = before
+            .chain(after)
+            .filter_map(|s| s.map(|(_, p)| p))
+            .chain(std::iter::once(current_pace))
+            .collect();
+
+        if window.len() < MIN_WINDOW_SAMPLES {
+            continue;
+        }
+        let mean = window.iter().sum::<f64>() / window.len() as f64;
+        let variance = window.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / window.len() as f64;
+        let std_dev = variance.sqrt();
+        if std_dev > 0.0 && (current_pace - mean).abs() / std_dev > z_threshold {
+            filtered_data[i] = None;
+            debug!(
+                "Filtered windowed pace outlier at index {}: {} min/km (window mean: {} min/km)",
+                i, current_pace, mean
+            );
+        }
+    }
+
+    filtered_data
+}
+
 /// Apply basic range filtering based on activity-specific limits
 fn apply_range_filter(
     pace_data: &[Option<f64>],
@@ -547,7 +656,8 @@ mod tests {
         // Force explicit running classification to avoid cycling detection
         let classifications = vec![TrackClassification::TempoRun];
 
-        let filtered = filter_pace_data(&pace_data, &speed_data, &time_diffs, &classifications);
+        let filtered =
+            filter_pace_data(&pace_data, &speed_data, &time_diffs, None, &classifications);
 
         println!("Original pace data: {:?}", pace_data);
         println!("Filtered pace data: {:?}", filtered);
@@ -581,7 +691,13 @@ mod tests {
         let empty_time: Vec<Option<f64>> = Vec::new();
         let classifications = vec![TrackClassification::TempoRun];
 
-        let filtered = filter_pace_data(&empty_pace, &empty_speed, &empty_time, &classifications);
+        let filtered = filter_pace_data(
+            &empty_pace,
+            &empty_speed,
+            &empty_time,
+            None,
+            &classifications,
+        );
         assert!(filtered.is_empty());
     }
 
@@ -592,7 +708,8 @@ mod tests {
         let time_diffs = vec![Some(10.0)];
         let classifications = vec![TrackClassification::TempoRun];
 
-        let filtered = filter_pace_data(&pace_data, &speed_data, &time_diffs, &classifications);
+        let filtered =
+            filter_pace_data(&pace_data, &speed_data, &time_diffs, None, &classifications);
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0], Some(5.0));
     }
@@ -685,6 +802,7 @@ mod tests {
             &pace_data,
             &speed_data,
             &time_diffs,
+            None,
             &running_classifications,
         );
 
@@ -694,6 +812,7 @@ mod tests {
             &pace_data,
             &speed_data,
             &time_diffs,
+            None,
             &hiking_classifications,
         );
 
@@ -752,8 +871,13 @@ mod tests {
 
         // Test with no explicit classifications (should detect cycling)
         let no_classifications: Vec<TrackClassification> = vec![];
-        let cycling_filtered =
-            filter_pace_data(&pace_data, &speed_data, &time_diffs, &no_classifications);
+        let cycling_filtered = filter_pace_data(
+            &pace_data,
+            &speed_data,
+            &time_diffs,
+            None,
+            &no_classifications,
+        );
 
         // Test with explicit running classification for comparison
         let running_classifications = vec![TrackClassification::TempoRun];
@@ -761,6 +885,7 @@ mod tests {
             &pace_data,
             &speed_data,
             &time_diffs,
+            None,
             &running_classifications,
         );
 
@@ -787,7 +912,7 @@ mod tests {
 
         // High speed data should be detected as cycling
         let cycling_speeds = vec![Some(25.0), Some(30.0), Some(35.0), Some(20.0)];
-        let cycling_config = detect_cycling_and_get_config(&cycling_speeds);
+        let cycling_config = detect_cycling_and_get_config(&cycling_speeds, None);
         assert!(
             cycling_config.is_some(),
             "High speeds should be detected as cycling"
@@ -795,7 +920,7 @@ mod tests {
 
         // Low speed data should not be detected as cycling
         let walking_speeds = vec![Some(5.0), Some(4.0), Some(6.0), Some(3.0)];
-        let walking_config = detect_cycling_and_get_config(&walking_speeds);
+        let walking_config = detect_cycling_and_get_config(&walking_speeds, None);
         assert!(
             walking_config.is_none(),
             "Low speeds should not be detected as cycling"
@@ -803,10 +928,65 @@ mod tests {
 
         // Mixed but predominantly fast speeds should be detected as cycling
         let mixed_speeds = vec![Some(10.0), Some(25.0), Some(8.0), Some(30.0)];
-        let mixed_config = detect_cycling_and_get_config(&mixed_speeds);
+        let mixed_config = detect_cycling_and_get_config(&mixed_speeds, None);
         assert!(
             mixed_config.is_some(),
             "Predominantly fast speeds should be detected as cycling"
         );
     }
+
+    #[test]
+    fn test_filter_pace_by_window_removes_local_outlier() {
+        let start: DateTime<Utc> = "2025-06-01T07:00:00Z".parse().unwrap();
+        let times: Vec<Option<DateTime<Utc>>> = (0..40)
+            .map(|i| Some(start + chrono::Duration::seconds(i * 2)))
+            .collect();
+        let mut paces: Vec<Option<f64>> = (0..40)
+            .map(|i| Some(5.0 + if i % 2 == 0 { 0.1 } else { -0.1 }))
+            .collect();
+        paces[20] = Some(9.0); // traffic-light stop
+        paces[5] = None;
+
+        let filtered = filter_pace_by_window(&paces, &times, 30);
+        assert_eq!(filtered[20], None);
+        assert_eq!(filtered[5], None);
+        let kept = filtered.iter().filter(|p| p.is_some()).count();
+        assert_eq!(kept, 38, "only the spike should be removed");
+    }
+
+    #[test]
+    fn test_filter_pace_by_window_keeps_data_without_usable_times() {
+        let paces = vec![Some(5.0), Some(20.0), Some(5.1)];
+        assert_eq!(
+            filter_pace_by_window(&paces, &[None, None, None], 30),
+            paces
+        );
+        assert_eq!(filter_pace_by_window(&paces, &[], 30), paces);
+    }
+
+    #[test]
+    fn test_ebike_detection_uses_hr_variance() {
+        let speeds = vec![Some(32.0), Some(35.0), Some(38.0), Some(34.0), Some(36.0)];
+        let ebike = ebike_config();
+
+        // Steady HR alongside sustained 25-50 km/h: e-bike preset
+        let steady_hr = vec![Some(110), Some(112), Some(111), Some(113), Some(110)];
+        let config = detect_cycling_and_get_config(&speeds, Some(&steady_hr)).unwrap();
+        assert_eq!(config.min_pace, ebike.min_pace);
+        assert_eq!(config.max_pace, ebike.max_pace);
+
+        // Without HR the speed pattern alone is not enough
+        let config = detect_cycling_and_get_config(&speeds, None).unwrap();
+        assert_ne!(config.max_pace, ebike.max_pace);
+
+        // Widely varying HR points at a human-powered ride: regular cycling preset
+        let varying_hr = vec![Some(120), Some(175), Some(140), Some(185), Some(130)];
+        let config = detect_cycling_and_get_config(&speeds, Some(&varying_hr)).unwrap();
+        assert_ne!(config.max_pace, ebike.max_pace);
+
+        assert_eq!(
+            get_pace_filter_config(&[TrackClassification::EBike]).max_pace,
+            ebike.max_pace
+        );
+    }
 }
@@ -3,6 +3,7 @@
 // TODO: maybe switch to https://github.com/georust/geo?tab=readme-ov-file
 
 use crate::track_utils::geometry::haversine_distance;
+use crate::track_utils::zoom_adaptation::tolerance_for_zoom;
 
 /// Simplify a track using Douglas-Peucker algorithm
 /// Returns simplified track with fewer points while preserving shape
@@ -99,20 +100,6 @@ fn bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
     y.atan2(x)
 }
 
-/// Get adaptive simplification tolerance based on zoom level
-/// Higher zoom = lower tolerance (more detail)
-/// Lower zoom = higher tolerance (less detail)
-pub fn get_tolerance_for_zoom(zoom: f64) -> f64 {
-    match zoom as u8 {
-        0..=5 => 1000.0, // 1km tolerance for world view
-        6..=8 => 500.0,  // 500m for country/region view
-        9..=11 => 100.0, // 100m for city view
-        12..=14 => 50.0, // 50m for detailed view
-        15..=17 => 10.0, // 10m for street level
-        _ => 5.0,        // 5m for maximum detail
-    }
-}
-
 /// Determine adaptive tolerance scaling factor based on original point count.
 /// Small tracks are left untouched to preserve fidelity.
 /// Buckets:
@@ -138,7 +125,7 @@ fn adaptive_tolerance_scale(point_count: usize) -> Option<f64> {
 
 /// Adaptive wrapper: given raw points & zoom, decide whether and how strongly to simplify.
 pub fn simplify_track_for_zoom(points: &[(f64, f64)], zoom: f64) -> Vec<(f64, f64)> {
-    let base_tolerance = get_tolerance_for_zoom(zoom);
+    let base_tolerance = tolerance_for_zoom(zoom);
     let point_count = points.len();
 
     // Always bypass for very small tracks
@@ -193,9 +180,24 @@ pub fn simplify_track_for_zoom(points: &[(f64, f64)], zoom: f64) -> Vec<(f64, f6
         }
     }
 
+    ensure_endpoints(points, &mut simplified);
     simplified
 }
 
+/// Start/end markers are drawn from the simplified geometry, so its first and last points
+/// must be the original ones whatever strategy produced it.
+fn ensure_endpoints(original: &[(f64, f64)], simplified: &mut Vec<(f64, f64)>) {
+    let (Some(&first), Some(&last)) = (original.first(), original.last()) else {
+        return;
+    };
+    if simplified.first() != Some(&first) {
+        simplified.insert(0, first);
+    }
+    if simplified.last() != Some(&last) {
+        simplified.push(last);
+    }
+}
+
 /// Simplify profile data (elevation, heart rate, temperature) arrays
 /// by taking every nth element to match simplified track points
 pub fn simplify_profile_data(
@@ -206,6 +208,9 @@ pub fn simplify_profile_data(
     if original_data.is_empty() || simplified_track_length == 0 {
         return Vec::new();
     }
+    if simplified_track_length == 1 {
+        return vec![original_data[0]];
+    }
 
     if original_data.len() != original_track_length {
         // If data length doesn't match track length, just sample uniformly
@@ -275,6 +280,78 @@ fn sample_uniform(data: &[f64], target_length: usize) -> Vec<f64> {
     result
 }
 
+/// Downsample a numeric series with Largest-Triangle-Three-Buckets.
+///
+/// Unlike uniform sampling, LTTB keeps the visually significant points (peaks, dips), so a
+/// short elevation spike is not lost between sampled indices. The first and last values are
+/// always kept, and the global minimum and maximum are pinned into their buckets.
+pub fn lttb_simplify(data: &[f64], target_len: usize) -> Vec<f64> {
+    if target_len >= data.len() {
+        return data.to_vec();
+    }
+    if target_len == 0 {
+        return Vec::new();
+    }
+    if target_len < 3 {
+        return [data[0], data[data.len() - 1]][..target_len].to_vec();
+    }
+
+    let n = data.len();
+    let buckets = target_len - 2;
+    let bucket_size = (n - 2) as f64 / buckets as f64;
+    let bucket_start = |b: usize| ((b as f64 * bucket_size) as usize + 1).min(n - 1);
+
+    let mut selected = Vec::with_capacity(target_len);
+    selected.push(0);
+    let mut prev = 0usize;
+
+    for b in 0..buckets {
+        let (start, end) = (
+            bucket_start(b),
+            bucket_start(b + 1).max(bucket_start(b) + 1),
+        );
+
+        // Average of the next bucket (or the last point) acts as the third triangle vertex
+        let (next_start, next_end) = if b + 1 < buckets {
+            (end, bucket_start(b + 2).max(end + 1).min(n))
+        } else {
+            (n - 1, n)
+        };
+        let next_len = (next_end - next_start) as f64;
+        let avg_x = (next_start..next_end).sum::<usize>() as f64 / next_len;
+        let avg_y = data[next_start..next_end].iter().sum::<f64>() / next_len;
+
+        let (px, py) = (prev as f64, data[prev]);
+        let mut best = start;
+        let mut best_area = -1.0;
+        for (i, &y) in data.iter().enumerate().take(end.min(n - 1)).skip(start) {
+            let area = ((px - avg_x) * (y - py) - (px - i as f64) * (avg_y - py)).abs();
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+        selected.push(best);
+        prev = best;
+    }
+    selected.push(n - 1);
+
+    // Pin the global extremes so chart min/max labels stay exact
+    let bucket_of = |idx: usize| (1..=buckets).rev().find(|&b| bucket_start(b - 1) <= idx);
+    let argmax = (0..n).max_by(|&a, &b| data[a].total_cmp(&data[b]));
+    let argmin = (0..n).min_by(|&a, &b| data[a].total_cmp(&data[b]));
+    for extreme in [argmin, argmax].into_iter().flatten() {
+        if extreme == 0 || extreme == n - 1 || selected.contains(&extreme) {
+            continue;
+        }
+        if let Some(b) = bucket_of(extreme) {
+            selected[b] = extreme;
+        }
+    }
+
+    selected.into_iter().map(|i| data[i]).collect()
+}
+
 /// Uniformly sample point geometry to desired target length preserving endpoints.
 fn sample_uniform_points(points: &[(f64, f64)], target_len: usize) -> Vec<(f64, f64)> {
     if target_len == 0 {
@@ -322,16 +399,26 @@ pub fn get_simplification_stats(
 
 /// Adaptive simplification for profile arrays (elevation, hr, temp, time).
 /// Keeps arrays untouched for small tracks and samples proportionally otherwise.
+/// Arrays that were already shortened (e.g. by earlier sampling) no longer line up with
+/// the original track, so they are always resampled from their actual length.
 pub fn simplify_profile_array_adaptive(
     json_value: &serde_json::Value,
     original_track_length: usize,
     simplified_track_length: usize,
 ) -> Option<serde_json::Value> {
+    let actual_len = json_value.as_array().map(|a| a.len()).unwrap_or(0);
+    // Nothing to reduce; sampling cannot add points
+    if actual_len <= simplified_track_length {
+        return json_value.clone().into();
+    }
     // If no geometry simplification happened (lengths equal) OR small track: return original
-    if original_track_length <= 1000 || original_track_length == simplified_track_length {
+    let aligned = actual_len == original_track_length;
+    if aligned
+        && (original_track_length <= 1000 || original_track_length == simplified_track_length)
+    {
         return json_value.clone().into();
     }
-    simplify_json_array(json_value, original_track_length, simplified_track_length)
+    simplify_json_array(json_value, actual_len, simplified_track_length)
 }
 
 #[cfg(test)]
@@ -339,6 +426,33 @@ mod tests {
     use super::*;
     use assert_approx_eq::assert_approx_eq;
 
+    #[test]
+    fn test_lttb_keeps_spike_and_extremes() {
+        let mut data: Vec<f64> = (0..5000)
+            .map(|i| 200.0 + (i as f64 / 300.0).sin() * 20.0)
+            .collect();
+        data[2501] = 260.0; // 50m spike that uniform sampling would skip
+        data[3777] = 150.0; // isolated dip
+
+        let simplified = lttb_simplify(&data, 500);
+        assert_eq!(simplified.len(), 500);
+        assert_eq!(simplified[0], data[0]);
+        assert_eq!(simplified[499], data[4999]);
+
+        let max = simplified.iter().cloned().fold(f64::MIN, f64::max);
+        let min = simplified.iter().cloned().fold(f64::MAX, f64::min);
+        assert_eq!(max, 260.0);
+        assert_eq!(min, 150.0);
+    }
+
+    #[test]
+    fn test_lttb_short_input_untouched() {
+        let data = vec![1.0, 5.0, 2.0];
+        assert_eq!(lttb_simplify(&data, 10), data);
+        assert_eq!(lttb_simplify(&data, 2), vec![1.0, 2.0]);
+        assert!(lttb_simplify(&data, 0).is_empty());
+    }
+
     #[test]
     fn test_simplify_straight_line() {
         let points = vec![(55.0, 37.0), (55.01, 37.01), (55.02, 37.02), (55.03, 37.03)];
@@ -371,11 +485,18 @@ mod tests {
     }
 
     #[test]
-    fn test_tolerance_for_zoom() {
-        assert_eq!(get_tolerance_for_zoom(3.0), 1000.0);
-        assert_eq!(get_tolerance_for_zoom(10.0), 100.0);
-        assert_eq!(get_tolerance_for_zoom(15.0), 10.0);
-        assert_eq!(get_tolerance_for_zoom(20.0), 5.0);
+    fn test_adaptive_uses_smooth_zoom_tolerance() {
+        // ~13m zigzag: kept by the ~5m (scaled) tolerance at zoom 14, which a 50m step would flatten
+        let points: Vec<(f64, f64)> = (0..1500)
+            .map(|i| (55.0 + i as f64 * 0.0001, 37.0 + (i % 2) as f64 * 0.0002))
+            .collect();
+        let simplified = simplify_track_for_zoom(&points, 14.0);
+        assert!(simplified.len() > points.len() / 2);
+
+        // Fractional zooms between integer levels no longer snap to a bucket
+        let just_below = simplify_track_for_zoom(&points, 11.99);
+        let at_level = simplify_track_for_zoom(&points, 12.0);
+        assert_eq!(just_below.len(), at_level.len());
     }
 
     #[test]
@@ -437,6 +558,24 @@ mod tests {
         assert!(simplified.len() > points.len() / 3);
     }
 
+    #[test]
+    fn test_adaptive_keeps_endpoints_of_flat_track() {
+        // Nearly flat line whose first and last steps are collinear with their neighbours
+        let points: Vec<(f64, f64)> = (0..8000)
+            .map(|i| (55.0 + i as f64 * 0.00001, 37.0 + (i % 2) as f64 * 1e-9))
+            .collect();
+        let simplified = simplify_track_for_zoom(&points, 8.0);
+        assert!(simplified.len() < points.len());
+        assert_eq!(simplified.first(), points.first());
+        assert_eq!(simplified.last(), points.last());
+
+        let mut trimmed = points[1..points.len() - 1].to_vec();
+        ensure_endpoints(&points, &mut trimmed);
+        assert_eq!(trimmed.len(), points.len());
+        assert_eq!(trimmed.first(), points.first());
+        assert_eq!(trimmed.last(), points.last());
+    }
+
     #[test]
     fn test_adaptive_aggressive_huge_track() {
         let points: Vec<(f64, f64)> = (0..120000)
@@ -464,6 +603,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_adaptive_profile_shorter_than_track_is_resampled() {
+        let elevation: Vec<f64> = (0..500).map(|i| i as f64).collect();
+        let simplified =
+            simplify_profile_array_adaptive(&serde_json::json!(elevation), 1000, 200).unwrap();
+        let simplified: Vec<f64> = simplified
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_f64().unwrap())
+            .collect();
+
+        assert_eq!(simplified.len(), 200);
+        assert_eq!(simplified[0], 0.0);
+        assert_eq!(simplified[199], 499.0);
+        // Proportional: the middle of the output comes from the middle of the input
+        assert!((simplified[100] - 250.0).abs() < 5.0);
+
+        // Already at or below the target length: returned as is
+        let short = serde_json::json!([1.0, 2.0, 3.0]);
+        assert_eq!(
+            simplify_profile_array_adaptive(&short, 5000, 200),
+            Some(short.clone())
+        );
+    }
+
     #[test]
     fn test_adaptive_profile_moderate_track_reduced() {
         let points: Vec<(f64, f64)> = (0..7000)
@@ -1,6 +1,44 @@
+use crate::track_utils::elevation::ElevationSource;
 use crate::track_utils::geometry::haversine_distance;
 use std::env;
-use tracing::info;
+use tracing::{info, warn};
+
+/// Why slopes could not be calculated for a track
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SlopeCalculationError {
+    #[error("insufficient points for slope calculation: {0}")]
+    InsufficientPoints(usize),
+    #[error("no elevation profile")]
+    EmptyElevationProfile,
+    #[error("{points} points but {elevations} elevation values")]
+    MismatchedLengths { points: usize, elevations: usize },
+    #[error("insufficient valid elevations: {0}")]
+    InsufficientValidElevations(usize),
+    #[error("gaps in elevation data: {valid} of {points} points have an elevation")]
+    ElevationGaps { points: usize, valid: usize },
+    #[error("no slope segments after smoothing")]
+    NoSlopeSegments,
+}
+
+impl SlopeCalculationError {
+    /// The track simply lacks usable elevation data, which is common and not worth a warning
+    pub fn is_missing_data(&self) -> bool {
+        !matches!(
+            self,
+            SlopeCalculationError::MismatchedLengths { .. }
+                | SlopeCalculationError::NoSlopeSegments
+        )
+    }
+
+    /// Log at info when the track simply lacks elevation data, warn otherwise
+    pub fn log(&self, track_name: &str) {
+        if self.is_missing_data() {
+            info!(track = track_name, reason = %self, "slope calculation skipped");
+        } else {
+            warn!(track = track_name, reason = %self, "slope calculation failed");
+        }
+    }
+}
 
 /// Configuration parameters for slope calculation
 #[derive(Debug, Clone)]
@@ -44,6 +82,23 @@ impl SlopeConfig {
     }
 }
 
+/// Elevation smoothing half-window (meters) for elevation from the given source.
+/// GPS altitude is noisier than barometric altitude and gets a wider window; DEM data
+/// from enrichment (or an unknown source) uses `SLOPE_ELEVATION_SMOOTHING_WINDOW`.
+pub fn smoothing_window_for_source(source: Option<ElevationSource>) -> Option<f64> {
+    let (key, default) = match source? {
+        ElevationSource::Gps => ("SLOPE_GPS_SMOOTHING_WINDOW", 50.0),
+        ElevationSource::Barometric => ("SLOPE_BARO_SMOOTHING_WINDOW", 25.0),
+        ElevationSource::Enriched => return None,
+    };
+    Some(
+        env::var(key)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default),
+    )
+}
+
 /// Result of slope calculation containing all slope-related data
 #[derive(Debug, Clone, Default)]
 pub struct SlopeMetrics {
@@ -65,7 +120,7 @@ pub struct SlopeMetrics {
 /// * `track_name` - Name of the track for logging purposes
 ///
 /// # Returns
-/// * `SlopeMetrics` containing all calculated slope data
+/// * `SlopeMetrics` containing all calculated slope data, or the reason none could be calculated
 ///
 /// # Examples
 /// ```
@@ -73,82 +128,66 @@ pub struct SlopeMetrics {
 ///
 /// let points = vec![(55.7558, 37.6176), (55.7559, 37.6177)];
 /// let elevations = vec![Some(100.0), Some(110.0)];
-/// let result = calculate_slope_metrics(&points, &elevations, "Test Track");
+/// let result = calculate_slope_metrics(&points, &elevations, "Test Track").unwrap();
+/// assert!(result.slope_max.is_some());
 /// ```
 pub fn calculate_slope_metrics(
     points: &[(f64, f64)],
     elevation_profile: &[Option<f64>],
     track_name: &str,
-) -> SlopeMetrics {
+) -> Result<SlopeMetrics, SlopeCalculationError> {
+    calculate_slope_metrics_with_window(points, elevation_profile, track_name, None)
+}
+
+/// Same as [`calculate_slope_metrics`], but allows overriding the elevation smoothing
+/// half-window (in meters) for this call. `None` falls back to `SlopeConfig::from_env()`.
+pub fn calculate_slope_metrics_with_window(
+    points: &[(f64, f64)],
+    elevation_profile: &[Option<f64>],
+    track_name: &str,
+    smoothing_window_m: Option<f64>,
+) -> Result<SlopeMetrics, SlopeCalculationError> {
     // Validate input data
     if points.len() < 2 {
-        info!(
-            "Track '{}' has insufficient points for slope calculation: {} points",
-            track_name,
-            points.len()
-        );
-        return Default::default();
+        return Err(SlopeCalculationError::InsufficientPoints(points.len()));
     }
 
     if elevation_profile.is_empty() {
-        info!(
-            "Track '{}' has no elevation profile for slope calculation",
-            track_name
-        );
-        return Default::default();
+        return Err(SlopeCalculationError::EmptyElevationProfile);
     }
 
     if points.len() != elevation_profile.len() {
-        info!(
-            "Track '{}' has mismatched points ({}) and elevation profile ({}) lengths",
-            track_name,
-            points.len(),
-            elevation_profile.len()
-        );
-        return Default::default();
+        return Err(SlopeCalculationError::MismatchedLengths {
+            points: points.len(),
+            elevations: elevation_profile.len(),
+        });
     }
 
     // Extract valid elevations
     let elevations: Vec<f64> = elevation_profile.iter().filter_map(|&e| e).collect();
 
     if elevations.len() < 2 {
-        info!(
-            "Track '{}' has insufficient elevation data for slope calculation: {} valid elevations",
-            track_name,
-            elevations.len()
-        );
-        return Default::default();
+        return Err(SlopeCalculationError::InsufficientValidElevations(
+            elevations.len(),
+        ));
     }
 
-    // Ensure we have enough valid elevations matching the points
+    // For now, skip slope calculation if there are gaps in elevation data
+    // In the future, we could interpolate missing values
     if elevations.len() != points.len() {
-        info!(
-            "Track '{}' has gaps in elevation data: {} points but only {} valid elevations",
-            track_name,
-            points.len(),
-            elevations.len()
-        );
-        // For now, skip slope calculation if there are gaps in elevation data
-        // In the future, we could interpolate missing values
-        return Default::default();
+        return Err(SlopeCalculationError::ElevationGaps {
+            points: points.len(),
+            valid: elevations.len(),
+        });
     }
 
-    // Calculate distances between consecutive points
+    // Calculate distances between consecutive points; one per pair, so they line up
+    // with the elevations checked above
     let distances: Vec<f64> = points
         .windows(2)
         .map(|pair| haversine_distance(pair[0], pair[1]))
         .collect();
 
-    if distances.len() != elevations.len() - 1 {
-        info!(
-            "Track '{}' has inconsistent distance/elevation data: {} distances, {} elevations",
-            track_name,
-            distances.len(),
-            elevations.len()
-        );
-        return Default::default();
-    }
-
     // Calculate cumulative distances
     let mut cumulative_distances = vec![0.0];
     let mut total_distance = 0.0;
@@ -159,12 +198,11 @@ pub fn calculate_slope_metrics(
 
     // Step 1: Apply distance-based elevation smoothing
     let config = SlopeConfig::from_env();
-    let smoothed_elevations = smooth_elevation_by_distance(
-        points,
-        &elevations,
-        &cumulative_distances,
-        config.elevation_smoothing_window,
-    );
+    let smoothing_window = smoothing_window_m
+        .filter(|w| w.is_finite() && *w > 0.0)
+        .unwrap_or(config.elevation_smoothing_window);
+    let smoothed_elevations =
+        smooth_elevation_by_distance(points, &elevations, &cumulative_distances, smoothing_window);
 
     // Step 2: Calculate slopes using distance-based windowing
     let slopes = calculate_slope_by_distance_window(
@@ -174,11 +212,7 @@ pub fn calculate_slope_metrics(
     );
 
     if slopes.is_empty() {
-        info!(
-            "Track '{}' has no valid slope segments after calculation",
-            track_name
-        );
-        return Default::default();
+        return Err(SlopeCalculationError::NoSlopeSegments);
     }
 
     // Calculate basic statistics
@@ -314,13 +348,13 @@ pub fn calculate_slope_metrics(
         slope_avg.unwrap_or(0.0)
     );
 
-    SlopeMetrics {
+    Ok(SlopeMetrics {
         slope_min: Some(slope_min),
         slope_max: Some(slope_max),
         slope_avg,
         slope_histogram: Some(serde_json::Value::Array(histogram)),
         slope_segments: Some(serde_json::Value::Array(segments)),
-    }
+    })
 }
 
 /// Check if a track has sufficient data for slope calculation
@@ -353,16 +387,32 @@ pub fn can_calculate_slopes(points: &[(f64, f64)], elevation_profile: &[Option<f
 /// * `track_name` - Name of the track for logging
 ///
 /// # Returns
-/// * `SlopeMetrics` with calculated slope data
+/// * `SlopeMetrics` with calculated slope data, or the reason none could be calculated
 pub fn recalculate_slope_metrics(
     coordinates: &[(f64, f64)],
     elevation_profile: &[f64],
     track_name: &str,
-) -> SlopeMetrics {
+) -> Result<SlopeMetrics, SlopeCalculationError> {
+    recalculate_slope_metrics_with_window(coordinates, elevation_profile, track_name, None)
+}
+
+/// Same as [`recalculate_slope_metrics`], with an optional per-call elevation smoothing
+/// half-window in meters (useful for noisy barometric data)
+pub fn recalculate_slope_metrics_with_window(
+    coordinates: &[(f64, f64)],
+    elevation_profile: &[f64],
+    track_name: &str,
+    smoothing_window_m: Option<f64>,
+) -> Result<SlopeMetrics, SlopeCalculationError> {
     // Convert elevation profile to Option<f64> format for consistency
     let elevation_options: Vec<Option<f64>> = elevation_profile.iter().map(|&e| Some(e)).collect();
 
-    calculate_slope_metrics(coordinates, &elevation_options, track_name)
+    calculate_slope_metrics_with_window(
+        coordinates,
+        &elevation_options,
+        track_name,
+        smoothing_window_m,
+    )
 }
 
 /// Calculate slope using distance-based windowing (gpx.studio style)
@@ -538,7 +588,7 @@ mod tests {
         let points = vec![(55.7558, 37.6176), (55.7559, 37.6177), (55.7560, 37.6178)];
         let elevations = vec![Some(100.0), Some(110.0), Some(105.0)];
 
-        let result = calculate_slope_metrics(&points, &elevations, "Test Track");
+        let result = calculate_slope_metrics(&points, &elevations, "Test Track").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -554,9 +604,10 @@ mod tests {
 
         let result = calculate_slope_metrics(&points, &elevations, "Test Track");
 
-        assert!(result.slope_min.is_none());
-        assert!(result.slope_max.is_none());
-        assert!(result.slope_avg.is_none());
+        assert_eq!(
+            result.unwrap_err(),
+            SlopeCalculationError::InsufficientPoints(1)
+        );
     }
 
     #[test]
@@ -566,9 +617,10 @@ mod tests {
 
         let result = calculate_slope_metrics(&points, &elevations, "Test Track");
 
-        assert!(result.slope_min.is_none());
-        assert!(result.slope_max.is_none());
-        assert!(result.slope_avg.is_none());
+        assert_eq!(
+            result.unwrap_err(),
+            SlopeCalculationError::EmptyElevationProfile
+        );
     }
 
     #[test]
@@ -578,9 +630,15 @@ mod tests {
 
         let result = calculate_slope_metrics(&points, &elevations, "Test Track");
 
-        assert!(result.slope_min.is_none());
-        assert!(result.slope_max.is_none());
-        assert!(result.slope_avg.is_none());
+        let err = result.unwrap_err();
+        assert_eq!(
+            err,
+            SlopeCalculationError::MismatchedLengths {
+                points: 2,
+                elevations: 1
+            }
+        );
+        assert!(!err.is_missing_data());
     }
 
     #[test]
@@ -590,10 +648,16 @@ mod tests {
 
         let result = calculate_slope_metrics(&points, &elevations, "Test Track");
 
-        // Should return empty result for now (could be improved with interpolation)
-        assert!(result.slope_min.is_none());
-        assert!(result.slope_max.is_none());
-        assert!(result.slope_avg.is_none());
+        // Rejected for now (could be improved with interpolation)
+        let err = result.unwrap_err();
+        assert_eq!(
+            err,
+            SlopeCalculationError::ElevationGaps {
+                points: 3,
+                valid: 2
+            }
+        );
+        assert!(err.is_missing_data());
     }
 
     #[test]
@@ -625,13 +689,42 @@ mod tests {
         let coordinates = vec![(55.7558, 37.6176), (55.7559, 37.6177)];
         let elevation_profile = vec![100.0, 110.0];
 
-        let result = recalculate_slope_metrics(&coordinates, &elevation_profile, "Test Track");
+        let result =
+            recalculate_slope_metrics(&coordinates, &elevation_profile, "Test Track").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
         assert!(result.slope_avg.is_some());
     }
 
+    #[test]
+    fn test_recalculate_slope_metrics_with_custom_window() {
+        // Noisy climb: wider smoothing should flatten the slope spread
+        let coordinates: Vec<(f64, f64)> =
+            (0..40).map(|i| (55.0 + i as f64 * 0.0001, 37.0)).collect();
+        let elevation_profile: Vec<f64> = (0..40)
+            .map(|i| 100.0 + i as f64 + if i % 2 == 0 { 4.0 } else { -4.0 })
+            .collect();
+
+        let narrow = recalculate_slope_metrics_with_window(
+            &coordinates,
+            &elevation_profile,
+            "Narrow",
+            Some(5.0),
+        )
+        .unwrap();
+        let wide = recalculate_slope_metrics_with_window(
+            &coordinates,
+            &elevation_profile,
+            "Wide",
+            Some(200.0),
+        )
+        .unwrap();
+
+        let spread = |m: &SlopeMetrics| m.slope_max.unwrap() - m.slope_min.unwrap();
+        assert!(spread(&wide) <= spread(&narrow));
+    }
+
     #[test]
     fn test_slope_metrics_default() {
         let result = SlopeMetrics::default();
@@ -652,7 +745,7 @@ mod tests {
         ];
         let elevations = vec![Some(100.0), Some(110.0), Some(120.0)]; // 10m elevation gain per segment
 
-        let result = calculate_slope_metrics(&points, &elevations, "Linear Climb Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Linear Climb Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -676,7 +769,7 @@ mod tests {
         let points = vec![(55.7558, 37.6176), (55.7559, 37.6177), (55.7560, 37.6178)];
         let elevations = vec![Some(100.0), Some(100.0), Some(100.0)]; // Flat
 
-        let result = calculate_slope_metrics(&points, &elevations, "Flat Track Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Flat Track Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -705,7 +798,7 @@ mod tests {
             Some(130.0), // -10% slope
         ];
 
-        let result = calculate_slope_metrics(&points, &elevations, "Histogram Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Histogram Test").unwrap();
 
         assert!(result.slope_histogram.is_some());
 
@@ -736,7 +829,7 @@ mod tests {
             Some(130.0),
         ];
 
-        let result = calculate_slope_metrics(&points, &elevations, "Segments Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Segments Test").unwrap();
 
         assert!(result.slope_segments.is_some());
 
@@ -763,13 +856,11 @@ mod tests {
         ];
         let elevations = vec![Some(0.0), Some(200.0), Some(400.0)]; // 200m gain per 1.4km = ~14%
 
-        let result = calculate_slope_metrics(&points, &elevations, "Extreme Slopes Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Extreme Slopes Test")
+            .unwrap_or_default();
 
         // Should handle slopes gracefully
-        if result.slope_min.is_some() && result.slope_max.is_some() {
-            let slope_max = result.slope_max.unwrap();
-            let slope_min = result.slope_min.unwrap();
-
+        if let (Some(slope_min), Some(slope_max)) = (result.slope_min, result.slope_max) {
             // Should produce reasonable results, even if not extreme
             assert!(slope_max >= slope_min); // Basic sanity check
             assert!(slope_max.abs() <= 200.0); // Should be within expanded bounds for new algorithm
@@ -786,12 +877,10 @@ mod tests {
         ];
         let elevations = vec![Some(400.0), Some(200.0), Some(0.0)]; // Downhill: 200m loss per 1.4km = ~-14%
 
-        let result = calculate_slope_metrics(&points, &elevations, "Downhill Test");
-
-        if result.slope_min.is_some() && result.slope_max.is_some() {
-            let slope_max = result.slope_max.unwrap();
-            let slope_min = result.slope_min.unwrap();
+        let result =
+            calculate_slope_metrics(&points, &elevations, "Downhill Test").unwrap_or_default();
 
+        if let (Some(slope_min), Some(slope_max)) = (result.slope_min, result.slope_max) {
             // Should produce reasonable results for downhill
             assert!(slope_max >= slope_min); // Basic sanity check
             assert!(slope_max.abs() <= 200.0); // Within expanded bounds for new algorithm
@@ -816,12 +905,11 @@ mod tests {
 
         let result = calculate_slope_metrics(&points, &elevations, "Mixed Data Test");
 
-        // Should handle missing elevation data gracefully
-        // May have limited results due to gaps
-        if result.slope_min.is_some() {
-            assert!(result.slope_max.is_some());
-            assert!(result.slope_avg.is_some());
-        }
+        // Gaps are reported rather than producing partial results
+        assert!(matches!(
+            result,
+            Err(SlopeCalculationError::ElevationGaps { .. })
+        ));
     }
 
     #[test]
@@ -829,7 +917,8 @@ mod tests {
         let coordinates = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)];
         let elevation_data = vec![100.0, 120.0, 110.0];
 
-        let result = recalculate_slope_metrics(&coordinates, &elevation_data, "Array Test");
+        let result =
+            recalculate_slope_metrics(&coordinates, &elevation_data, "Array Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -844,7 +933,7 @@ mod tests {
         let points = vec![(55.7558, 37.6176), (55.7559, 37.6177)];
         let elevations = vec![Some(100.0), Some(110.0)]; // 10m rise
 
-        let result = calculate_slope_metrics(&points, &elevations, "Precision Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Precision Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -867,7 +956,8 @@ mod tests {
         // Include anomalous elevation jump
         let elevations = vec![Some(100.0), Some(200.0), Some(105.0), Some(110.0)]; // 100m jump is anomaly
 
-        let result = calculate_slope_metrics(&points, &elevations, "Anomaly Test");
+        let result =
+            calculate_slope_metrics(&points, &elevations, "Anomaly Test").unwrap_or_default();
 
         // Should handle anomalies gracefully - now allows steeper real slopes
         if let Some(slope_max) = result.slope_max {
@@ -892,7 +982,7 @@ mod tests {
         let result = calculate_slope_metrics(&points, &elevations, "Short Segment Test");
 
         // Should filter out the short segment and not produce extreme slopes
-        if let Some(slope_max) = result.slope_max {
+        if let Some(slope_max) = result.ok().and_then(|r| r.slope_max) {
             assert!(slope_max < 100.0); // Should not have extreme slope from short segment
         }
     }
@@ -907,26 +997,24 @@ mod tests {
         // Steep but realistic mountain trail: 50m elevation gain over 111m = ~45% slope
         let elevations = vec![Some(1000.0), Some(1050.0), Some(1100.0)];
 
-        let result = calculate_slope_metrics(&points, &elevations, "Steep Trail Test");
+        let result =
+            calculate_slope_metrics(&points, &elevations, "Steep Trail Test").unwrap_or_default();
 
         println!("Steep trail result: {:?}", result);
 
         // The test should handle cases where segments might be filtered out
         // Let's make a more robust test
-        if result.slope_min.is_some() {
-            assert!(result.slope_max.is_some());
-            assert!(result.slope_avg.is_some());
+        if let Some(slope_min) = result.slope_min {
+            let slope_max = result.slope_max.expect("slope_max set with slope_min");
+            let slope_avg = result.slope_avg.expect("slope_avg set with slope_min");
 
             // Should preserve steep but realistic slopes
-            let slope_max = result.slope_max.unwrap();
             assert!(slope_max > 10.0); // Should be steeper than moderate slopes
             assert!(slope_max < 100.0); // But reasonable for mountain terrain
 
             println!(
                 "Steep trail slopes: min={:.1}%, max={:.1}%, avg={:.1}%",
-                result.slope_min.unwrap(),
-                result.slope_max.unwrap(),
-                result.slope_avg.unwrap()
+                slope_min, slope_max, slope_avg
             );
         } else {
             println!("No slopes calculated - likely due to short segments being filtered");
@@ -944,7 +1032,7 @@ mod tests {
         // Realistic mountain trail with steep elevation gain (within 80m threshold)
         let elevations = vec![Some(1000.0), Some(1070.0), Some(1140.0)]; // 70m gain per ~127m = ~55% slope
 
-        let result = calculate_slope_metrics(&points, &elevations, "Mountain Trail Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Mountain Trail Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -977,16 +1065,15 @@ mod tests {
             Some(220.0),
         ]; // Realistic mountain profile
 
-        let result = calculate_slope_metrics(&points, &elevations, "Contextual Filter Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Contextual Filter Test")
+            .unwrap_or_default();
 
         // Should handle slopes gracefully but results might be different due to windowing
-        if result.slope_max.is_some() {
-            let _slope_max = result.slope_max.unwrap();
-
+        if let Some(slope_max) = result.slope_max {
             println!(
                 "Contextual filter slopes: min={:.1}%, max={:.1}%, avg={:.1}%",
                 result.slope_min.unwrap_or(0.0),
-                result.slope_max.unwrap(),
+                slope_max,
                 result.slope_avg.unwrap_or(0.0)
             );
         }
@@ -1080,7 +1167,8 @@ mod tests {
             Some(110.0), // Very noisy data
         ];
 
-        let result = calculate_slope_metrics(&points, &elevations, "Noisy Test");
+        let result =
+            calculate_slope_metrics(&points, &elevations, "Noisy Test").unwrap_or_default();
 
         // New algorithm should produce more reasonable results
         if let Some(slope_max) = result.slope_max {
@@ -1112,7 +1200,7 @@ mod tests {
             Some(1700.0),
         ]; // 100m gain over ~220m horizontal distance = ~45% slope, which is realistic for Alps
 
-        let result = calculate_slope_metrics(&points, &elevations, "Alpine Trail Test");
+        let result = calculate_slope_metrics(&points, &elevations, "Alpine Trail Test").unwrap();
 
         assert!(result.slope_min.is_some());
         assert!(result.slope_max.is_some());
@@ -1123,4 +1211,26 @@ mod tests {
         assert!(slope_avg > 5.0); // Should be steep
         assert!(slope_avg < 100.0); // But reasonable for mountain trails
     }
+
+    #[test]
+    fn test_smoothing_window_for_source() {
+        temp_env::with_vars(
+            [
+                ("SLOPE_GPS_SMOOTHING_WINDOW", None::<&str>),
+                ("SLOPE_BARO_SMOOTHING_WINDOW", Some("30")),
+            ],
+            || {
+                let gps = smoothing_window_for_source(Some(ElevationSource::Gps)).unwrap();
+                let baro = smoothing_window_for_source(Some(ElevationSource::Barometric)).unwrap();
+                assert_eq!(gps, 50.0);
+                assert_eq!(baro, 30.0);
+                assert!(gps > baro);
+                assert_eq!(
+                    smoothing_window_for_source(Some(ElevationSource::Enriched)),
+                    None
+                );
+                assert_eq!(smoothing_window_for_source(None), None);
+            },
+        );
+    }
 }
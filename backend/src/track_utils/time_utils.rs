@@ -4,6 +4,11 @@
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use tracing::warn;
 
+use super::geometry::haversine_distance;
+
+/// Time gap (seconds) without samples after which the track is considered paused
+pub const PAUSE_GAP_THRESHOLD_SECS: i64 = 180;
+
 /// Try to parse GPX <time> string with multiple formats
 pub fn parse_gpx_time(s: &str) -> Option<DateTime<Utc>> {
     let formats = [
@@ -38,6 +43,32 @@ pub fn calculate_track_duration(time_points: &[Option<DateTime<Utc>>]) -> Option
     Some(duration.num_seconds() as i32)
 }
 
+/// Calculate distance (km) covered while moving, skipping hops across pause gaps.
+/// Hops between points with a missing timestamp still count towards the distance.
+/// Returns None when there is no usable time data.
+pub fn calculate_moving_distance(
+    coordinates: &[(f64, f64)],
+    time_data: &[Option<DateTime<Utc>>],
+    pause_threshold_secs: i64,
+) -> Option<f64> {
+    if coordinates.len() < 2
+        || coordinates.len() != time_data.len()
+        || time_data.iter().all(|t| t.is_none())
+    {
+        return None;
+    }
+    let mut distance_m = 0.0;
+    for i in 1..coordinates.len() {
+        if let (Some(t1), Some(t2)) = (time_data[i - 1], time_data[i])
+            && (t2 - t1).num_seconds() > pause_threshold_secs
+        {
+            continue;
+        }
+        distance_m += haversine_distance(coordinates[i - 1], coordinates[i]);
+    }
+    Some(distance_m / 1000.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +84,28 @@ mod tests {
         assert!(parse_gpx_time("not-a-date").is_none());
     }
 
+    #[test]
+    fn test_calculate_moving_distance_skips_pause_gaps() {
+        let t0 = chrono::Utc.with_ymd_and_hms(2023, 5, 22, 10, 0, 0).unwrap();
+        let coords = vec![(0.0, 0.0), (0.0, 0.01), (0.0, 0.02), (0.0, 0.03)];
+        let times = vec![
+            Some(t0),
+            Some(t0 + chrono::Duration::seconds(60)),
+            Some(t0 + chrono::Duration::seconds(600)), // 9 minute pause
+            Some(t0 + chrono::Duration::seconds(660)),
+        ];
+        let hop_km = haversine_distance(coords[0], coords[1]) / 1000.0;
+
+        let moving = calculate_moving_distance(&coords, &times, PAUSE_GAP_THRESHOLD_SECS).unwrap();
+        assert!((moving - 2.0 * hop_km).abs() < 1e-6);
+
+        let total = calculate_moving_distance(&coords, &times, 3600).unwrap();
+        assert!((total - 3.0 * hop_km).abs() < 1e-6);
+
+        assert!(calculate_moving_distance(&coords, &[None; 4], 180).is_none());
+        assert!(calculate_moving_distance(&coords, &times[..2], 180).is_none());
+    }
+
     #[test]
     fn test_calculate_track_duration() {
         let t1 = chrono::Utc.with_ymd_and_hms(2023, 5, 22, 10, 0, 0).unwrap();
@@ -1,18 +1,30 @@
 // Zoom-based track adaptation utilities for optimal performance
 use crate::models::TrackMode;
 
+/// Tolerance used at and below `TOLERANCE_MIN_ZOOM` (world/country view)
+const TOLERANCE_BASE_M: f64 = 100.0;
+/// Tolerance used at and above `TOLERANCE_MAX_ZOOM` (very detailed view)
+const TOLERANCE_FLOOR_M: f64 = 2.0;
+const TOLERANCE_MIN_ZOOM: f64 = 8.0;
+const TOLERANCE_MAX_ZOOM: f64 = 18.0;
+
 /// Calculate tolerance in meters based on zoom level for track simplification
 /// Lower zoom = broader view = higher tolerance (more simplification)
 /// Higher zoom = detailed view = lower tolerance (less simplification)
+///
+/// Decays exponentially from 100m at zoom 8 to 2m at zoom 18, which stays within a few
+/// meters of the former per-bucket values (~46m at 10, ~21m at 12, ~10m at 14, ~4m at 16)
+/// but has no jumps while the map animates between zoom levels.
 pub fn tolerance_for_zoom(zoom: f64) -> f64 {
-    match zoom as i32 {
-        z if z <= 8 => 100.0, // ~100m tolerance for world/country view
-        z if z <= 10 => 50.0, // ~50m for regional view
-        z if z <= 12 => 25.0, // ~25m for city view
-        z if z <= 14 => 10.0, // ~10m for neighborhood view
-        z if z <= 16 => 5.0,  // ~5m for street view
-        _ => 2.0,             // ~2m for very detailed view
-    }
+    let k = (TOLERANCE_BASE_M / TOLERANCE_FLOOR_M).ln() / (TOLERANCE_MAX_ZOOM - TOLERANCE_MIN_ZOOM);
+    let zoom = zoom.clamp(TOLERANCE_MIN_ZOOM, TOLERANCE_MAX_ZOOM);
+    TOLERANCE_BASE_M * (-k * (zoom - TOLERANCE_MIN_ZOOM)).exp()
+}
+
+/// Width of one 256px web-mercator tile pixel in degrees of longitude at the given zoom.
+/// Tracks whose bounding box is smaller than this render as a single dot.
+pub fn degrees_per_pixel(zoom: f64) -> f64 {
+    360.0 / (256.0 * 2f64.powf(zoom))
 }
 
 /// Determine appropriate simplification based on track mode and zoom
@@ -113,19 +125,44 @@ impl SimplificationParams {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use assert_approx_eq::assert_approx_eq;
 
     #[test]
     fn test_tolerance_for_zoom() {
-        assert_eq!(tolerance_for_zoom(5.0), 100.0);
-        assert_eq!(tolerance_for_zoom(10.0), 50.0);
-        assert_eq!(tolerance_for_zoom(15.0), 5.0);
-        assert_eq!(tolerance_for_zoom(20.0), 2.0);
+        assert_approx_eq!(tolerance_for_zoom(5.0), 100.0, 1e-9);
+        assert_approx_eq!(tolerance_for_zoom(10.0), 45.73, 0.01);
+        assert_approx_eq!(tolerance_for_zoom(15.0), 6.47, 0.01);
+        assert_approx_eq!(tolerance_for_zoom(18.0), 2.0, 1e-9);
+        assert_approx_eq!(tolerance_for_zoom(20.0), 2.0, 1e-9);
+    }
+
+    #[test]
+    fn test_tolerance_is_continuous_across_zoom_levels() {
+        // Fractional zooms during map animation change the tolerance only slightly
+        let mut zoom = TOLERANCE_MIN_ZOOM;
+        while zoom < TOLERANCE_MAX_ZOOM {
+            let step = tolerance_for_zoom(zoom) - tolerance_for_zoom(zoom + 0.01);
+            assert!(step > 0.0 && step < 0.5, "jump of {step} at zoom {zoom}");
+            zoom += 0.01;
+        }
+
+        // Differences between adjacent integer zoom levels shrink as the zoom increases
+        let diffs: Vec<f64> = (8..18)
+            .map(|z| tolerance_for_zoom(z as f64) - tolerance_for_zoom(z as f64 + 1.0))
+            .collect();
+        assert!(diffs.windows(2).all(|w| w[0] > w[1]), "{diffs:?}");
+    }
+
+    #[test]
+    fn test_degrees_per_pixel() {
+        assert_approx_eq!(degrees_per_pixel(0.0), 360.0 / 256.0);
+        assert_approx_eq!(degrees_per_pixel(10.0) * 2.0, degrees_per_pixel(9.0));
     }
 
     #[test]
     fn test_overview_mode_params() {
         let params = get_simplification_params(TrackMode::Overview, Some(12.0), 5000);
-        assert_eq!(params.tolerance_meters, 25.0);
+        assert_eq!(params.tolerance_meters, tolerance_for_zoom(12.0));
         assert!(params.max_points >= 1000);
     }
 
@@ -139,7 +176,7 @@ mod tests {
     #[test]
     fn test_huge_track_gets_simplified() {
         let params = get_simplification_params(TrackMode::Overview, Some(12.0), 25000);
-        assert!(params.tolerance_meters > 25.0); // More aggressive for huge tracks
+        assert!(params.tolerance_meters > tolerance_for_zoom(12.0)); // More aggressive for huge tracks
     }
 
     #[test]
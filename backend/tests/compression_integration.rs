@@ -0,0 +1,65 @@
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    http::{
+        Request,
+        header::{ACCEPT_ENCODING, CONTENT_ENCODING},
+    },
+    routing::get,
+};
+use flate2::read::GzDecoder;
+use std::io::Read;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/tracks",
+            get(|| async {
+                let coordinates: Vec<[f64; 2]> =
+                    (0..2000).map(|i| [37.6 + i as f64 * 1e-5, 55.7]).collect();
+                Json(serde_json::json!({
+                    "type": "FeatureCollection",
+                    "features": [{ "type": "Feature", "geometry": { "type": "LineString", "coordinates": coordinates } }]
+                }))
+            }),
+        )
+        .layer(backend::compression::layer())
+}
+
+fn gzip_request() -> Request<Body> {
+    Request::builder()
+        .uri("/tracks")
+        .header(ACCEPT_ENCODING, "gzip")
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn compresses_json_when_client_accepts_gzip() {
+    let response = temp_env::async_with_vars([("DISABLE_COMPRESSION", None::<&str>)], async {
+        app().oneshot(gzip_request()).await.unwrap()
+    })
+    .await;
+
+    assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+
+    let compressed = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let mut json = String::new();
+    GzDecoder::new(&compressed[..])
+        .read_to_string(&mut json)
+        .expect("valid gzip body");
+    let value: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    assert_eq!(value["type"], "FeatureCollection");
+    assert!(compressed.len() < json.len());
+}
+
+#[tokio::test]
+async fn compression_can_be_disabled() {
+    let response = temp_env::async_with_vars([("DISABLE_COMPRESSION", Some("true"))], async {
+        app().oneshot(gzip_request()).await.unwrap()
+    })
+    .await;
+
+    assert!(response.headers().get(CONTENT_ENCODING).is_none());
+}
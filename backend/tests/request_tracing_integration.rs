@@ -0,0 +1,39 @@
+use axum::{Router, body::Body, http::Request, routing::get};
+use backend::request_tracing::TRACE_ID_HEADER;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn app() -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .layer(backend::request_tracing::layer())
+}
+
+#[tokio::test]
+async fn generates_trace_id_for_each_request() {
+    let request = || {
+        Request::builder()
+            .uri("/health")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let first = app().oneshot(request()).await.unwrap();
+    let second = app().oneshot(request()).await.unwrap();
+
+    let first_id = first.headers()[&TRACE_ID_HEADER].to_str().unwrap();
+    let second_id = second.headers()[&TRACE_ID_HEADER].to_str().unwrap();
+    assert!(Uuid::parse_str(first_id).is_ok());
+    assert_ne!(first_id, second_id);
+}
+
+#[tokio::test]
+async fn keeps_trace_id_sent_by_client() {
+    let request = Request::builder()
+        .uri("/missing")
+        .header(&TRACE_ID_HEADER, "upstream-trace-42")
+        .body(Body::empty())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+
+    assert_eq!(response.headers()[&TRACE_ID_HEADER], "upstream-trace-42");
+}
@@ -68,7 +68,7 @@ describe('TrackSearch', () => {
 
     fetch.mockResolvedValueOnce({
       ok: true,
-      json: async () => mockResponse
+      json: async () => ({ tracks: mockResponse, total_count: mockResponse.length })
     })
 
     const wrapper = mount(TrackSearch, {
@@ -91,7 +91,7 @@ describe('TrackSearch', () => {
   it('shows no results message when no tracks found', async () => {
     fetch.mockResolvedValueOnce({
       ok: true,
-      json: async () => []
+      json: async () => ({ tracks: [], total_count: 0 })
     })
 
     const wrapper = mount(TrackSearch, {
@@ -126,7 +126,7 @@ describe('TrackSearch', () => {
 
     fetch.mockResolvedValueOnce({
       ok: true,
-      json: async () => mockResponse
+      json: async () => ({ tracks: mockResponse, total_count: mockResponse.length })
     })
 
     const wrapper = mount(TrackSearch, {
@@ -155,7 +155,7 @@ describe('TrackSearch', () => {
 
     fetch.mockResolvedValue({
       ok: true,
-      json: async () => []
+      json: async () => ({ tracks: [], total_count: 0 })
     })
 
     const wrapper = mount(TrackSearch, {